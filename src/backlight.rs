@@ -0,0 +1,132 @@
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::command::Command;
+use crate::{Error, TransferObserver, ST7789V};
+
+/// LEDPWM output frequency (PWMFRSEL)
+///
+/// The frequency is selected by the 3 bit `CS` and `CLK` divider codes, the resulting
+/// frequencies are listed in the PWMFRSEL table of the datasheet. A frequency outside the
+/// audible range avoids coil whine of the backlight driver. The reset default is `CS = 1`,
+/// `CLK = 0`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PwmFrequency {
+    cs: u8,
+    clk: u8,
+}
+
+impl PwmFrequency {
+    /// Creates the frequency from the divider codes, returns `None` if a code is above 7.
+    pub fn from_codes(cs: u8, clk: u8) -> Option<Self> {
+        if cs > 7 || clk > 7 {
+            return None;
+        }
+
+        Some(PwmFrequency { cs, clk })
+    }
+
+    /// Returns the `CS` divider code.
+    pub fn cs(self) -> u8 {
+        self.cs
+    }
+
+    /// Returns the `CLK` divider code.
+    pub fn clk(self) -> u8 {
+        self.clk
+    }
+
+    /// Get as PWMFRSEL register value
+    pub fn value(self) -> u8 {
+        self.cs << 3 | self.clk
+    }
+}
+
+impl Default for PwmFrequency {
+    fn default() -> Self {
+        PwmFrequency { cs: 1, clk: 0 }
+    }
+}
+
+/// LEDPWM and LEDONR output control (CABCCTRL)
+///
+/// All options are off after reset. Display dimming is enabled together with the brightness
+/// control block by [`brightness_control`](struct.ST7789V.html#method.brightness_control).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CabcControl {
+    led_on_inverted: bool,
+    pwm_high_when_off: bool,
+    pwm_fixed: bool,
+    pwm_active_low: bool,
+}
+
+impl CabcControl {
+    /// Creates the reset default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inverts the LEDONR output (LEDONREV).
+    pub fn led_on_inverted(mut self, inverted: bool) -> Self {
+        self.led_on_inverted = inverted;
+        self
+    }
+
+    /// Drives LEDPWM high instead of low while the display is off (DPOFPWM).
+    pub fn pwm_high_when_off(mut self, high: bool) -> Self {
+        self.pwm_high_when_off = high;
+        self
+    }
+
+    /// Fixes the LEDPWM duty cycle, ignoring the display brightness (PWMFIX).
+    pub fn pwm_fixed(mut self, fixed: bool) -> Self {
+        self.pwm_fixed = fixed;
+        self
+    }
+
+    /// Inverts the LEDPWM polarity (PWMPOL).
+    pub fn pwm_active_low(mut self, active_low: bool) -> Self {
+        self.pwm_active_low = active_low;
+        self
+    }
+
+    /// Get as CABCCTRL register value
+    pub fn value(self) -> u8 {
+        let flag = |set: bool, bit: u8| if set { bit } else { 0 };
+        flag(self.led_on_inverted, 0b1000)
+            | flag(self.pwm_high_when_off, 0b0100)
+            | flag(self.pwm_fixed, 0b0010)
+            | flag(self.pwm_active_low, 0b0001)
+    }
+}
+
+/// Backlight PWM output
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Sets the frequency of the LEDPWM output driving the backlight.
+    pub fn set_backlight_pwm_frequency(
+        &mut self,
+        freq: PwmFrequency,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.command(Command::PWMFRSEL, Some(&[freq.value()]))?;
+
+        Ok(self)
+    }
+
+    /// Sets the polarities and behavior of the LEDPWM and LEDONR outputs.
+    pub fn set_cabc_control(
+        &mut self,
+        options: CabcControl,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.command(Command::CABCCTRL, Some(&[options.value()]))?;
+
+        Ok(self)
+    }
+}
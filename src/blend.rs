@@ -0,0 +1,90 @@
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::color;
+use crate::copy::rgb666_to_rgb565;
+use crate::{Error, PixelFormat, TransferObserver, ST7789V};
+
+/// Blends the RGB565 color `foreground` over `background` with the opacity `alpha`, from
+/// `0` for fully transparent to `255` for opaque.
+///
+/// Each channel is blended separately and rounded to the nearest value.
+///
+/// ```
+/// use st7789v::blend_rgb565;
+///
+/// assert_eq!(blend_rgb565(0x0000, 0xFFFF, 255), 0xFFFF);
+/// assert_eq!(blend_rgb565(0x0000, 0xFFFF, 0), 0x0000);
+/// ```
+pub fn blend_rgb565(background: u16, foreground: u16, alpha: u8) -> u16 {
+    color::blend(background, foreground, alpha)
+}
+
+/// Alpha blending on the frame memory
+///
+/// Blending reads the frame memory content back, see
+/// [`copy_area`](struct.ST7789V.html#method.copy_area) for the requirements on the SPI bus. Each
+/// pixel takes five bytes on the bus instead of two, plus the window setup for every row, so
+/// blending is meant for occasional effects rather than animations.
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError> + spi::Transfer<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Blends the RGB565 `color` with the opacity `alpha` over the pixel at `x`, `y`.
+    pub fn blend_pixel(
+        &mut self,
+        x: u16,
+        y: u16,
+        color: u16,
+        alpha: u8,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.blend_rect(x, y, 1, 1, color, alpha, &mut [0; 4])
+    }
+
+    /// Blends the RGB565 `color` with the opacity `alpha` over the `width` by `height` pixel
+    /// area at `x`, `y`.
+    ///
+    /// The area is processed row by row: each row is read into `scratch`, blended and written
+    /// back. `scratch` has to hold `1 + 3 * width` bytes, otherwise `Error::BufferTooSmall` is
+    /// returned.
+    #[allow(clippy::too_many_arguments)]
+    pub fn blend_rect(
+        &mut self,
+        x: u16,
+        y: u16,
+        width: u16,
+        height: u16,
+        color: u16,
+        alpha: u8,
+        scratch: &mut [u8],
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        if width == 0 || height == 0 {
+            return Ok(self);
+        }
+
+        let len = 1 + 3 * usize::from(width);
+        let row = scratch.get_mut(..len).ok_or(Error::BufferTooSmall)?;
+        self.check_pixel_format(PixelFormat::Rgb565)?;
+
+        let xe = x.saturating_add(width - 1);
+        for y in y..y.saturating_add(height) {
+            self.address_window(x, y, xe, y)?.read_memory(row)?;
+            let colors = rgb666_to_rgb565(row);
+            for pixel in colors.chunks_exact_mut(2) {
+                let background = u16::from_be_bytes([pixel[0], pixel[1]]);
+                pixel.copy_from_slice(&blend_rgb565(background, color, alpha).to_be_bytes());
+            }
+            self.address_window(x, y, xe, y)?
+                .start_memory_write()?
+                .write_data(colors)?
+                .end_transaction()?;
+        }
+
+        Ok(self)
+    }
+}
@@ -0,0 +1,206 @@
+use core::convert::TryFrom;
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::color;
+use crate::{Error, PixelFormat, TransferObserver, ST7789V};
+
+/// Blitting of 1 bit per pixel bitmaps like font glyphs, of parts of RGB565 images and of 8 bit
+/// grayscale images
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Draws the `w` by `h` pixel 1 bit per pixel `bitmap` at `x`, `y`, set bits in the RGB565
+    /// color `fg` and cleared bits in `bg`.
+    ///
+    /// Each row of the bitmap starts at a new byte, the leftmost pixel is the MSB. The window
+    /// is set once and all colors are streamed with a single memory write. Parts of the bitmap
+    /// outside the display are clipped.
+    ///
+    /// Returns `Error::BufferTooSmall` if `bitmap` holds less than `h` rows of `(w + 7) / 8`
+    /// bytes.
+    #[allow(clippy::too_many_arguments)]
+    pub fn blit_mono(
+        &mut self,
+        x: u16,
+        y: u16,
+        w: u16,
+        h: u16,
+        bitmap: &[u8],
+        fg: u16,
+        bg: u16,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        let stride = (usize::from(w) + 7) / 8;
+        if bitmap.len() < stride * usize::from(h) {
+            return Err(Error::BufferTooSmall);
+        }
+
+        let (width, height) = self.size();
+        if w == 0 || h == 0 || x >= width || y >= height {
+            return Ok(self);
+        }
+
+        self.check_pixel_format(PixelFormat::Rgb565)?;
+        let visible_w = w.min(width - x);
+        let visible_h = h.min(height - y);
+        self.address_window(x, y, x + visible_w - 1, y + visible_h - 1)?;
+        self.start_memory_write()?;
+
+        let colors = bitmap
+            .chunks(stride)
+            .take(usize::from(visible_h))
+            .flat_map(|row| {
+                (0..usize::from(visible_w)).map(move |col| {
+                    if row[col / 8] & (0x80 >> (col % 8)) != 0 {
+                        fg
+                    } else {
+                        bg
+                    }
+                })
+            });
+        self.write_colors(colors)
+    }
+
+    /// Draws the `w` by `h` pixel part at `src_x`, `src_y` of the RGB565 image `src` at
+    /// `dest_x`, `dest_y`, e.g. a sprite out of a texture atlas.
+    ///
+    /// `src` holds the image row by row, each row `src_stride` pixels long. Only the selected
+    /// part of each row is streamed, the window is set once and written with a single memory
+    /// write. Parts outside the display are clipped like in [`blit_mono`](#method.blit_mono).
+    ///
+    /// ```ignore
+    /// // the third 32x32 cell of the first row of a 240 pixel wide atlas
+    /// display.blit_sub(100, 50, &ATLAS, 240, 64, 0, 32, 32)?;
+    /// ```
+    ///
+    /// Returns `Error::InvalidParameter` if the part does not fit into a row of `src_stride`
+    /// pixels and `Error::BufferTooSmall` if `src` ends before its last row.
+    #[allow(clippy::too_many_arguments)]
+    pub fn blit_sub(
+        &mut self,
+        dest_x: u16,
+        dest_y: u16,
+        src: &[u16],
+        src_stride: u16,
+        src_x: u16,
+        src_y: u16,
+        w: u16,
+        h: u16,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        if u32::from(src_x) + u32::from(w) > u32::from(src_stride) {
+            return Err(Error::InvalidParameter);
+        }
+        if w == 0 || h == 0 {
+            return Ok(self);
+        }
+        let stride = usize::from(src_stride);
+        let end = (usize::from(src_y) + usize::from(h) - 1) * stride + usize::from(src_x);
+        if end + usize::from(w) > src.len() {
+            return Err(Error::BufferTooSmall);
+        }
+
+        let (width, height) = self.size();
+        if dest_x >= width || dest_y >= height {
+            return Ok(self);
+        }
+
+        self.check_pixel_format(PixelFormat::Rgb565)?;
+        let visible_w = w.min(width - dest_x);
+        let visible_h = h.min(height - dest_y);
+        self.address_window(
+            dest_x,
+            dest_y,
+            dest_x + visible_w - 1,
+            dest_y + visible_h - 1,
+        )?;
+        self.start_memory_write()?;
+
+        let colors = src[usize::from(src_y) * stride..]
+            .chunks(stride)
+            .take(usize::from(visible_h))
+            .flat_map(|row| {
+                let start = usize::from(src_x);
+                row[start..start + usize::from(visible_w)].iter().copied()
+            });
+        self.write_colors(colors)
+    }
+
+    /// Draws the `w` by `h` pixel 8 bit grayscale image `data` at `x`, `y`, e.g. a camera
+    /// frame.
+    ///
+    /// Each byte is converted to an RGB565 gray while the pixels are streamed, see
+    /// [`color::gray`](color/fn.gray.html), no RGB565 copy of the image is needed.
+    ///
+    /// Returns `Error::PixelCountMismatch` if `data` does not hold exactly `w * h` pixels.
+    pub fn write_gray8(
+        &mut self,
+        x: u16,
+        y: u16,
+        w: u16,
+        h: u16,
+        data: &[u8],
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.write_gray8_mapped(x, y, w, h, data, color::gray)
+    }
+
+    /// Draws an 8 bit grayscale image like [`write_gray8`](#method.write_gray8) and converts
+    /// each byte with the lookup table `lut`, e.g. to apply a gamma curve or a false color
+    /// palette.
+    ///
+    /// ```ignore
+    /// // gamma 2 curve, darkens the mid tones
+    /// let mut lut = [0; 256];
+    /// for (luma, color) in lut.iter_mut().enumerate() {
+    ///     *color = color::gray((luma * luma / 255) as u8);
+    /// }
+    /// display.write_gray8_lut(0, 0, 160, 120, &frame, &lut)?;
+    /// ```
+    pub fn write_gray8_lut(
+        &mut self,
+        x: u16,
+        y: u16,
+        w: u16,
+        h: u16,
+        data: &[u8],
+        lut: &[u16; 256],
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.write_gray8_mapped(x, y, w, h, data, |luma| lut[usize::from(luma)])
+    }
+
+    /// Streams the grayscale image `data` converted by `convert`.
+    fn write_gray8_mapped<F>(
+        &mut self,
+        x: u16,
+        y: u16,
+        w: u16,
+        h: u16,
+        data: &[u8],
+        convert: F,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>>
+    where
+        F: Fn(u8) -> u16,
+    {
+        let expected = u32::from(w) * u32::from(h);
+        if data.len() != expected as usize {
+            return Err(Error::PixelCountMismatch {
+                expected,
+                written: u32::try_from(data.len()).unwrap_or(u32::MAX),
+            });
+        }
+        if expected == 0 {
+            return Ok(self);
+        }
+
+        self.check_pixel_format(PixelFormat::Rgb565)?;
+        self.address_window(x, y, x.saturating_add(w - 1), y.saturating_add(h - 1))?;
+        self.start_memory_write()?
+            .write_colors(data.iter().map(|&luma| convert(luma)))
+    }
+}
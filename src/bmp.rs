@@ -0,0 +1,141 @@
+use core::convert::TryFrom;
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{Error, PixelFormat, TransferObserver, ST7789V};
+
+/// BI_BITFIELDS compression, the pixel format is given by color masks
+const BI_BITFIELDS: u32 = 3;
+/// Red, green and blue masks of RGB565
+const RGB565_MASKS: [u32; 3] = [0xF800, 0x07E0, 0x001F];
+
+/// Reasons a BMP image is rejected by [`draw_bmp565`]
+///
+/// [`draw_bmp565`]: struct.ST7789V.html#method.draw_bmp565
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BmpError {
+    /// The data does not start with a BMP file and info header
+    InvalidHeader,
+    /// The image does not use 16 bits per pixel
+    UnsupportedBitDepth(u16),
+    /// The image is compressed or its 16 bit pixels are not RGB565, e.g. RGB555
+    UnsupportedCompression(u32),
+    /// The pixel data ends before the last row
+    Truncated,
+}
+
+/// Header fields needed to draw an image
+struct BmpInfo {
+    /// Offset of the pixel data
+    offset: usize,
+    width: u16,
+    height: u16,
+    /// The first row in the data is the top row
+    top_down: bool,
+}
+
+impl BmpInfo {
+    /// Parses the file header and BITMAPINFOHEADER of a 16 bit RGB565 image.
+    fn parse(data: &[u8]) -> Result<Self, BmpError> {
+        if data.len() < 66 || &data[..2] != b"BM" {
+            return Err(BmpError::InvalidHeader);
+        }
+
+        let u16_at = |i: usize| u16::from_le_bytes([data[i], data[i + 1]]);
+        let u32_at =
+            |i: usize| u32::from_le_bytes([data[i], data[i + 1], data[i + 2], data[i + 3]]);
+
+        let header_size = u32_at(14);
+        if header_size < 40 {
+            return Err(BmpError::InvalidHeader);
+        }
+        let bpp = u16_at(28);
+        if bpp != 16 {
+            return Err(BmpError::UnsupportedBitDepth(bpp));
+        }
+        // the masks follow the 40 byte BITMAPINFOHEADER, later headers include them there
+        let compression = u32_at(30);
+        let masks = [u32_at(54), u32_at(58), u32_at(62)];
+        if compression != BI_BITFIELDS || masks != RGB565_MASKS {
+            return Err(BmpError::UnsupportedCompression(compression));
+        }
+
+        // a negative height marks top-down images
+        let width = u32_at(18) as i32;
+        let height = u32_at(22) as i32;
+        let size = |value: i32| u16::try_from(value.unsigned_abs()).ok();
+        match (size(width), size(height)) {
+            (Some(w), Some(h)) if width >= 0 => Ok(BmpInfo {
+                offset: u32_at(10) as usize,
+                width: w,
+                height: h,
+                top_down: height < 0,
+            }),
+            _ => Err(BmpError::InvalidHeader),
+        }
+    }
+
+    /// Returns the length of a row including the padding to a multiple of 4 bytes.
+    fn stride(&self) -> usize {
+        (2 * usize::from(self.width) + 3) & !3
+    }
+}
+
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Draws an uncompressed 16 bit RGB565 BMP image with its top left corner at `x`, `y`.
+    ///
+    /// `data` is the whole BMP file, e.g. embedded with `include_bytes!`. Only images with a
+    /// BITMAPINFOHEADER or later header, 16 bits per pixel and RGB565 bit fields are supported,
+    /// other images are rejected with `Error::InvalidBmp`. Bottom-up and top-down images are
+    /// streamed row by row into a single window, the image has to fit onto the display.
+    pub fn draw_bmp565(
+        &mut self,
+        x: u16,
+        y: u16,
+        data: &[u8],
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        let info = BmpInfo::parse(data).map_err(Error::InvalidBmp)?;
+        if info.width == 0 || info.height == 0 {
+            return Ok(self);
+        }
+
+        let stride = info.stride();
+        let rows = data
+            .get(info.offset..)
+            .and_then(|pixels| pixels.get(..stride * usize::from(info.height)))
+            .ok_or(Error::InvalidBmp(BmpError::Truncated))?;
+
+        self.check_pixel_format(PixelFormat::Rgb565)?;
+        self.address_window(
+            x,
+            y,
+            x.saturating_add(info.width - 1),
+            y.saturating_add(info.height - 1),
+        )?;
+        self.start_memory_write()?;
+
+        let row_len = 2 * usize::from(info.width);
+        let colors = (0..usize::from(info.height))
+            .map(|row| {
+                let row = if info.top_down {
+                    row
+                } else {
+                    usize::from(info.height) - 1 - row
+                };
+                &rows[row * stride..row * stride + row_len]
+            })
+            .flat_map(|row| row.chunks_exact(2))
+            .map(|color| u16::from_le_bytes([color[0], color[1]]));
+
+        self.write_colors(colors)
+    }
+}
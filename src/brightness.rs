@@ -0,0 +1,116 @@
+use embedded_hal::blocking::delay::DelayMs;
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::command::Command;
+use crate::{Error, TransferObserver, ST7789V};
+
+/// Minimum time between two brightness steps of a fade in milliseconds.
+const FADE_STEP_MS: u16 = 10;
+
+/// WRCTRLD bits: brightness control block, display dimming and backlight on.
+const CTRLD_BRIGHTNESS_ON: u8 = 0b0010_1100;
+
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Enables or disables the brightness control block.
+    ///
+    /// The value written by [`brightness`](#method.brightness) only has an effect while the
+    /// brightness control block is enabled.
+    pub fn brightness_control(
+        &mut self,
+        enable: bool,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        let ctrl = if enable { CTRLD_BRIGHTNESS_ON } else { 0 };
+        self.command(Command::WRCTRLD, Some(&[ctrl]))?;
+
+        Ok(self)
+    }
+
+    /// Sets the display brightness. `0x00` is the lowest and `0xFF` the highest brightness.
+    pub fn brightness(
+        &mut self,
+        value: u8,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.command(Command::WRDISBV, Some(&[value]))?;
+        self.state.brightness = Some(value);
+
+        Ok(self)
+    }
+
+    /// Fades the brightness from `from` to `to` over roughly `duration_ms` milliseconds.
+    ///
+    /// The brightness is stepped at most every 10 ms and never more often than there are
+    /// brightness levels between `from` and `to`. Durations too short for a single step jump
+    /// straight to `to`.
+    pub fn fade_brightness<DELAY>(
+        &mut self,
+        from: u8,
+        to: u8,
+        duration_ms: u16,
+        delay: &mut DELAY,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>>
+    where
+        DELAY: DelayMs<u16>,
+    {
+        if from == to {
+            return self.brightness(to);
+        }
+
+        let distance = u16::from(from.max(to) - from.min(to));
+        let from = i32::from(from);
+        let to = i32::from(to);
+        let steps = (duration_ms / FADE_STEP_MS).min(distance).max(1);
+        let step_ms = duration_ms / steps;
+
+        for step in 1..=i32::from(steps) {
+            let value = from + (to - from) * step / i32::from(steps);
+            self.brightness(value.clamp(0, 255) as u8)?;
+            delay.delay_ms(step_ms);
+        }
+
+        Ok(self)
+    }
+
+    /// Leaves sleep mode, turns the display on and fades the brightness from zero up to `to`.
+    ///
+    /// The brightness is set to zero before the display is turned on, so the frame memory
+    /// content does not flash up at the previous brightness.
+    pub fn fade_in<DELAY>(
+        &mut self,
+        to: u8,
+        duration_ms: u16,
+        delay: &mut DELAY,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>>
+    where
+        DELAY: DelayMs<u16>,
+    {
+        self.brightness(0)?
+            .sleep_out(delay)?
+            .display_on()?
+            .fade_brightness(0, to, duration_ms, delay)
+    }
+
+    /// Fades the brightness from `from` down to zero, turns the display off and enters sleep
+    /// mode.
+    pub fn fade_out<DELAY>(
+        &mut self,
+        from: u8,
+        duration_ms: u16,
+        delay: &mut DELAY,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>>
+    where
+        DELAY: DelayMs<u16>,
+    {
+        self.fade_brightness(from, 0, duration_ms, delay)?
+            .display_off()?
+            .sleep_in(delay)
+    }
+}
@@ -0,0 +1,98 @@
+//! RGB565 color helpers
+//!
+//! The raw color APIs of the driver take RGB565 colors as `u16`: 5 bits red in the MSBs,
+//! 6 bits green and 5 bits blue. Conversions round to the nearest value, so the full range
+//! endpoints map onto each other.
+//!
+//! ```
+//! use st7789v::color::{self, RED};
+//!
+//! assert_eq!(color::rgb565(255, 0, 0), RED);
+//! assert_eq!(color::from_rgb888(0x00FF00), color::GREEN);
+//! assert_eq!(color::to_rgb888(color::WHITE), (255, 255, 255));
+//! ```
+
+/// Black
+pub const BLACK: u16 = 0x0000;
+/// White
+pub const WHITE: u16 = 0xFFFF;
+/// Red
+pub const RED: u16 = 0xF800;
+/// Green
+pub const GREEN: u16 = 0x07E0;
+/// Blue
+pub const BLUE: u16 = 0x001F;
+/// Yellow
+pub const YELLOW: u16 = 0xFFE0;
+/// Cyan
+pub const CYAN: u16 = 0x07FF;
+/// Magenta
+pub const MAGENTA: u16 = 0xF81F;
+/// 50 % gray
+pub const GRAY: u16 = 0x8410;
+/// Orange
+pub const ORANGE: u16 = 0xFD20;
+
+/// Scales an 8 bit channel value to `max`, rounding to the nearest value.
+const fn scale_down(value: u8, max: u32) -> u16 {
+    ((value as u32 * max + 127) / 255) as u16
+}
+
+/// Scales a channel value from `0..=max` to 8 bits, rounding to the nearest value.
+const fn scale_up(value: u16, max: u32) -> u8 {
+    ((value as u32 * 255 + max / 2) / max) as u8
+}
+
+/// Packs 8 bit red, green and blue values into an RGB565 color.
+pub const fn rgb565(r: u8, g: u8, b: u8) -> u16 {
+    scale_down(r, 0x1F) << 11 | scale_down(g, 0x3F) << 5 | scale_down(b, 0x1F)
+}
+
+/// Converts an 8 bit gray value to RGB565, scaling it to 5 bits red and blue and 6 bits green.
+pub const fn gray(luma: u8) -> u16 {
+    rgb565(luma, luma, luma)
+}
+
+/// Converts a `0xRRGGBB` color to RGB565, the upper 8 bits are ignored.
+pub const fn from_rgb888(color: u32) -> u16 {
+    rgb565((color >> 16) as u8, (color >> 8) as u8, color as u8)
+}
+
+/// Unpacks an RGB565 color into 8 bit red, green and blue values.
+pub const fn to_rgb888(color: u16) -> (u8, u8, u8) {
+    (
+        scale_up(color >> 11, 0x1F),
+        scale_up(color >> 5 & 0x3F, 0x3F),
+        scale_up(color & 0x1F, 0x1F),
+    )
+}
+
+/// Returns the 5 bit red channel of an RGB565 color.
+pub const fn red(color: u16) -> u8 {
+    (color >> 11) as u8
+}
+
+/// Returns the 6 bit green channel of an RGB565 color.
+pub const fn green(color: u16) -> u8 {
+    (color >> 5 & 0x3F) as u8
+}
+
+/// Returns the 5 bit blue channel of an RGB565 color.
+pub const fn blue(color: u16) -> u8 {
+    (color & 0x1F) as u8
+}
+
+/// Blends `a` into `b` with `alpha` from `0` for `a` to `255` for `b`.
+///
+/// Each channel is blended separately and rounded to the nearest value. This is the same as
+/// [`blend_rgb565`](../fn.blend_rgb565.html) with `a` as background.
+pub const fn blend(a: u16, b: u16, alpha: u8) -> u16 {
+    const fn channel(a: u16, b: u16, alpha: u8, shift: u32, mask: u16) -> u16 {
+        let a = (a >> shift & mask) as u32;
+        let b = (b >> shift & mask) as u32;
+        let alpha = alpha as u32;
+        (((b * alpha + a * (255 - alpha) + 127) / 255) as u16) << shift
+    }
+
+    channel(a, b, alpha, 11, 0x1F) | channel(a, b, alpha, 5, 0x3F) | channel(a, b, alpha, 0, 0x1F)
+}
@@ -1,5 +1,6 @@
 /// LCD Command
-#[allow(unused, non_camel_case_types)]
+#[allow(unused, non_camel_case_types, clippy::upper_case_acronyms)]
+#[derive(Clone, Copy)]
 #[repr(u8)]
 pub enum Command {
     /// No operation
@@ -92,12 +93,63 @@ pub enum Command {
     RDCABCMB = 0x5F,
     /// Read Automatic brightness control self-diagnostic result
     RDABCSDR = 0x68,
+    /// RAM control
+    RAMCTRL = 0xB0,
+    /// RGB interface control
+    RGBCTRL = 0xB1,
+    /// Frame rate control in partial and idle mode
+    FRCTRL1 = 0xB3,
+    /// Partial mode control
+    PARCTRL = 0xB5,
+    /// Gate control
+    GCTRL = 0xB7,
+    /// Digital gamma enable
+    DGMEN = 0xBA,
+    /// VCOM setting
+    VCOMS = 0xBB,
+    /// VDV and VRH command enable
+    VDVVRHEN = 0xC2,
+    /// VRH set
+    VRHS = 0xC3,
+    /// VDV set
+    VDVS = 0xC4,
+    /// VCOM offset set
+    VCMOFSET = 0xC5,
+    /// CABC control
+    CABCCTRL = 0xC7,
+    /// PWM frequency selection
+    PWMFRSEL = 0xCC,
     /// Read ID1
     RDID1 = 0xDA,
     /// Read ID2
     RDID2 = 0xDB,
     /// Read ID3
     RDID3 = 0xDC,
+    /// Command 2 enable
+    CMD2EN = 0xDF,
+    /// Digital gamma look-up table for red
+    DGMLUTR = 0xE2,
+    /// Digital gamma look-up table for blue
+    DGMLUTB = 0xE3,
+    /// Gate control
+    GATECTRL = 0xE4,
+    /// Program mode control
+    PROMCTRL = 0xEC,
+    /// Program mode enable
+    PROMEN = 0xFA,
+    /// NVM address and data setting
+    NVMSET = 0xFC,
+    /// Program action
+    PROMACT = 0xFE,
+}
+
+/// Number of parameter bytes a command is sent with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParameterCount {
+    /// Exactly this many bytes
+    Exact(usize),
+    /// Any number of bytes, e.g. pixel data
+    Variable,
 }
 
 impl Command {
@@ -105,4 +157,71 @@ impl Command {
     pub fn value(self) -> u8 {
         self as u8
     }
+
+    /// Returns the number of parameter bytes the command is sent with.
+    ///
+    /// Read commands take no parameters, their response is not counted.
+    pub fn parameter_count(self) -> ParameterCount {
+        use ParameterCount::{Exact, Variable};
+
+        match self {
+            Command::RAMWR | Command::RAMWRC => Variable,
+            Command::CASET | Command::RASET | Command::PTLAR => Exact(4),
+            Command::VSCRDEF => Exact(6),
+            Command::VSCRSADD | Command::TESCAN => Exact(2),
+            Command::GAMSET
+            | Command::TEON
+            | Command::MADCTL
+            | Command::COLMOD
+            | Command::WRDISBV
+            | Command::WRCTRLD
+            | Command::WRCACE
+            | Command::WRCABCMB => Exact(1),
+            Command::RAMCTRL | Command::VDVVRHEN | Command::NVMSET | Command::PROMACT => Exact(2),
+            Command::RGBCTRL | Command::FRCTRL1 | Command::GATECTRL => Exact(3),
+            Command::PARCTRL
+            | Command::GCTRL
+            | Command::DGMEN
+            | Command::VCOMS
+            | Command::VRHS
+            | Command::VDVS
+            | Command::VCMOFSET
+            | Command::CABCCTRL
+            | Command::PWMFRSEL
+            | Command::PROMCTRL => Exact(1),
+            Command::CMD2EN | Command::PROMEN => Exact(4),
+            Command::DGMLUTR | Command::DGMLUTB => Exact(64),
+            _ => Exact(0),
+        }
+    }
+
+    /// Returns `true` if the command is sent with `len` parameter bytes, see
+    /// [`parameter_count`](#method.parameter_count).
+    pub fn accepts_parameters(self, len: usize) -> bool {
+        match self.parameter_count() {
+            ParameterCount::Exact(count) => count == len,
+            ParameterCount::Variable => true,
+        }
+    }
+
+    /// Panics in debug builds if the command is not sent with `len` parameter bytes. A wrong
+    /// count shifts every following parameter and is hard to spot on the display.
+    pub fn debug_check_parameters(self, len: usize) {
+        debug_assert!(
+            self.accepts_parameters(len),
+            "command {:#04x} sent with {} parameter bytes, expected {:?}",
+            self.value(),
+            len,
+            self.parameter_count()
+        );
+    }
+
+    /// Returns `true` for the system function commands of Command 2, which are only accepted
+    /// while Command 2 is enabled.
+    pub fn is_command2(self) -> bool {
+        match self {
+            Command::RDID1 | Command::RDID2 | Command::RDID3 | Command::CMD2EN => false,
+            cmd => cmd.value() >= 0xB0,
+        }
+    }
 }
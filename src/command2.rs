@@ -0,0 +1,41 @@
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::command::Command;
+use crate::{Error, TransferObserver, ST7789V};
+
+/// CMD2EN key preceding the enable parameter
+const CMD2EN_KEY: [u8; 3] = [0x5A, 0x69, 0x02];
+
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Enables or disables Command 2.
+    ///
+    /// The panel, voltage and interface settings of Command 2, e.g.
+    /// [`set_vcom`](#method.set_vcom) or [`set_gate_lines`](#method.set_gate_lines), are only
+    /// accepted by the controller while Command 2 is enabled, which it is after reset. While it
+    /// is disabled through this method they return `Error::Command2Locked` instead of being
+    /// silently ignored by the controller.
+    pub fn enable_command2(
+        &mut self,
+        enable: bool,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        let [k0, k1, k2] = CMD2EN_KEY;
+        self.command(Command::CMD2EN, Some(&[k0, k1, k2, u8::from(enable)]))?;
+        self.state.command2_locked = !enable;
+
+        Ok(self)
+    }
+
+    /// Returns `true` while Command 2 is enabled.
+    pub fn is_command2_enabled(&self) -> bool {
+        !self.state.command2_locked
+    }
+}
@@ -0,0 +1,226 @@
+use core::fmt;
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::font::{glyph, GLYPH_WIDTH};
+use crate::{Error, PageColumnOrder, PixelFormat, TransferObserver, ST7789V};
+
+/// Width of a character cell in pixels: a glyph and one column of spacing
+const CELL_WIDTH: u16 = 6;
+/// Height of a character cell in pixels: a glyph and one row of spacing
+const CELL_HEIGHT: u16 = 8;
+/// Frame memory rows
+const MEMORY_ROWS: u16 = 320;
+
+/// Text output on the display
+///
+/// Created by [`ST7789V::console`]. Prints text in 6x8 pixel cells using a built-in 5x7 font
+/// and implements `core::fmt::Write`, so `write!` and `writeln!` can be used:
+///
+/// ```ignore
+/// let mut console = display.console(0xFFFF, 0x0000)?;
+/// writeln!(console, "boot ok: {}", version).ok();
+/// ```
+///
+/// Lines wrap at the right edge of the display. Once the cursor moves past the last line the
+/// text is scrolled up using hardware scrolling, which expects the default memory access
+/// control. Characters missing in the font are shown as '?'. Driver errors are reported as
+/// `fmt::Error` by `write!`, use [`put_char`](#method.put_char) to get the driver error.
+///
+/// [`ST7789V::console`]: struct.ST7789V.html#method.console
+pub struct TextConsole<'a, SPI, CS, DC, RST, D = (), O = (), const BUF: usize = 128>
+where
+    SPI: spi::Write<u8>,
+    CS: OutputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    display: &'a mut ST7789V<SPI, CS, DC, RST, D, O, BUF>,
+    foreground: u16,
+    background: u16,
+    column: u16,
+    row: u16,
+    columns: u16,
+    rows: u16,
+}
+
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Clears the display and returns a text console printing in the RGB565 `foreground`
+    /// color on `background`.
+    ///
+    /// The vertical scroll area is set to the rows covered by text lines. The console scrolls
+    /// along the gate lines, so it returns `Error::InvalidParameter` while the memory access
+    /// control exchanges page and column order.
+    pub fn console(
+        &mut self,
+        foreground: u16,
+        background: u16,
+    ) -> Result<
+        TextConsole<'_, SPI, CS, DC, RST, D, O, BUF>,
+        Error<PinError, SpiError, CS::Error, RST::Error>,
+    > {
+        self.check_pixel_format(PixelFormat::Rgb565)?;
+        if self.madctl().get_page_column_order() == PageColumnOrder::ReverseMode {
+            return Err(Error::InvalidParameter);
+        }
+
+        let columns = self.panel.width / CELL_WIDTH;
+        let rows = self.panel.height / CELL_HEIGHT;
+        let tfa = self.panel.y_offset;
+        let vsa = rows * CELL_HEIGHT;
+        self.vertical_scroll_area(tfa, vsa, MEMORY_ROWS - tfa - vsa)?;
+
+        let mut console = TextConsole {
+            display: self,
+            foreground,
+            background,
+            column: 0,
+            row: 0,
+            columns,
+            rows,
+        };
+        console.clear()?;
+
+        Ok(console)
+    }
+}
+
+impl<'a, SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    TextConsole<'a, SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Fills the display with the background color and moves the cursor home.
+    pub fn clear(&mut self) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        let top = self.display.panel.y_offset;
+        self.display.vertical_scroll_start(top)?;
+        let height = self.display.panel.height;
+        let background = self.background;
+        self.display.fill_rows(top, height, background)?;
+        self.column = 0;
+        self.row = 0;
+
+        Ok(self)
+    }
+
+    /// Sets the RGB565 colors used for the following characters.
+    pub fn colors(&mut self, foreground: u16, background: u16) -> &mut Self {
+        self.foreground = foreground;
+        self.background = background;
+        self
+    }
+
+    /// Moves the cursor to `column` and `row`, counted in characters.
+    pub fn cursor(&mut self, column: u16, row: u16) -> &mut Self {
+        self.column = column.min(self.columns.saturating_sub(1));
+        self.row = row.min(self.rows.saturating_sub(1));
+        self
+    }
+
+    /// Returns the number of columns and rows in characters.
+    pub fn size(&self) -> (u16, u16) {
+        (self.columns, self.rows)
+    }
+
+    /// Releases the display.
+    pub fn release(self) -> &'a mut ST7789V<SPI, CS, DC, RST, D, O, BUF> {
+        self.display
+    }
+
+    /// Prints a character at the cursor and advances it.
+    ///
+    /// `'\n'` starts a new line, `'\r'` moves the cursor to the start of the line.
+    pub fn put_char(
+        &mut self,
+        c: char,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        match c {
+            '\n' => return self.new_line(),
+            '\r' => {
+                self.column = 0;
+                return Ok(self);
+            }
+            _ => {}
+        }
+
+        if self.column >= self.columns {
+            self.new_line()?;
+        }
+        self.draw_glyph(c)?;
+        self.column += 1;
+
+        Ok(self)
+    }
+
+    /// Moves the cursor to the start of the next line, scrolling up at the last line.
+    fn new_line(&mut self) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.column = 0;
+        if self.row + 1 < self.rows {
+            self.row += 1;
+            return Ok(self);
+        }
+
+        let background = self.background;
+        self.display.scroll_up(CELL_HEIGHT, background)?;
+
+        Ok(self)
+    }
+
+    /// Draws the glyph of `c` into the cell at the cursor with a single memory write.
+    fn draw_glyph(
+        &mut self,
+        c: char,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        let glyph = glyph(c);
+        let fg = self.foreground.to_be_bytes();
+        let bg = self.background.to_be_bytes();
+
+        let mut bytes = [0u8; (CELL_WIDTH * CELL_HEIGHT * 2) as usize];
+        for (i, pixel) in bytes.chunks_mut(2).enumerate() {
+            let x = i % usize::from(CELL_WIDTH);
+            let y = i / usize::from(CELL_WIDTH);
+            let set = x < GLYPH_WIDTH && glyph[x] & (1 << y) != 0;
+            pixel.copy_from_slice(if set { &fg } else { &bg });
+        }
+
+        let xs = self.display.panel.x_offset + self.column * CELL_WIDTH;
+        let rs = self.display.scrolled_row(self.row * CELL_HEIGHT);
+        self.display
+            .column_address(xs, xs + CELL_WIDTH - 1)?
+            .row_address(rs, rs + CELL_HEIGHT - 1)?
+            .mem_write(&bytes)?;
+
+        Ok(self)
+    }
+}
+
+impl<'a, SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize> fmt::Write
+    for TextConsole<'a, SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            self.put_char(c).map_err(|_| fmt::Error)?;
+        }
+
+        Ok(())
+    }
+}
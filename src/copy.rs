@@ -0,0 +1,116 @@
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::command::Command;
+use crate::{Error, PixelFormat, TransferObserver, ST7789V};
+
+/// Frame memory reads
+///
+/// Reading needs the SPI bus to receive data from the controller, i.e. a connected SDO line
+/// or a bidirectional SDA line handled by the bus implementation. The controller sends a dummy
+/// byte after RAMRD and then three bytes per pixel, the 6 bit red, green and blue values in
+/// the upper bits of each byte, independent of the configured color format.
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError> + spi::Transfer<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Copies the `(x, y, width, height)` area `src` to the top left corner `dst`.
+    ///
+    /// The area is copied row by row: each row is read into `scratch`, converted to RGB565
+    /// and written to its destination. Overlapping areas are copied in the order which reads
+    /// every row before it is overwritten.
+    ///
+    /// `scratch` has to hold `1 + 3 * width` bytes, otherwise `Error::BufferTooSmall` is
+    /// returned.
+    pub fn copy_area(
+        &mut self,
+        src: (u16, u16, u16, u16),
+        dst: (u16, u16),
+        scratch: &mut [u8],
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        let (x, y, width, height) = src;
+        if width == 0 || height == 0 {
+            return Ok(self);
+        }
+
+        let len = 1 + 3 * usize::from(width);
+        let row = scratch.get_mut(..len).ok_or(Error::BufferTooSmall)?;
+        self.check_pixel_format(PixelFormat::Rgb565)?;
+
+        for offset in 0..height {
+            let offset = if dst.1 > y {
+                height - 1 - offset
+            } else {
+                offset
+            };
+            self.copy_row(
+                (x, y.saturating_add(offset)),
+                (dst.0, dst.1.saturating_add(offset)),
+                width,
+                row,
+            )?;
+        }
+
+        Ok(self)
+    }
+
+    /// Copies `width` pixels from `src` to `dst` through `buffer`.
+    fn copy_row(
+        &mut self,
+        src: (u16, u16),
+        dst: (u16, u16),
+        width: u16,
+        buffer: &mut [u8],
+    ) -> Result<(), Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.address_window(src.0, src.1, src.0.saturating_add(width - 1), src.1)?
+            .read_memory(buffer)?;
+        let colors = rgb666_to_rgb565(buffer);
+        self.address_window(dst.0, dst.1, dst.0.saturating_add(width - 1), dst.1)?
+            .start_memory_write()?
+            .write_data(colors)?
+            .end_transaction()?;
+
+        Ok(())
+    }
+
+    /// Reads the current address window from the frame memory into `buffer`, including the
+    /// leading dummy byte.
+    pub(crate) fn read_memory(
+        &mut self,
+        buffer: &mut [u8],
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.read_command(Command::RAMRD, buffer)
+    }
+
+    /// Sends a read command and reads the response into `buffer`.
+    pub(crate) fn read_command(
+        &mut self,
+        cmd: Command,
+        buffer: &mut [u8],
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.open_command(cmd, &[])?.set_dc(true)?;
+        buffer.iter_mut().for_each(|byte| *byte = 0);
+        self.observer.on_data(buffer.len());
+        self.spi.transfer(buffer).map_err(Error::Spi)?;
+
+        self.end_transaction()
+    }
+}
+
+/// Converts a RAMRD result in place to big endian RGB565 and returns the converted part.
+pub(crate) fn rgb666_to_rgb565(buffer: &mut [u8]) -> &mut [u8] {
+    let pixels = (buffer.len() - 1) / 3;
+    for i in 0..pixels {
+        let rgb = &buffer[1 + 3 * i..4 + 3 * i];
+        let color =
+            u16::from(rgb[0] >> 3) << 11 | u16::from(rgb[1] >> 2) << 5 | u16::from(rgb[2] >> 3);
+        buffer[2 * i..2 * i + 2].copy_from_slice(&color.to_be_bytes());
+    }
+
+    &mut buffer[..2 * pixels]
+}
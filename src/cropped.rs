@@ -0,0 +1,259 @@
+#![deny(clippy::cast_sign_loss)]
+
+use core::convert::TryFrom;
+
+use embedded_graphics::drawable::Pixel;
+use embedded_graphics::geometry::Dimensions;
+use embedded_graphics::image::{Image, ImageDimensions, IntoPixelIter};
+use embedded_graphics::pixelcolor::raw::{RawData, RawU16};
+use embedded_graphics::pixelcolor::Rgb565;
+use embedded_graphics::prelude::{DrawTarget, Point, Size};
+use embedded_graphics::primitives::{Circle, Line, Rectangle, Triangle};
+use embedded_graphics::style::{PrimitiveStyle, Styled};
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{Error, TransferObserver, ST7789V};
+
+/// Draw target covering a rectangle of the display
+///
+/// Created by [`ST7789V::cropped`]. The top left corner of the rectangle is the origin of the
+/// target and its size is the size of the rectangle, so a widget can draw in its own
+/// coordinates. Everything outside the rectangle or the display is clipped. Filled rectangles
+/// and images take a single memory write of their visible part, other primitives are drawn in
+/// runs like on the display itself.
+///
+/// A cropped target can be cropped further, the rectangle is then given in its coordinates and
+/// clipped to it. If nothing of the rectangle is visible, drawing does nothing.
+///
+/// ```ignore
+/// let mut widget = display.cropped(Rectangle::new(Point::new(20, 40), Point::new(119, 79)));
+/// Rectangle::new(Point::zero(), Point::new(99, 39))
+///     .into_styled(PrimitiveStyle::with_fill(Rgb565::BLUE))
+///     .draw(&mut widget)?;
+/// Text::new("OK", Point::new(4, 4)).into_styled(style).draw(&mut widget.cropped(label))?;
+/// ```
+///
+/// [`ST7789V::cropped`]: struct.ST7789V.html#method.cropped
+pub struct CroppedDisplay<'a, SPI, CS, DC, RST, D = (), O = (), const BUF: usize = 128>
+where
+    SPI: spi::Write<u8>,
+    CS: OutputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    display: &'a mut ST7789V<SPI, CS, DC, RST, D, O, BUF>,
+    /// Display coordinates of the target origin
+    origin: Point,
+    /// Size of the target
+    size: Size,
+    /// Visible part in display coordinates, both corners inclusive, empty if the top left
+    /// corner is right of or below the bottom right corner
+    clip: Rectangle,
+}
+
+/// Returns the intersection of `a` and `b`, both corners inclusive.
+fn intersection(a: &Rectangle, b: &Rectangle) -> Rectangle {
+    Rectangle::new(
+        Point::new(
+            a.top_left.x.max(b.top_left.x),
+            a.top_left.y.max(b.top_left.y),
+        ),
+        Point::new(
+            a.bottom_right.x.min(b.bottom_right.x),
+            a.bottom_right.y.min(b.bottom_right.y),
+        ),
+    )
+}
+
+/// Returns `point` moved by `origin` if the result lies in `clip`.
+fn visible(origin: Point, clip: &Rectangle, point: Point) -> Option<Point> {
+    let point = point + origin;
+    let inside = (clip.top_left.x..=clip.bottom_right.x).contains(&point.x)
+        && (clip.top_left.y..=clip.bottom_right.y).contains(&point.y);
+
+    Some(point).filter(|_| inside)
+}
+
+/// Returns the size of `rect`, both corners inclusive, zero if it is empty.
+fn rect_size(rect: &Rectangle) -> Size {
+    let len = |start: i32, end: i32| u32::try_from(end - start + 1).unwrap_or(0);
+    Size::new(
+        len(rect.top_left.x, rect.bottom_right.x),
+        len(rect.top_left.y, rect.bottom_right.y),
+    )
+}
+
+impl<'a, SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    CroppedDisplay<'a, SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Returns a target covering `rect` of this target, both corners inclusive, see
+    /// [`CroppedDisplay`](struct.CroppedDisplay.html).
+    pub fn cropped(&mut self, rect: Rectangle) -> CroppedDisplay<'_, SPI, CS, DC, RST, D, O, BUF> {
+        let size = rect_size(&rect);
+        let rect = Rectangle::new(rect.top_left + self.origin, rect.bottom_right + self.origin);
+        CroppedDisplay {
+            origin: rect.top_left,
+            size,
+            clip: intersection(&self.clip, &rect),
+            display: self.display,
+        }
+    }
+
+    /// Writes the visible part of the area from `top_left` to `bottom_right` with a single
+    /// memory write. `pixels` have to cover the area row by row.
+    fn draw_area<I>(
+        &mut self,
+        top_left: Point,
+        bottom_right: Point,
+        pixels: I,
+    ) -> Result<(), Error<PinError, SpiError, CS::Error, RST::Error>>
+    where
+        I: IntoIterator<Item = Pixel<Rgb565>>,
+    {
+        let area = Rectangle::new(top_left + self.origin, bottom_right + self.origin);
+        let window = intersection(&self.clip, &area);
+        let corner =
+            |point: Point| Some((u16::try_from(point.x).ok()?, u16::try_from(point.y).ok()?));
+        let (xs, ys, xe, ye) = match (corner(window.top_left), corner(window.bottom_right)) {
+            (Some((xs, ys)), Some((xe, ye))) if xs <= xe && ys <= ye => (xs, ys, xe, ye),
+            _ => return Ok(()),
+        };
+
+        let origin = self.origin;
+        let colors = pixels
+            .into_iter()
+            .filter(|Pixel(point, _)| visible(origin, &window, *point).is_some())
+            .map(|Pixel(_, color)| RawU16::from(color).into_inner());
+        self.display.set_pixels_checked(xs, ys, xe, ye, colors)?;
+
+        Ok(())
+    }
+}
+
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Returns a draw target covering `rect` of the display, both corners inclusive, see
+    /// [`CroppedDisplay`](struct.CroppedDisplay.html).
+    pub fn cropped(&mut self, rect: Rectangle) -> CroppedDisplay<'_, SPI, CS, DC, RST, D, O, BUF> {
+        let (width, height) = self.size();
+        let screen = Rectangle::new(
+            Point::zero(),
+            Point::new(i32::from(width) - 1, i32::from(height) - 1),
+        );
+
+        CroppedDisplay {
+            origin: rect.top_left,
+            size: rect_size(&rect),
+            clip: intersection(&screen, &rect),
+            display: self,
+        }
+    }
+}
+
+impl<'a, SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize> DrawTarget<Rgb565>
+    for CroppedDisplay<'a, SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    type Error = Error<PinError, SpiError, CS::Error, RST::Error>;
+
+    fn draw_pixel(&mut self, pixel: Pixel<Rgb565>) -> Result<(), Self::Error> {
+        match visible(self.origin, &self.clip, pixel.0) {
+            Some(point) => self.display.draw_pixel(Pixel(point, pixel.1)),
+            None => Ok(()),
+        }
+    }
+
+    /// Translates and clips the pixels and coalesces them into runs like the display.
+    fn draw_iter<T>(&mut self, item: T) -> Result<(), Self::Error>
+    where
+        T: IntoIterator<Item = Pixel<Rgb565>>,
+    {
+        let (origin, clip) = (self.origin, self.clip);
+        let pixels = item.into_iter().filter_map(move |Pixel(point, color)| {
+            Some(Pixel(visible(origin, &clip, point)?, color))
+        });
+
+        self.display.draw_iter(pixels)
+    }
+
+    /// Draws horizontal and vertical lines with a single memory write of their visible part.
+    fn draw_line(
+        &mut self,
+        item: &Styled<Line, PrimitiveStyle<Rgb565>>,
+    ) -> Result<(), Self::Error> {
+        let Line { start, end } = item.primitive;
+        let axis_aligned = start.x == end.x || start.y == end.y;
+        match item.style.stroke_color {
+            Some(_) if item.style.stroke_width == 1 && axis_aligned => {
+                let top_left = Point::new(start.x.min(end.x), start.y.min(end.y));
+                let bottom_right = Point::new(start.x.max(end.x), start.y.max(end.y));
+                self.draw_area(top_left, bottom_right, item)
+            }
+            _ => self.draw_iter(item),
+        }
+    }
+
+    fn draw_triangle(
+        &mut self,
+        item: &Styled<Triangle, PrimitiveStyle<Rgb565>>,
+    ) -> Result<(), Self::Error> {
+        self.draw_iter(item)
+    }
+
+    /// Draws the visible part of filled rectangles with a single memory write.
+    fn draw_rectangle(
+        &mut self,
+        item: &Styled<Rectangle, PrimitiveStyle<Rgb565>>,
+    ) -> Result<(), Self::Error> {
+        if item.style.fill_color.is_some() {
+            self.draw_area(item.top_left(), item.bottom_right(), item)
+        } else {
+            self.draw_iter(item)
+        }
+    }
+
+    fn draw_circle(
+        &mut self,
+        item: &Styled<Circle, PrimitiveStyle<Rgb565>>,
+    ) -> Result<(), Self::Error> {
+        self.draw_iter(item)
+    }
+
+    /// Draws the visible part of images with a single memory write.
+    fn draw_image<'b, 'c, I>(&mut self, item: &'b Image<'c, I, Rgb565>) -> Result<(), Self::Error>
+    where
+        &'c I: IntoPixelIter<Rgb565>,
+        I: ImageDimensions,
+    {
+        // TODO: this is inconsistent in embedded-graphics between Rectangle and Image
+        // See: https://github.com/jamwaffles/embedded-graphics/issues/182
+        let end = item.bottom_right();
+        let bottom_right = Point::new(end.x.saturating_sub(1), end.y.saturating_sub(1));
+
+        self.draw_area(item.top_left(), bottom_right, item)
+    }
+
+    fn size(&self) -> Size {
+        self.size
+    }
+}
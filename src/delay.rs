@@ -0,0 +1,90 @@
+use embedded_hal::blocking::delay::DelayMs;
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{ColorFormat, Error, InitOptions, TransferObserver, ST7789V};
+
+/// Waits of the timed commands, done by a delay provider passed in or by the one owned by the
+/// driver
+pub(crate) trait Wait<D> {
+    /// Waits `ms` milliseconds, `own` is the delay provider owned by the driver.
+    fn wait_ms(&mut self, own: &mut D, ms: u16);
+}
+
+impl<DELAY, D> Wait<D> for DELAY
+where
+    DELAY: DelayMs<u16>,
+{
+    fn wait_ms(&mut self, _own: &mut D, ms: u16) {
+        self.delay_ms(ms);
+    }
+}
+
+/// Waits with the delay provider owned by the driver
+pub(crate) struct OwnDelay;
+
+impl<D> Wait<D> for OwnDelay
+where
+    D: DelayMs<u16>,
+{
+    fn wait_ms(&mut self, own: &mut D, ms: u16) {
+        own.delay_ms(ms);
+    }
+}
+
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+    D: DelayMs<u16>,
+{
+    /// Initialize the display using the owned delay provider, see [`init`](#method.init).
+    pub fn init_with_own_delay(
+        &mut self,
+    ) -> Result<(), Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.init_waiting(InitOptions::default(), &mut OwnDelay)
+    }
+
+    /// Sets the color format using the owned delay provider, see
+    /// [`color_mode`](#method.color_mode).
+    pub fn color_mode_with_own_delay(
+        &mut self,
+        color_format: ColorFormat,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.color_mode_waiting(color_format, &mut OwnDelay)
+    }
+
+    /// Enters sleep mode using the owned delay provider, see [`sleep_in`](#method.sleep_in).
+    pub fn sleep_in_with_own_delay(
+        &mut self,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.sleep_in_waiting(&mut OwnDelay)
+    }
+
+    /// Leaves sleep mode using the owned delay provider, see [`sleep_out`](#method.sleep_out).
+    pub fn sleep_out_with_own_delay(
+        &mut self,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.sleep_out_waiting(&mut OwnDelay)
+    }
+
+    /// Performs a hard reset using the owned delay provider, see
+    /// [`hard_reset`](#method.hard_reset).
+    pub fn hard_reset_with_own_delay(
+        &mut self,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.hard_reset_waiting(&mut OwnDelay)
+    }
+
+    /// Performs a software reset using the owned delay provider, see
+    /// [`soft_reset`](#method.soft_reset).
+    pub fn soft_reset_with_own_delay(
+        &mut self,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.soft_reset_waiting(&mut OwnDelay)
+    }
+}
@@ -0,0 +1,64 @@
+use core::convert::TryFrom;
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{Error, PixelFormat, TransferObserver, ST7789V};
+
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Writes a buffer of RGB565 colors to the area from `x0`, `y0` to `x1`, `y1`, both
+    /// corners inclusive, as used by the flush callback of LVGL.
+    ///
+    /// The display expects the colors MSB first. With `swap` the bytes of each color are
+    /// swapped in place before they are written, e.g. for the native little endian colors
+    /// of LVGL, so `buf` holds the swapped colors afterwards. The buffer is swapped and
+    /// written in chunks of `BUF` bytes, so each chunk is written right after it was swapped.
+    ///
+    /// ```ignore
+    /// display.flush_area(area.x1, area.y1, area.x2, area.y2, color_bytes, true)?;
+    /// ```
+    ///
+    /// Returns `Error::PixelCountMismatch` if `buf` does not hold exactly two bytes for each
+    /// pixel of the area.
+    pub fn flush_area(
+        &mut self,
+        x0: u16,
+        y0: u16,
+        x1: u16,
+        y1: u16,
+        buf: &mut [u8],
+        swap: bool,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::CHUNK_SIZE_CHECK;
+
+        let expected = u32::from(x1.saturating_sub(x0)) + 1;
+        let expected = expected * (u32::from(y1.saturating_sub(y0)) + 1);
+        if buf.len() as u64 != 2 * u64::from(expected) {
+            return Err(Error::PixelCountMismatch {
+                expected,
+                written: u32::try_from(buf.len() / 2).unwrap_or(u32::MAX),
+            });
+        }
+
+        self.check_pixel_format(PixelFormat::Rgb565)?;
+        self.address_window(x0, y0, x1, y1)?.start_memory_write()?;
+        // an even chunk size keeps the colors in one piece
+        for chunk in buf.chunks_mut(BUF & !1) {
+            if swap {
+                chunk.chunks_exact_mut(2).for_each(|color| color.swap(0, 1));
+            }
+            self.write_data(chunk)?;
+        }
+
+        self.end_transaction()
+    }
+}
@@ -0,0 +1,126 @@
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{Error, PixelFormat, TransferObserver, ST7789V};
+
+/// Serializes an RGB666 color, given as `0bRRRRRRGGGGGGBBBBBB`, into the 18 bit frame memory
+/// format: one byte per component with the two low bits unused.
+fn rgb666_bytes(color: u32) -> [u8; 3] {
+    [
+        ((color >> 10) & 0xFC) as u8,
+        ((color >> 4) & 0xFC) as u8,
+        ((color << 2) & 0xFC) as u8,
+    ]
+}
+
+/// Packs two RGB444 colors, given as `0x0RGB`, into three bytes of the 12 bit frame memory
+/// format.
+fn rgb444_pair_bytes(first: u16, second: u16) -> [u8; 3] {
+    [
+        ((first >> 4) & 0xFF) as u8,
+        (((first & 0x0F) << 4) | ((second >> 8) & 0x0F)) as u8,
+        (second & 0xFF) as u8,
+    ]
+}
+
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Sets a single pixel to the given RGB666 color.
+    ///
+    /// The color is given as `0bRRRRRRGGGGGGBBBBBB`, higher bits are ignored. The color mode has
+    /// to be one of the 18 bit or 16M truncated formats.
+    pub fn pixel18(
+        &mut self,
+        x: u16,
+        y: u16,
+        color: u32,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.check_pixel_format(PixelFormat::Rgb666)?;
+        self.address_window(x, y, x, y)?;
+        self.mem_write(&rgb666_bytes(color))?;
+
+        Ok(self)
+    }
+
+    /// Sets the pixels of a window to the given RGB666 colors, see [`pixel18`](#method.pixel18).
+    pub fn pixels18(
+        &mut self,
+        xs: u16,
+        ys: u16,
+        xe: u16,
+        ye: u16,
+        colors: &mut dyn Iterator<Item = u32>,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.check_pixel_format(PixelFormat::Rgb666)?;
+        self.address_window(xs, ys, xe, ye)?;
+        self.start_memory_write()?;
+        self.write_packed(colors.map(|color| (rgb666_bytes(color), 3)))?;
+
+        self.end_transaction()
+    }
+
+    /// Sets the pixels of a window to the given RGB444 colors.
+    ///
+    /// The colors are given as `0x0RGB`, higher bits are ignored. Two pixels are packed into
+    /// three bytes, a single remaining pixel at the end is sent as two bytes. The color mode has
+    /// to be one of the 12 bit formats.
+    pub fn pixels12(
+        &mut self,
+        xs: u16,
+        ys: u16,
+        xe: u16,
+        ye: u16,
+        colors: &mut dyn Iterator<Item = u16>,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.check_pixel_format(PixelFormat::Rgb444)?;
+        self.address_window(xs, ys, xe, ye)?;
+        self.start_memory_write()?;
+        let pairs = core::iter::from_fn(|| {
+            let first = colors.next()?;
+            Some(match colors.next() {
+                Some(second) => (rgb444_pair_bytes(first, second), 3),
+                None => (rgb444_pair_bytes(first, 0), 2),
+            })
+        });
+        self.write_packed(pairs)?;
+
+        self.end_transaction()
+    }
+
+    /// Writes the first `len` bytes of each item, collected in chunks of up to `BUF` bytes.
+    /// Items which do not fit into a chunk at all are written on their own.
+    fn write_packed<I>(
+        &mut self,
+        items: I,
+    ) -> Result<(), Error<PinError, SpiError, CS::Error, RST::Error>>
+    where
+        I: IntoIterator<Item = ([u8; 3], usize)>,
+    {
+        let mut buffer = [0u8; BUF];
+        let mut len = 0;
+        for (bytes, item_len) in items {
+            if len + item_len > BUF && len > 0 {
+                self.write_data(&buffer[..len])?;
+                len = 0;
+            }
+            if item_len > BUF {
+                self.write_data(&bytes[..item_len])?;
+                continue;
+            }
+            buffer[len..len + item_len].copy_from_slice(&bytes[..item_len]);
+            len += item_len;
+        }
+        if len > 0 {
+            self.write_data(&buffer[..len])?;
+        }
+
+        Ok(())
+    }
+}
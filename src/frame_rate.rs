@@ -0,0 +1,135 @@
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::command::Command;
+use crate::{Error, TransferObserver, ST7789V};
+
+/// FRCTRL1 FRSEN bit enabling separate frame rates in idle and partial mode
+const FRCTRL1_FRSEN: u8 = 0b0001_0000;
+
+/// Frame rate (RTN)
+///
+/// The frame rate is set by the number of clocks per line, the rates below are the ones of
+/// the datasheet for the default porch settings and 320 lines.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrameRateDiv {
+    /// 119 Hz
+    Hz119 = 0x00,
+    /// 111 Hz
+    Hz111 = 0x01,
+    /// 105 Hz
+    Hz105 = 0x02,
+    /// 99 Hz
+    Hz99 = 0x03,
+    /// 94 Hz
+    Hz94 = 0x04,
+    /// 90 Hz
+    Hz90 = 0x05,
+    /// 86 Hz
+    Hz86 = 0x06,
+    /// 82 Hz
+    Hz82 = 0x07,
+    /// 78 Hz
+    Hz78 = 0x08,
+    /// 75 Hz
+    Hz75 = 0x09,
+    /// 72 Hz
+    Hz72 = 0x0A,
+    /// 69 Hz
+    Hz69 = 0x0B,
+    /// 67 Hz
+    Hz67 = 0x0C,
+    /// 64 Hz
+    Hz64 = 0x0D,
+    /// 62 Hz
+    Hz62 = 0x0E,
+    /// 60 Hz, the reset default
+    Hz60 = 0x0F,
+    /// 58 Hz
+    Hz58 = 0x10,
+    /// 57 Hz
+    Hz57 = 0x11,
+    /// 55 Hz
+    Hz55 = 0x12,
+    /// 53 Hz
+    Hz53 = 0x13,
+    /// 52 Hz
+    Hz52 = 0x14,
+    /// 50 Hz
+    Hz50 = 0x15,
+    /// 49 Hz
+    Hz49 = 0x16,
+    /// 48 Hz
+    Hz48 = 0x17,
+    /// 46 Hz
+    Hz46 = 0x18,
+    /// 45 Hz
+    Hz45 = 0x19,
+    /// 44 Hz
+    Hz44 = 0x1A,
+    /// 43 Hz
+    Hz43 = 0x1B,
+    /// 42 Hz
+    Hz42 = 0x1C,
+    /// 41 Hz
+    Hz41 = 0x1D,
+    /// 40 Hz
+    Hz40 = 0x1E,
+    /// 39 Hz
+    Hz39 = 0x1F,
+}
+
+impl FrameRateDiv {
+    pub fn value(self) -> u8 {
+        self as u8
+    }
+}
+
+/// Inversion method (NL)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrameInversion {
+    /// Dot inversion, the reset default
+    Dot = 0b0000_0000,
+    /// Column inversion
+    Column = 0b1110_0000,
+}
+
+impl FrameInversion {
+    pub fn value(self) -> u8 {
+        self as u8
+    }
+}
+
+/// Frame rate control
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Sets separate frame rates for idle and partial mode (FRCTRL1).
+    ///
+    /// Lower frame rates save power in the low power modes, see
+    /// [`low_power_mode`](#method.low_power_mode). The frame rate in normal mode is not
+    /// changed. `inversion` is used in both modes.
+    pub fn set_idle_partial_frame_rates(
+        &mut self,
+        idle: FrameRateDiv,
+        partial: FrameRateDiv,
+        inversion: FrameInversion,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.command(
+            Command::FRCTRL1,
+            Some(&[
+                FRCTRL1_FRSEN,
+                inversion.value() | idle.value(),
+                inversion.value() | partial.value(),
+            ]),
+        )?;
+
+        Ok(self)
+    }
+}
@@ -0,0 +1,91 @@
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::command::Command;
+use crate::{Error, PixelFormat, TransferObserver, ST7789V};
+
+/// Frame memory write with direct access to the SPI bus
+///
+/// Created by [`ST7789V::start_frame_write`]. The address window is set, the memory write is
+/// started and the data/command pin is high, so everything written to the bus ends up in the
+/// frame memory, e.g. a frame buffer pushed by DMA.
+///
+/// The driver is borrowed for the lifetime of the writer, so no other command can interrupt
+/// the transfer. [`finish`](#method.finish) ends the memory write. Dropping the writer without
+/// finishing it is fine as well, the next command ends the memory write. A display created
+/// with [`with_cs_per_transaction`] keeps its chip select pin low until then.
+///
+/// Data written through [`spi`](#method.spi) or [`with_bus`](#method.with_bus) bypasses the
+/// driver and is not reported to its [`TransferObserver`](trait.TransferObserver.html).
+///
+/// [`ST7789V::start_frame_write`]: struct.ST7789V.html#method.start_frame_write
+/// [`with_cs_per_transaction`]: struct.ST7789V.html#method.with_cs_per_transaction
+pub struct FrameWriter<'a, SPI, CS, DC, RST, D = (), O = (), const BUF: usize = 128>
+where
+    SPI: spi::Write<u8>,
+    CS: OutputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    display: &'a mut ST7789V<SPI, CS, DC, RST, D, O, BUF>,
+}
+
+impl<'a, SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    FrameWriter<'a, SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Returns the SPI bus.
+    pub fn spi(&mut self) -> &mut SPI {
+        &mut self.display.spi
+    }
+
+    /// Calls `f` with the SPI bus and returns its result.
+    pub fn with_bus<F, R>(&mut self, f: F) -> R
+    where
+        F: FnOnce(&mut SPI) -> R,
+    {
+        f(&mut self.display.spi)
+    }
+
+    /// Ends the memory write.
+    pub fn finish(self) -> Result<(), Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.display.command(Command::NOP, None)?;
+
+        Ok(())
+    }
+}
+
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Sets the address window, starts a memory write and hands out the SPI bus to write the
+    /// RGB565 pixel data, see [`FrameWriter`](struct.FrameWriter.html).
+    pub fn start_frame_write(
+        &mut self,
+        xs: u16,
+        ys: u16,
+        xe: u16,
+        ye: u16,
+    ) -> Result<
+        FrameWriter<'_, SPI, CS, DC, RST, D, O, BUF>,
+        Error<PinError, SpiError, CS::Error, RST::Error>,
+    > {
+        self.check_pixel_format(PixelFormat::Rgb565)?;
+        self.address_window(xs, ys, xe, ye)?;
+        self.start_memory_write()?;
+        self.set_dc(true)?;
+
+        Ok(FrameWriter { display: self })
+    }
+}
@@ -0,0 +1,226 @@
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{Error, PixelFormat, TransferObserver, ST7789V};
+
+/// RGB565 frame buffer with dirty rectangle tracking
+///
+/// Draws into a caller provided buffer of `width * height` colors, row by row. The bounding
+/// box of all changes since the last flush is tracked, so [`flush`](#method.flush) only sends
+/// the changed region to the display.
+pub struct FrameBuffer<'a> {
+    buffer: &'a mut [u16],
+    width: u16,
+    height: u16,
+    /// Inclusive bounding box of the changes: `(xs, ys, xe, ye)`
+    dirty: Option<(u16, u16, u16, u16)>,
+}
+
+impl<'a> FrameBuffer<'a> {
+    /// Creates a frame buffer for a `width` by `height` display.
+    ///
+    /// The whole buffer is considered dirty, so the first flush sends all of it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buffer` holds less than `width * height` colors.
+    pub fn new(buffer: &'a mut [u16], width: u16, height: u16) -> Self {
+        assert!(buffer.len() >= usize::from(width) * usize::from(height));
+
+        let mut frame_buffer = FrameBuffer {
+            buffer,
+            width,
+            height,
+            dirty: None,
+        };
+        frame_buffer.mark_all_dirty();
+
+        frame_buffer
+    }
+
+    /// Returns the width and height in pixels.
+    pub fn size(&self) -> (u16, u16) {
+        (self.width, self.height)
+    }
+
+    /// Returns the color at `x`, `y`, or `None` outside the buffer.
+    pub fn pixel(&self, x: u16, y: u16) -> Option<u16> {
+        if x < self.width && y < self.height {
+            Some(self.buffer[self.index(x, y)])
+        } else {
+            None
+        }
+    }
+
+    /// Sets the pixel at `x`, `y` to the RGB565 `color`. Pixels outside the buffer are
+    /// ignored.
+    pub fn set_pixel(&mut self, x: u16, y: u16, color: u16) -> &mut Self {
+        if x < self.width && y < self.height {
+            let index = self.index(x, y);
+            self.buffer[index] = color;
+            self.mark_dirty(x, y, x, y);
+        }
+
+        self
+    }
+
+    /// Fills the whole buffer with the RGB565 `color`.
+    pub fn clear(&mut self, color: u16) -> &mut Self {
+        let len = usize::from(self.width) * usize::from(self.height);
+        for pixel in &mut self.buffer[..len] {
+            *pixel = color;
+        }
+        self.mark_all_dirty()
+    }
+
+    /// Returns the buffer for direct access.
+    ///
+    /// Changes made through the buffer are not tracked, use
+    /// [`mark_dirty`](#method.mark_dirty) to include them in the next flush.
+    pub fn buffer_mut(&mut self) -> &mut [u16] {
+        self.buffer
+    }
+
+    /// Adds the rectangle from `xs`, `ys` to `xe`, `ye` to the region sent by the next flush.
+    /// The rectangle is clipped to the buffer.
+    pub fn mark_dirty(&mut self, xs: u16, ys: u16, xe: u16, ye: u16) -> &mut Self {
+        if self.width == 0 || self.height == 0 || xs > xe || ys > ye {
+            return self;
+        }
+        if xs >= self.width || ys >= self.height {
+            return self;
+        }
+
+        let xe = xe.min(self.width - 1);
+        let ye = ye.min(self.height - 1);
+        self.dirty = Some(match self.dirty {
+            Some((dxs, dys, dxe, dye)) => (dxs.min(xs), dys.min(ys), dxe.max(xe), dye.max(ye)),
+            None => (xs, ys, xe, ye),
+        });
+
+        self
+    }
+
+    /// Marks the whole buffer dirty.
+    pub fn mark_all_dirty(&mut self) -> &mut Self {
+        let (width, height) = (self.width, self.height);
+        self.mark_dirty(0, 0, width.saturating_sub(1), height.saturating_sub(1))
+    }
+
+    /// Returns the region sent by the next flush as `(xs, ys, xe, ye)`, or `None` if nothing
+    /// changed.
+    pub fn dirty(&self) -> Option<(u16, u16, u16, u16)> {
+        self.dirty
+    }
+
+    /// Sends the changed region to the display with a single address window.
+    ///
+    /// Does nothing if nothing changed since the last flush. The dirty region is only reset
+    /// once the transfer succeeded.
+    pub fn flush<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>(
+        &mut self,
+        display: &mut ST7789V<SPI, CS, DC, RST, D, O, BUF>,
+    ) -> Result<(), Error<PinError, SpiError, CS::Error, RST::Error>>
+    where
+        SPI: spi::Write<u8, Error = SpiError>,
+        CS: OutputPin,
+        DC: OutputPin<Error = PinError>,
+        RST: OutputPin,
+        O: TransferObserver,
+    {
+        let (xs, ys, xe, ye) = match self.dirty {
+            Some(dirty) => dirty,
+            None => return Ok(()),
+        };
+
+        display.check_pixel_format(PixelFormat::Rgb565)?;
+        display.address_window(xs, ys, xe, ye)?;
+        display.start_memory_write()?;
+        let rows = (ys..=ye).flat_map(|y| {
+            let (start, end) = (self.index(xs, y), self.index(xe, y));
+            self.buffer[start..=end].iter().copied()
+        });
+        display.write_colors(rows)?;
+        self.dirty = None;
+
+        Ok(())
+    }
+
+    /// Sends the whole buffer to the display, regardless of what changed.
+    pub fn flush_full<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>(
+        &mut self,
+        display: &mut ST7789V<SPI, CS, DC, RST, D, O, BUF>,
+    ) -> Result<(), Error<PinError, SpiError, CS::Error, RST::Error>>
+    where
+        SPI: spi::Write<u8, Error = SpiError>,
+        CS: OutputPin,
+        DC: OutputPin<Error = PinError>,
+        RST: OutputPin,
+        O: TransferObserver,
+    {
+        self.mark_all_dirty();
+        self.flush(display)
+    }
+
+    fn index(&self, x: u16, y: u16) -> usize {
+        usize::from(y) * usize::from(self.width) + usize::from(x)
+    }
+}
+
+/// Canvas of [`ST7789V::update_region`], a frame buffer with the size of the region
+///
+/// [`ST7789V::update_region`]: struct.ST7789V.html#method.update_region
+pub type RegionCanvas<'a> = FrameBuffer<'a>;
+
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Renders the `w` by `h` pixel region at `x`, `y` into `buf` and writes it with a single
+    /// memory write, so the display never shows a half drawn region.
+    ///
+    /// `render` draws onto a [`RegionCanvas`](type.RegionCanvas.html) over the first `w * h`
+    /// colors of `buf` in coordinates local to the region, with the graphics feature e.g. with
+    /// embedded-graphics. The buffer keeps its content between calls, so it can be updated
+    /// partially.
+    ///
+    /// ```ignore
+    /// let mut buf = [0u16; 64 * 16];
+    /// display.update_region(100, 20, 64, 16, &mut buf, |canvas| {
+    ///     canvas.clear(0x0000);
+    ///     Text::new(value, Point::zero()).into_styled(style).draw(canvas).ok();
+    /// })?;
+    /// ```
+    ///
+    /// Returns `Error::InvalidParameter` for an empty region and `Error::BufferTooSmall` if
+    /// `buf` holds less than `w * h` colors. The region has to lie on the display.
+    pub fn update_region<F>(
+        &mut self,
+        x: u16,
+        y: u16,
+        w: u16,
+        h: u16,
+        buf: &mut [u16],
+        render: F,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>>
+    where
+        F: FnOnce(&mut RegionCanvas<'_>),
+    {
+        if w == 0 || h == 0 {
+            return Err(Error::InvalidParameter);
+        }
+        let buf = buf
+            .get_mut(..usize::from(w) * usize::from(h))
+            .ok_or(Error::BufferTooSmall)?;
+
+        render(&mut RegionCanvas::new(buf, w, h));
+
+        let (xe, ye) = (x.saturating_add(w - 1), y.saturating_add(h - 1));
+        self.set_pixels(x, y, xe, ye, buf.iter().copied())
+    }
+}
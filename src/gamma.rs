@@ -0,0 +1,51 @@
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::command::Command;
+use crate::{Error, TransferObserver, ST7789V};
+
+/// DGMEN bit enabling the digital gamma look-up tables
+const DGMEN_ENABLE: u8 = 0b0000_0100;
+
+/// Digital gamma look-up tables
+///
+/// The digital gamma tables remap the red and blue color components before the analog gamma
+/// correction set by PVGAMCTRL and NVGAMCTRL is applied, so a calibration has to take both
+/// into account.
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Enables or disables the digital gamma look-up tables.
+    ///
+    /// Load the tables with [`set_digital_gamma_luts`](#method.set_digital_gamma_luts) before
+    /// enabling them. Disabling passes the colors through unchanged again.
+    pub fn enable_digital_gamma(
+        &mut self,
+        enable: bool,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        let dgmen = if enable { DGMEN_ENABLE } else { 0 };
+        self.command(Command::DGMEN, Some(&[dgmen]))?;
+
+        Ok(self)
+    }
+
+    /// Writes the 64 entry digital gamma look-up tables for red and blue.
+    ///
+    /// Green has no digital gamma table on the ST7789V.
+    pub fn set_digital_gamma_luts(
+        &mut self,
+        red: &[u8; 64],
+        blue: &[u8; 64],
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.command(Command::DGMLUTR, Some(red))?
+            .command(Command::DGMLUTB, Some(blue))?;
+
+        Ok(self)
+    }
+}
@@ -0,0 +1,71 @@
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::command::Command;
+use crate::{Error, TransferObserver, ST7789V};
+
+/// Number of gate lines of the ST7789V
+const GATE_LINES: u16 = 320;
+
+/// GATECTRL TMG bit, gate on timing adjustment as after reset
+const GATECTRL_TMG: u8 = 0b0001_0000;
+
+/// Gate scan mode (SM and GS)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GateScanMode {
+    /// Gates are scanned from the start line upwards
+    Normal = 0b0000_0000,
+    /// Gates are scanned from the last line downwards
+    Reverse = 0b0000_0001,
+    /// Odd and even gates are scanned alternately
+    Interlaced = 0b0000_0100,
+    /// Interlaced and from the last line downwards
+    InterlacedReverse = 0b0000_0101,
+}
+
+impl GateScanMode {
+    pub fn value(self) -> u8 {
+        self as u8
+    }
+}
+
+/// Gate line configuration for panels with less than 320 rows
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Sets the number of gate lines driven and the first gate line scanned.
+    ///
+    /// Panels with less than 320 rows, e.g. 135x240 or 170x320 modules, only drive part of the
+    /// gate lines. `lines` and `start_line` are counted in rows and have to be multiples of 8,
+    /// `lines` has to be at least 8 and the driven lines have to end within the 320 gate
+    /// lines, otherwise `Error::InvalidParameter` is returned.
+    pub fn set_gate_lines(
+        &mut self,
+        lines: u16,
+        start_line: u16,
+        mode: GateScanMode,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        if lines == 0
+            || lines % 8 != 0
+            || start_line % 8 != 0
+            || u32::from(lines) + u32::from(start_line) > u32::from(GATE_LINES)
+        {
+            return Err(Error::InvalidParameter);
+        }
+
+        let nl = (lines / 8 - 1) as u8;
+        let scn = (start_line / 8) as u8;
+        self.command(
+            Command::GATECTRL,
+            Some(&[nl, scn, GATECTRL_TMG | mode.value()]),
+        )?;
+
+        Ok(self)
+    }
+}
@@ -1,69 +1,328 @@
+#![deny(clippy::cast_sign_loss)]
+
+use core::convert::TryFrom;
+
 use embedded_graphics::drawable::Pixel;
 use embedded_graphics::geometry::Dimensions;
 use embedded_graphics::image::{Image, ImageDimensions, IntoPixelIter};
-use embedded_graphics::pixelcolor::raw::{RawData, RawU16};
-use embedded_graphics::pixelcolor::Rgb565;
-use embedded_graphics::prelude::{DrawTarget, Size};
-use embedded_graphics::primitives::Rectangle;
+use embedded_graphics::pixelcolor::raw::{RawData, RawU16, RawU24};
+use embedded_graphics::pixelcolor::{PixelColor, Rgb565, Rgb888, RgbColor};
+use embedded_graphics::prelude::{DrawTarget, Point, Size};
+use embedded_graphics::primitives::{Circle, Line, Rectangle, Triangle};
 use embedded_graphics::style::{PrimitiveStyle, Styled};
 
 use embedded_hal::blocking::spi;
 use embedded_hal::digital::v2::OutputPin;
 
-use crate::{Error, ST7789V};
+use crate::runs::Direction;
+use crate::{Error, FrameBuffer, TransferObserver, ST7789V};
+
+/// 18 bit RGB color with 6 bits per channel
+///
+/// embedded-graphics 0.6 has no 18 bit color type, this one is used by the 18 bit draw target.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct Rgb666(u32);
+
+impl Rgb666 {
+    /// Creates a new color, each channel is in the range `0..=63`.
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Rgb666(((r as u32 & 0x3F) << 12) | ((g as u32 & 0x3F) << 6) | (b as u32 & 0x3F))
+    }
+
+    /// Returns the color as `0bRRRRRRGGGGGGBBBBBB`.
+    pub fn into_inner(self) -> u32 {
+        self.0
+    }
+}
+
+impl PixelColor for Rgb666 {
+    type Raw = RawU24;
+}
+
+impl RgbColor for Rgb666 {
+    fn r(&self) -> u8 {
+        (self.0 >> 12) as u8 & 0x3F
+    }
+
+    fn g(&self) -> u8 {
+        (self.0 >> 6) as u8 & 0x3F
+    }
+
+    fn b(&self) -> u8 {
+        self.0 as u8 & 0x3F
+    }
+
+    const MAX_R: u8 = 0x3F;
+    const MAX_G: u8 = 0x3F;
+    const MAX_B: u8 = 0x3F;
+
+    const BLACK: Self = Rgb666::new(0, 0, 0);
+    const RED: Self = Rgb666::new(0x3F, 0, 0);
+    const GREEN: Self = Rgb666::new(0, 0x3F, 0);
+    const BLUE: Self = Rgb666::new(0, 0, 0x3F);
+    const YELLOW: Self = Rgb666::new(0x3F, 0x3F, 0);
+    const MAGENTA: Self = Rgb666::new(0x3F, 0, 0x3F);
+    const CYAN: Self = Rgb666::new(0, 0x3F, 0x3F);
+    const WHITE: Self = Rgb666::new(0x3F, 0x3F, 0x3F);
+}
+
+impl From<RawU24> for Rgb666 {
+    fn from(raw: RawU24) -> Self {
+        Rgb666(raw.into_inner() & 0x3_FFFF)
+    }
+}
+
+impl From<Rgb666> for RawU24 {
+    fn from(color: Rgb666) -> Self {
+        RawU24::new(color.0)
+    }
+}
+
+impl From<Rgb565> for Rgb666 {
+    fn from(color: Rgb565) -> Self {
+        Rgb666::new(
+            color.r() << 1 | color.r() >> 4,
+            color.g(),
+            color.b() << 1 | color.b() >> 4,
+        )
+    }
+}
+
+impl From<Rgb888> for Rgb666 {
+    fn from(color: Rgb888) -> Self {
+        Rgb666::new(color.r() >> 2, color.g() >> 2, color.b() >> 2)
+    }
+}
 
-impl<SPI, CS, DC, RST, PinError, SpiError> ST7789V<SPI, CS, DC, RST, PinError, SpiError>
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
 where
     SPI: spi::Write<u8, Error = SpiError>,
-    CS: OutputPin<Error = PinError>,
+    CS: OutputPin,
     DC: OutputPin<Error = PinError>,
-    RST: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
 {
-    fn fill_rect(
+    /// Sets the pixels of the window from `xs`, `ys` to `xe`, `ye` to the given colors.
+    ///
+    /// Takes anything iterating `Rgb565` colors, e.g. an array or a `map` chain, and writes
+    /// it through the same chunked memory write as [`set_pixels`](#method.set_pixels).
+    ///
+    /// ```ignore
+    /// display.pixels_rgb(0, 0, 1, 0, [Rgb565::RED, Rgb565::BLUE])?;
+    /// display.pixels_rgb(0, 1, 9, 1, (0..10).map(|i| Rgb565::new(i, 0, 0)))?;
+    /// ```
+    pub fn pixels_rgb<I>(
         &mut self,
-        item: &dyn Dimensions,
-        colors: &mut dyn Iterator<Item = u16>,
-    ) -> Result<(), Error<PinError, SpiError>> {
-        let sx = item.top_left().x as u16;
-        let sy = item.top_left().y as u16;
-        let ex = item.bottom_right().x as u16;
-        let ey = item.bottom_right().y as u16;
+        xs: u16,
+        ys: u16,
+        xe: u16,
+        ye: u16,
+        colors: I,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>>
+    where
+        I: IntoIterator<Item = Rgb565>,
+    {
+        let colors = colors
+            .into_iter()
+            .map(|color| RawU16::from(color).into_inner());
 
-        self.pixels(sx, sy, ex, ey, colors)?;
+        self.set_pixels(xs, ys, xe, ye, colors)
+    }
 
-        Ok(())
+    /// Draws the part `source` of `image` with its top left corner at `position`, e.g. a
+    /// sprite out of a texture atlas. Both corners of `source` are inclusive.
+    ///
+    /// Like [`blit_sub`](#method.blit_sub) for embedded-graphics images. The selected pixels
+    /// are written with a single memory write if the part lies completely on the display,
+    /// otherwise it is clipped and drawn in runs like a clipped image.
+    ///
+    /// ```ignore
+    /// let atlas = ImageRaw::<Rgb565>::new(ATLAS, 240, 240);
+    /// let cell = Rectangle::new(Point::new(64, 0), Point::new(95, 31));
+    /// display.draw_image_region(&atlas, cell, Point::new(100, 50))?;
+    /// ```
+    ///
+    /// Returns `Error::InvalidParameter` if `source` is empty or not inside the image.
+    pub fn draw_image_region<I>(
+        &mut self,
+        image: &I,
+        source: Rectangle,
+        position: Point,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>>
+    where
+        I: ImageDimensions,
+        for<'b> &'b I: IntoPixelIter<Rgb565>,
+    {
+        let Rectangle {
+            top_left,
+            bottom_right,
+        } = source;
+        let inside = |point: Point| {
+            let x = u32::try_from(point.x).ok().filter(|&x| x < image.width());
+            let y = u32::try_from(point.y).ok().filter(|&y| y < image.height());
+            x.is_some() && y.is_some()
+        };
+        let empty = top_left.x > bottom_right.x || top_left.y > bottom_right.y;
+        if empty || !inside(top_left) || !inside(bottom_right) {
+            return Err(Error::InvalidParameter);
+        }
+
+        // images yield their pixels row by row, the rows above and below the part are not
+        // filtered pixel by pixel
+        let width = image.width() as usize;
+        let rows = (bottom_right.y - top_left.y).unsigned_abs() as usize + 1;
+        let offset = position - top_left;
+        let pixels = image
+            .pixel_iter()
+            .skip(top_left.y.unsigned_abs() as usize * width)
+            .take(rows * width)
+            .filter(|Pixel(point, _)| (top_left.x..=bottom_right.x).contains(&point.x))
+            .map(|Pixel(point, color)| Pixel(point + offset, color));
+
+        match self.display_window(position, bottom_right + offset) {
+            Some((xs, ys, xe, ye)) => {
+                let colors = pixels.map(|Pixel(_, color)| RawU16::from(color).into_inner());
+                self.set_pixels(xs, ys, xe, ye, colors)
+            }
+            None => {
+                self.draw_iter(pixels)?;
+                Ok(self)
+            }
+        }
+    }
+
+    /// Returns the display coordinates of `point`, `None` if it lies outside the display.
+    pub(crate) fn display_point(&self, point: Point) -> Option<(u16, u16)> {
+        let (width, height) = ST7789V::size(self);
+        let x = u16::try_from(point.x).ok().filter(|&x| x < width)?;
+        let y = u16::try_from(point.y).ok().filter(|&y| y < height)?;
+
+        Some((x, y))
+    }
+
+    /// Returns the window from `top_left` to `bottom_right` if it is not empty and lies
+    /// completely on the display.
+    pub(crate) fn display_window(
+        &self,
+        top_left: Point,
+        bottom_right: Point,
+    ) -> Option<(u16, u16, u16, u16)> {
+        let (xs, ys) = self.display_point(top_left)?;
+        let (xe, ye) = self.display_point(bottom_right)?;
+        if xs > xe || ys > ye {
+            return None;
+        }
+
+        Some((xs, ys, xe, ye))
+    }
+
+    /// Returns the window of an image if it is not empty and lies completely on the display.
+    pub(crate) fn image_window(&self, item: &dyn Dimensions) -> Option<(u16, u16, u16, u16)> {
+        // TODO: this is inconsistent in embedded-graphics between Rectangle and Image
+        // See: https://github.com/jamwaffles/embedded-graphics/issues/182
+        let end = item.bottom_right();
+        let bottom_right = Point::new(end.x.saturating_sub(1), end.y.saturating_sub(1));
+
+        self.display_window(item.top_left(), bottom_right)
     }
 }
 
-impl<SPI, CS, DC, RST, PinError, SpiError> DrawTarget<Rgb565>
-    for ST7789V<SPI, CS, DC, RST, PinError, SpiError>
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize> DrawTarget<Rgb565>
+    for ST7789V<SPI, CS, DC, RST, D, O, BUF>
 where
     SPI: spi::Write<u8, Error = SpiError>,
-    CS: OutputPin<Error = PinError>,
+    CS: OutputPin,
     DC: OutputPin<Error = PinError>,
-    RST: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
 {
-    type Error = Error<PinError, SpiError>;
+    type Error = Error<PinError, SpiError, CS::Error, RST::Error>;
 
+    /// Pixels outside the display are skipped.
     fn draw_pixel(&mut self, pixel: Pixel<Rgb565>) -> Result<(), Self::Error> {
-        let color = RawU16::from(pixel.1).into_inner();
-        let x = pixel.0.x as u16;
-        let y = pixel.0.y as u16;
-
-        self.pixel(x, y, color)?;
+        if let Some((x, y)) = self.display_point(pixel.0) {
+            self.pixel(x, y, RawU16::from(pixel.1).into_inner())?;
+        }
 
         Ok(())
     }
 
+    /// Coalesces horizontally adjacent pixels on the same row into runs, each written with a
+    /// single address window and memory write instead of one per pixel. Fonts and other
+    /// pixel iterators going row by row benefit the most. Pixels outside the display are
+    /// skipped.
+    fn draw_iter<T>(&mut self, item: T) -> Result<(), Self::Error>
+    where
+        T: IntoIterator<Item = Pixel<Rgb565>>,
+    {
+        self.draw_pixel_runs(item, Direction::Horizontal)
+    }
+
+    /// Draws horizontal and vertical lines with a single memory write. Other lines are drawn
+    /// as spans of adjacent pixels, horizontal ones for shallow and vertical ones for steep
+    /// lines.
+    fn draw_line(
+        &mut self,
+        item: &Styled<Line, PrimitiveStyle<Rgb565>>,
+    ) -> Result<(), Self::Error> {
+        let color = match item.style.stroke_color {
+            Some(color) if item.style.stroke_width > 0 => RawU16::from(color).into_inner(),
+            _ => return Ok(()),
+        };
+
+        let Line { start, end } = item.primitive;
+        if item.style.stroke_width == 1 && (start.x == end.x || start.y == end.y) {
+            return self.fill_span(
+                start.x.min(end.x),
+                start.y.min(end.y),
+                start.x.max(end.x),
+                start.y.max(end.y),
+                color,
+            );
+        }
+
+        let direction = if (end.y - start.y).abs() > (end.x - start.x).abs() {
+            Direction::Vertical
+        } else {
+            Direction::Horizontal
+        };
+        self.draw_pixel_runs(item, direction)
+    }
+
+    /// Draws the circle row by row, each horizontal span of a single color is written with
+    /// one memory write. Spans are clipped at the display edges.
+    fn draw_circle(
+        &mut self,
+        item: &Styled<Circle, PrimitiveStyle<Rgb565>>,
+    ) -> Result<(), Self::Error> {
+        self.draw_pixel_runs(item, Direction::Horizontal)
+    }
+
+    /// Draws the triangle scanline by scanline, the filled part of each row is written with
+    /// one memory write and the border with a few short ones.
+    fn draw_triangle(
+        &mut self,
+        item: &Styled<Triangle, PrimitiveStyle<Rgb565>>,
+    ) -> Result<(), Self::Error> {
+        self.draw_pixel_runs(item, Direction::Horizontal)
+    }
+
     fn draw_rectangle(
         &mut self,
         item: &Styled<Rectangle, PrimitiveStyle<Rgb565>>,
     ) -> Result<(), Self::Error> {
-        // filled rect can be rendered into frame window directly
+        // filled rect can be rendered into frame window directly, unless it is clipped
         if item.style.fill_color.is_some() {
-            let mut colors = item.into_iter().map(|p| RawU16::from(p.1).into_inner());
+            match self.display_window(item.top_left(), item.bottom_right()) {
+                Some((sx, sy, ex, ey)) => {
+                    let colors = item.into_iter().map(|p| RawU16::from(p.1).into_inner());
+                    self.set_pixels_checked(sx, sy, ex, ey, colors)?;
 
-            self.fill_rect(item, &mut colors)
+                    Ok(())
+                }
+                None => self.draw_iter(item),
+            }
         } else if let Some(_color) = item.style.stroke_color {
             if item.style.stroke_width == 0 {
                 return Ok(()); // nothing to draw
@@ -83,20 +342,109 @@ where
         &'b I: IntoPixelIter<Rgb565>,
         I: ImageDimensions,
     {
-        // TODO: this is inconsistent in embedded-graphics between Rectangle and Image
-        // See: https://github.com/jamwaffles/embedded-graphics/issues/182
-        let sx = item.top_left().x as u16;
-        let sy = item.top_left().y as u16;
-        let ex = (item.bottom_right().x - 1) as u16;
-        let ey = (item.bottom_right().y - 1) as u16;
-        let mut colors = item.into_iter().map(|p| RawU16::from(p.1).into_inner());
+        // clipped images are drawn in runs
+        let (sx, sy, ex, ey) = match self.image_window(item) {
+            Some(window) => window,
+            None => return self.draw_iter(item),
+        };
+        let colors = item.into_iter().map(|p| RawU16::from(p.1).into_inner());
+
+        self.set_pixels_checked(sx, sy, ex, ey, colors)?;
+
+        Ok(())
+    }
+
+    fn size(&self) -> Size {
+        let (width, height) = ST7789V::size(self);
+        Size::new(u32::from(width), u32::from(height))
+    }
+}
+
+/// Draw target for the 18 bit color formats
+///
+/// Initialize the display with one of the 18 bit or 16M truncated color formats to use this
+/// target. Drawing with the [`Rgb565`] target in an 18 bit color format, or with this target in
+/// a 16 bit color format, fails with [`Error::PixelFormatMismatch`].
+///
+/// [`Rgb565`]: https://docs.rs/embedded-graphics/0.6/embedded_graphics/pixelcolor/struct.Rgb565.html
+/// [`Error::PixelFormatMismatch`]: enum.Error.html#variant.PixelFormatMismatch
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize> DrawTarget<Rgb666>
+    for ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    type Error = Error<PinError, SpiError, CS::Error, RST::Error>;
 
-        self.pixels(sx, sy, ex, ey, &mut colors)?;
+    /// Pixels outside the display are skipped.
+    fn draw_pixel(&mut self, pixel: Pixel<Rgb666>) -> Result<(), Self::Error> {
+        if let Some((x, y)) = self.display_point(pixel.0) {
+            self.pixel18(x, y, pixel.1.into_inner())?;
+        }
+
+        Ok(())
+    }
+
+    fn draw_rectangle(
+        &mut self,
+        item: &Styled<Rectangle, PrimitiveStyle<Rgb666>>,
+    ) -> Result<(), Self::Error> {
+        // filled rect can be rendered into frame window directly, unless it is clipped
+        if item.style.fill_color.is_some() {
+            match self.display_window(item.top_left(), item.bottom_right()) {
+                Some((sx, sy, ex, ey)) => {
+                    let mut colors = item.into_iter().map(|p| p.1.into_inner());
+                    self.pixels18(sx, sy, ex, ey, &mut colors)?;
+
+                    Ok(())
+                }
+                None => self.draw_iter(item),
+            }
+        } else {
+            self.draw_iter(item)
+        }
+    }
+
+    fn draw_image<'a, 'b, I>(&mut self, item: &'a Image<'b, I, Rgb666>) -> Result<(), Self::Error>
+    where
+        &'b I: IntoPixelIter<Rgb666>,
+        I: ImageDimensions,
+    {
+        // clipped images are drawn pixel by pixel
+        let (sx, sy, ex, ey) = match self.image_window(item) {
+            Some(window) => window,
+            None => return self.draw_iter(item),
+        };
+        let mut colors = item.into_iter().map(|p| p.1.into_inner());
+
+        self.pixels18(sx, sy, ex, ey, &mut colors)?;
+
+        Ok(())
+    }
+
+    fn size(&self) -> Size {
+        let (width, height) = ST7789V::size(self);
+        Size::new(u32::from(width), u32::from(height))
+    }
+}
+
+impl<'a> DrawTarget<Rgb565> for FrameBuffer<'a> {
+    type Error = core::convert::Infallible;
+
+    fn draw_pixel(&mut self, pixel: Pixel<Rgb565>) -> Result<(), Self::Error> {
+        let Pixel(point, color) = pixel;
+        if let (Ok(x), Ok(y)) = (u16::try_from(point.x), u16::try_from(point.y)) {
+            self.set_pixel(x, y, RawU16::from(color).into_inner());
+        }
 
         Ok(())
     }
 
     fn size(&self) -> Size {
-        Size::new(240, 240)
+        let (width, height) = FrameBuffer::size(self);
+        Size::new(u32::from(width), u32::from(height))
     }
 }
@@ -0,0 +1,278 @@
+use embedded_hal::blocking::delay::DelayMs;
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::delay::Wait;
+use crate::{ColorFormat, Error, MemAccCtrlConfig, Panel, State, TransferObserver, ST7789V};
+
+/// Display initialization options
+///
+/// The defaults match [`ST7789V::init`](struct.ST7789V.html#method.init): 16 bit color format,
+/// inversion on, default memory access control and a 240x240 panel without offset.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InitOptions {
+    color_format: ColorFormat,
+    inversion: bool,
+    memory_access_control: MemAccCtrlConfig,
+    panel: Panel,
+    soft_reset: bool,
+}
+
+impl Default for InitOptions {
+    fn default() -> Self {
+        InitOptions {
+            color_format: ColorFormat::RGB65K_CI16Bit,
+            inversion: true,
+            memory_access_control: MemAccCtrlConfig::default(),
+            panel: Panel::default(),
+            soft_reset: true,
+        }
+    }
+}
+
+impl InitOptions {
+    /// Creates the default init options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the interface color format.
+    pub fn color_format(mut self, color_format: ColorFormat) -> Self {
+        self.color_format = color_format;
+        self
+    }
+
+    /// Enables or disables display inversion. Most IPS panels need inversion on, some TFT
+    /// panels show a negative image with it.
+    ///
+    /// The setting is sent before the display is turned on and sent again by
+    /// [`ST7789V::resume`](struct.ST7789V.html#method.resume) when leaving sleep mode.
+    pub fn inversion(mut self, inversion: bool) -> Self {
+        self.inversion = inversion;
+        self
+    }
+
+    /// Sets the memory access control config.
+    pub fn memory_access_control(mut self, config: MemAccCtrlConfig) -> Self {
+        self.memory_access_control = config;
+        self
+    }
+
+    /// Sets the visible panel size in pixels.
+    pub fn size(mut self, width: u16, height: u16) -> Self {
+        self.panel.width = width;
+        self.panel.height = height;
+        self
+    }
+
+    /// Sets the position of the visible panel area in the frame memory.
+    ///
+    /// Panels smaller than the 240x320 frame memory are often not mounted at its origin.
+    pub fn offset(mut self, x: u16, y: u16) -> Self {
+        self.panel.x_offset = x;
+        self.panel.y_offset = y;
+        self
+    }
+
+    /// Enables or disables the soft reset following the hard reset.
+    ///
+    /// The hard reset already resets all registers, the soft reset can be skipped to save
+    /// 150 ms.
+    pub fn soft_reset(mut self, soft_reset: bool) -> Self {
+        self.soft_reset = soft_reset;
+        self
+    }
+}
+
+/// Progress of a non-blocking initialization
+///
+/// Created by [`ST7789V::begin_init`] and advanced by [`ST7789V::poll_init`], which sends the
+/// next step of the init sequence once the wait time of the previous one has elapsed. The
+/// caller supplies the time in milliseconds from any monotonic clock, it may wrap around.
+///
+/// ```ignore
+/// let mut init = display.begin_init(InitOptions::new(), millis())?;
+/// while let InitStatus::Pending { .. } = display.poll_init(&mut init, millis())? {
+///     usb.poll();
+///     watchdog.feed();
+/// }
+/// ```
+///
+/// [`ST7789V::begin_init`]: struct.ST7789V.html#method.begin_init
+/// [`ST7789V::poll_init`]: struct.ST7789V.html#method.poll_init
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InitSequence {
+    options: InitOptions,
+    step: InitStep,
+    /// Time the wait of the previous step started at
+    since: u32,
+    /// Wait before the next step
+    wait: u16,
+}
+
+/// Steps of the init sequence, each one is sent after the wait of the previous one
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum InitStep {
+    ResetPulse,
+    ResetRelease,
+    SoftReset,
+    SleepOut,
+    ColorMode,
+    Configure,
+    Complete,
+}
+
+/// Result of [`ST7789V::poll_init`](struct.ST7789V.html#method.poll_init)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InitStatus {
+    /// The next step is due in `wait_ms` milliseconds
+    Pending { wait_ms: u32 },
+    /// The display is initialized
+    Complete,
+}
+
+/// Delay which returns right away, the init sequence waits between its steps itself
+struct NoWait;
+
+impl DelayMs<u16> for NoWait {
+    fn delay_ms(&mut self, _ms: u16) {}
+}
+
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Initialize the display using the given options
+    ///
+    /// Runs the same sequence as [`begin_init`](#method.begin_init) and
+    /// [`poll_init`](#method.poll_init), waiting with `delay` between the steps.
+    pub fn init_with<DELAY>(
+        &mut self,
+        options: InitOptions,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<PinError, SpiError, CS::Error, RST::Error>>
+    where
+        DELAY: DelayMs<u16>,
+    {
+        self.init_waiting(options, delay)
+    }
+
+    /// Runs [`init_with`](#method.init_with) with the waits done by `wait`.
+    pub(crate) fn init_waiting<W: Wait<D>>(
+        &mut self,
+        options: InitOptions,
+        wait: &mut W,
+    ) -> Result<(), Error<PinError, SpiError, CS::Error, RST::Error>> {
+        let mut init = self.begin_init(options, 0)?;
+        while init.step != InitStep::Complete {
+            wait.wait_ms(&mut self.cfg.delay, init.wait);
+            self.init_step(&mut init)?;
+        }
+
+        Ok(())
+    }
+
+    /// Starts a non-blocking initialization with the given options at time `now_ms`, see
+    /// [`InitSequence`](struct.InitSequence.html).
+    ///
+    /// Raises the reset line, the reset pulse follows on the next
+    /// [`poll_init`](#method.poll_init).
+    pub fn begin_init(
+        &mut self,
+        options: InitOptions,
+        now_ms: u32,
+    ) -> Result<InitSequence, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.panel = options.panel;
+        self.cfg.set_reset(false).map_err(Error::Rst)?;
+
+        Ok(InitSequence {
+            options,
+            step: InitStep::ResetPulse,
+            since: now_ms,
+            wait: self.timings.reset_pulse_ms,
+        })
+    }
+
+    /// Sends the next steps of the init sequence which are due at time `now_ms`.
+    ///
+    /// Returns `InitStatus::Pending` with the time until the next step is due, or
+    /// `InitStatus::Complete` once the display is on. Polling more often than needed is fine,
+    /// nothing is sent before the wait of the previous step has elapsed.
+    pub fn poll_init(
+        &mut self,
+        init: &mut InitSequence,
+        now_ms: u32,
+    ) -> Result<InitStatus, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        while init.step != InitStep::Complete {
+            let elapsed = now_ms.wrapping_sub(init.since);
+            if elapsed < u32::from(init.wait) {
+                return Ok(InitStatus::Pending {
+                    wait_ms: u32::from(init.wait) - elapsed,
+                });
+            }
+
+            self.init_step(init)?;
+            init.since = now_ms;
+        }
+
+        Ok(InitStatus::Complete)
+    }
+
+    /// Sends the next step of the init sequence and sets the wait before the following one.
+    fn init_step(
+        &mut self,
+        init: &mut InitSequence,
+    ) -> Result<(), Error<PinError, SpiError, CS::Error, RST::Error>> {
+        let options = init.options;
+        let (next, wait) = match init.step {
+            InitStep::ResetPulse => {
+                self.cfg.set_reset(true).map_err(Error::Rst)?;
+                (InitStep::ResetRelease, self.timings.reset_pulse_ms)
+            }
+            InitStep::ResetRelease => {
+                self.cfg.set_reset(false).map_err(Error::Rst)?;
+                self.state = State::after_reset();
+                if options.soft_reset {
+                    (InitStep::SoftReset, self.timings.reset_ms)
+                } else {
+                    (InitStep::SleepOut, self.timings.reset_ms)
+                }
+            }
+            InitStep::SoftReset => {
+                self.soft_reset(&mut NoWait)?;
+                (InitStep::SleepOut, self.timings.soft_reset_ms)
+            }
+            InitStep::SleepOut => {
+                self.sleep_out(&mut NoWait)?;
+                (InitStep::ColorMode, self.timings.sleep_out_ms)
+            }
+            InitStep::ColorMode => {
+                self.color_mode(options.color_format, &mut NoWait)?;
+                (InitStep::Configure, self.timings.color_mode_ms)
+            }
+            InitStep::Configure => {
+                self.memory_access_control(options.memory_access_control)?;
+                // the panel axes are exchanged once MADCTL is written
+                let (width, height) = self.size();
+                self.address_window(0, 0, width.saturating_sub(1), height.saturating_sub(1))?;
+                if options.inversion {
+                    self.inversion_on()?;
+                } else {
+                    self.inversion_off()?;
+                }
+                self.normal_mode()?.display_on()?;
+                (InitStep::Complete, 0)
+            }
+            InitStep::Complete => (InitStep::Complete, 0),
+        };
+        init.step = next;
+        init.wait = wait;
+
+        Ok(())
+    }
+}
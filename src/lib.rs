@@ -1,33 +1,319 @@
 #![deny(unsafe_code, warnings)]
+#![allow(clippy::type_complexity)]
 #![no_std]
 
+#[cfg(feature = "std")]
+extern crate std;
+
+use core::convert::TryFrom;
+use core::fmt;
 use core::marker::PhantomData;
+use core::ops::ControlFlow;
 
-use embedded_hal::blocking::delay::DelayMs;
+use embedded_hal::blocking::delay::{DelayMs, DelayUs};
 use embedded_hal::blocking::spi;
 use embedded_hal::digital::v2::OutputPin;
 
+mod backlight;
+pub use crate::backlight::{CabcControl, PwmFrequency};
+mod blend;
+pub use crate::blend::blend_rgb565;
+mod blit;
+#[cfg(feature = "bmp")]
+mod bmp;
+#[cfg(feature = "bmp")]
+pub use crate::bmp::BmpError;
+mod brightness;
+pub mod color;
 mod command;
+mod command2;
+#[cfg(feature = "console")]
+mod console;
+mod copy;
+#[cfg(feature = "console")]
+pub use crate::console::TextConsole;
+mod delay;
+mod flush;
+#[cfg(feature = "console")]
+mod font;
+mod formats;
+mod frame_rate;
+pub use crate::frame_rate::{FrameInversion, FrameRateDiv};
+mod frame_writer;
+pub use crate::frame_writer::FrameWriter;
+mod framebuffer;
+pub use crate::framebuffer::{FrameBuffer, RegionCanvas};
+mod gamma;
+mod gate;
+pub use crate::gate::GateScanMode;
+mod init;
+pub use crate::init::{InitOptions, InitSequence, InitStatus};
+mod lines;
+mod madctl;
+mod no_pin;
+pub use crate::no_pin::NoPin;
+#[cfg(feature = "nvm")]
+mod nvm;
+#[cfg(feature = "nvm")]
+pub use crate::nvm::OneTimeProgrammable;
+mod observer;
+pub use crate::observer::{CountingObserver, TransferObserver};
+#[cfg(feature = "parallel")]
+mod parallel;
+#[cfg(feature = "parallel")]
+pub use crate::parallel::{OutputBus, Parallel8080, ParallelError};
+mod partial;
+#[cfg(feature = "patterns")]
+mod patterns;
+pub use crate::partial::{IntervalScan, NdsOutput};
+#[cfg(feature = "patterns")]
+pub use crate::patterns::GradientDirection;
+mod power;
+pub mod presets;
+mod progress;
+mod retry;
+use crate::retry::Retry;
+pub use crate::retry::RetryPolicy;
+mod rgb;
+pub use crate::rgb::{RgbInterfaceConfig, RgbTransferMode};
+mod scroll;
+mod self_test;
+mod shared_bus;
+pub use crate::shared_bus::{SharedBus, SharedDc, SharedSpi, SharedSpiError};
+mod timings;
+mod transaction;
+mod typestate;
+pub use crate::typestate::{Ready, Uninitialized};
+mod voltages;
+pub use crate::voltages::{
+    GateHighVoltage, GateLowVoltage, VcomOffset, VcomSetting, VdvSetting, VrhSetting,
+};
+mod verify;
+mod viewport;
+mod vsync;
+mod window;
+pub use crate::window::WindowWriter;
+mod words;
 use crate::command::Command;
+use crate::delay::Wait;
+pub use crate::power::LowPowerOptions;
+use crate::power::LowPowerRestore;
+pub use crate::timings::Timings;
 
+#[cfg(feature = "graphics")]
+mod cropped;
+#[cfg(feature = "graphics")]
+pub use crate::cropped::CroppedDisplay;
 #[cfg(feature = "graphics")]
 mod graphics;
+#[cfg(feature = "graphics")]
+pub use crate::graphics::Rgb666;
+#[cfg(feature = "graphics")]
+mod monochrome;
+#[cfg(feature = "graphics")]
+pub use crate::monochrome::Monochrome;
+#[cfg(feature = "graphics")]
+mod rgb888;
+#[cfg(feature = "graphics")]
+pub use crate::rgb888::Rgb888Target;
+#[cfg(feature = "graphics")]
+mod runs;
+
+#[cfg(feature = "std")]
+pub mod recording;
+#[cfg(feature = "simulator")]
+pub mod simulator;
 
 /// Errors
-#[derive(Debug)]
-pub enum Error<PinError, SpiError> {
+///
+/// `PinError` is the error type of the data/command and the tearing effect pin. The chip select
+/// and reset pins may have error types of their own, `CsError` and `RstError`, e.g. a pin of an
+/// I2C port expander next to MCU pins. They default to `PinError`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error<PinError, SpiError, CsError = PinError, RstError = PinError> {
+    /// Invalid column address: `start` is after `end` or `end` is after the last column `max`
+    InvalidColumnAddress { start: u16, end: u16, max: u16 },
+    /// Invalid row address: `start` is after `end` or `end` is after the last row `max`
+    InvalidRowAddress { start: u16, end: u16, max: u16 },
+    /// The display is in sleep mode and frame memory writes would not be visible
+    DisplayAsleep,
+    /// The pixel data does not match the configured color format
+    PixelFormatMismatch,
+    /// No tearing effect pulse was seen before the timeout
+    VsyncTimeout,
+    /// Scrolling needs a vertical scroll area
+    NoScrollArea,
+    /// Partial mode needs a partial area
+    NoPartialArea,
+    /// A command parameter is out of range
+    InvalidParameter,
+    /// The command needs Command 2 to be enabled
+    Command2Locked,
+    /// A window was not written with exactly `expected` pixels, `written` were pushed
+    PixelCountMismatch { expected: u32, written: u32 },
+    /// A buffer passed to the driver is too small for the operation
+    BufferTooSmall,
+    /// The display did not report sleep out, display on and booster on after initialization,
+    /// `power_mode` is the RDDPM and `status` the RDDST response
+    VerificationFailed { power_mode: u8, status: u32 },
+    /// The `register` read back from the display is `got` instead of the `expected` value the
+    /// driver wrote
+    ReadbackMismatch { register: u8, expected: u8, got: u8 },
+    /// The progress callback of a long operation stopped it
+    Aborted,
+    /// The BMP image is invalid or not in the supported format
+    #[cfg(feature = "bmp")]
+    InvalidBmp(BmpError),
+    /// Chip select pin error
+    Cs(CsError),
+    /// Data/command pin error
+    Dc(PinError),
+    /// Reset pin error
+    Rst(RstError),
+    /// Tearing effect pin error
+    Te(PinError),
+    /// SPI error
+    Spi(SpiError),
+}
+
+/// Error kinds without the wrapped pin and SPI errors
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
     /// Invalid column address
     InvalidColumnAddress,
     /// Invalid row address
     InvalidRowAddress,
-    /// Pin error
-    Pin(PinError),
+    /// The display is in sleep mode
+    DisplayAsleep,
+    /// The pixel data does not match the configured color format
+    PixelFormatMismatch,
+    /// No tearing effect pulse was seen before the timeout
+    VsyncTimeout,
+    /// Scrolling needs a vertical scroll area
+    NoScrollArea,
+    /// Partial mode needs a partial area
+    NoPartialArea,
+    /// A command parameter is out of range
+    InvalidParameter,
+    /// The command needs Command 2 to be enabled
+    Command2Locked,
+    /// A window was not written with the expected number of pixels
+    PixelCountMismatch,
+    /// A buffer passed to the driver is too small
+    BufferTooSmall,
+    /// The display status read back after initialization is wrong
+    VerificationFailed,
+    /// A register read back from the display differs from the written value
+    ReadbackMismatch,
+    /// The progress callback of a long operation stopped it
+    Aborted,
+    /// The BMP image is invalid or not in the supported format
+    InvalidBmp,
+    /// Chip select pin error
+    Cs,
+    /// Data/command pin error
+    Dc,
+    /// Reset pin error
+    Rst,
+    /// Tearing effect pin error
+    Te,
     /// SPI error
-    Spi(SpiError),
+    Spi,
+}
+
+impl<PinError, SpiError, CsError, RstError> Error<PinError, SpiError, CsError, RstError> {
+    /// Returns the kind of this error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::InvalidColumnAddress { .. } => ErrorKind::InvalidColumnAddress,
+            Error::InvalidRowAddress { .. } => ErrorKind::InvalidRowAddress,
+            Error::DisplayAsleep => ErrorKind::DisplayAsleep,
+            Error::PixelFormatMismatch => ErrorKind::PixelFormatMismatch,
+            Error::VsyncTimeout => ErrorKind::VsyncTimeout,
+            Error::NoScrollArea => ErrorKind::NoScrollArea,
+            Error::NoPartialArea => ErrorKind::NoPartialArea,
+            Error::InvalidParameter => ErrorKind::InvalidParameter,
+            Error::Command2Locked => ErrorKind::Command2Locked,
+            Error::PixelCountMismatch { .. } => ErrorKind::PixelCountMismatch,
+            Error::BufferTooSmall => ErrorKind::BufferTooSmall,
+            Error::VerificationFailed { .. } => ErrorKind::VerificationFailed,
+            Error::ReadbackMismatch { .. } => ErrorKind::ReadbackMismatch,
+            Error::Aborted => ErrorKind::Aborted,
+            #[cfg(feature = "bmp")]
+            Error::InvalidBmp(_) => ErrorKind::InvalidBmp,
+            Error::Cs(_) => ErrorKind::Cs,
+            Error::Dc(_) => ErrorKind::Dc,
+            Error::Rst(_) => ErrorKind::Rst,
+            Error::Te(_) => ErrorKind::Te,
+            Error::Spi(_) => ErrorKind::Spi,
+        }
+    }
+}
+
+impl<PinError, SpiError, CsError, RstError> fmt::Display
+    for Error<PinError, SpiError, CsError, RstError>
+where
+    PinError: fmt::Debug,
+    SpiError: fmt::Debug,
+    CsError: fmt::Debug,
+    RstError: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::InvalidColumnAddress { start, end, max } => write!(
+                f,
+                "invalid column address {}..={}, last column is {}",
+                start, end, max
+            ),
+            Error::InvalidRowAddress { start, end, max } => write!(
+                f,
+                "invalid row address {}..={}, last row is {}",
+                start, end, max
+            ),
+            Error::DisplayAsleep => f.write_str("display is in sleep mode"),
+            Error::PixelFormatMismatch => {
+                f.write_str("pixel data does not match the configured color format")
+            }
+            Error::VsyncTimeout => f.write_str("timed out waiting for tearing effect pulse"),
+            Error::NoScrollArea => f.write_str("no vertical scroll area defined"),
+            Error::NoPartialArea => f.write_str("no partial area defined"),
+            Error::InvalidParameter => f.write_str("command parameter out of range"),
+            Error::Command2Locked => f.write_str("command 2 is disabled"),
+            Error::PixelCountMismatch { expected, written } => write!(
+                f,
+                "window of {} pixels written with {} pixels",
+                expected, written
+            ),
+            Error::BufferTooSmall => f.write_str("buffer too small"),
+            Error::VerificationFailed { power_mode, status } => write!(
+                f,
+                "display status verification failed, power mode {:#04x}, status {:#010x}",
+                power_mode, status
+            ),
+            Error::ReadbackMismatch {
+                register,
+                expected,
+                got,
+            } => write!(
+                f,
+                "register {:#04x} read back as {:#04x}, expected {:#04x}",
+                register, got, expected
+            ),
+            Error::Aborted => f.write_str("operation aborted"),
+            #[cfg(feature = "bmp")]
+            Error::InvalidBmp(e) => write!(f, "unsupported BMP image: {:?}", e),
+            Error::Cs(e) => write!(f, "chip select pin error: {:?}", e),
+            Error::Dc(e) => write!(f, "data/command pin error: {:?}", e),
+            Error::Rst(e) => write!(f, "reset pin error: {:?}", e),
+            Error::Te(e) => write!(f, "tearing effect pin error: {:?}", e),
+            Error::Spi(e) => write!(f, "SPI error: {:?}", e),
+        }
+    }
 }
 
 /// RGB and control interface color format
 #[allow(dead_code, non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[repr(u8)]
 pub enum ColorFormat {
     /// RGB interface 65K, control interface 12 Bit/pixel
@@ -53,9 +339,30 @@ impl ColorFormat {
     pub fn value(self) -> u8 {
         self as u8
     }
+
+    /// Get the pixel format used to write frame memory over the control interface
+    pub fn pixel_format(self) -> PixelFormat {
+        match self.value() & 0b0000_0111 {
+            0b011 => PixelFormat::Rgb444,
+            0b101 => PixelFormat::Rgb565,
+            _ => PixelFormat::Rgb666,
+        }
+    }
+}
+
+/// Control interface pixel format
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PixelFormat {
+    /// 12 bit per pixel, two pixels are packed into three bytes
+    Rgb444,
+    /// 16 bit per pixel, two bytes per pixel
+    Rgb565,
+    /// 18 bit per pixel, three bytes per pixel with the two low bits of each byte unused
+    Rgb666,
 }
 
 /// Page Address Order (MY)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum PageAddressOrder {
     TopToBottom = 0b0000_0000,
     BottomToTop = 0b1000_0000,
@@ -68,6 +375,7 @@ impl PageAddressOrder {
 }
 
 /// Column Address Order (MX)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ColumnAddressOrder {
     LeftToRight = 0b0000_0000,
     RightToLeft = 0b0100_0000,
@@ -80,6 +388,7 @@ impl ColumnAddressOrder {
 }
 
 /// Page/Column Order (MV)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum PageColumnOrder {
     NormalMode = 0b0000_0000,
     ReverseMode = 0b0010_0000,
@@ -92,6 +401,7 @@ impl PageColumnOrder {
 }
 
 /// Line Address Order (ML)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum LineAddressOrder {
     TopToBottom = 0b0000_0000,
     BottomToTop = 0b0001_0000,
@@ -104,6 +414,7 @@ impl LineAddressOrder {
 }
 
 /// Color Order (RGB)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ColorOrder {
     Rgb = 0b0000_0000,
     Bgr = 0b0000_1000,
@@ -116,6 +427,7 @@ impl ColorOrder {
 }
 
 /// Display Data Latch Order (MH)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum LatchOrder {
     LeftToRight = 0b0000_0000,
     RightToLeft = 0b0000_0100,
@@ -128,6 +440,7 @@ impl LatchOrder {
 }
 
 /// Memory Access Control Config
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct MemAccCtrlConfig {
     color_order: ColorOrder,
     latch_order: LatchOrder,
@@ -137,8 +450,15 @@ pub struct MemAccCtrlConfig {
     column_order: ColumnAddressOrder,
 }
 
+impl Default for MemAccCtrlConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl MemAccCtrlConfig {
-    pub fn default() -> Self {
+    /// Creates the reset config: RGB, every address order top to bottom and left to right.
+    pub const fn new() -> Self {
         MemAccCtrlConfig {
             color_order: ColorOrder::Rgb,
             latch_order: LatchOrder::LeftToRight,
@@ -149,36 +469,98 @@ impl MemAccCtrlConfig {
         }
     }
 
-    pub fn color_order<'a>(&'a mut self, color_order: ColorOrder) -> &'a mut Self {
+    pub const fn color_order(mut self, color_order: ColorOrder) -> Self {
         self.color_order = color_order;
         self
     }
 
-    pub fn latch_order<'a>(&'a mut self, latch_order: LatchOrder) -> &'a mut Self {
+    pub const fn latch_order(mut self, latch_order: LatchOrder) -> Self {
         self.latch_order = latch_order;
         self
     }
 
-    pub fn line_order<'a>(&'a mut self, line_order: LineAddressOrder) -> &'a mut Self {
+    pub const fn line_order(mut self, line_order: LineAddressOrder) -> Self {
         self.line_order = line_order;
         self
     }
 
-    pub fn page_order<'a>(&'a mut self, page_order: PageAddressOrder) -> &'a mut Self {
+    pub const fn page_order(mut self, page_order: PageAddressOrder) -> Self {
         self.page_order = page_order;
         self
     }
 
-    pub fn page_column_order<'a>(&'a mut self, page_column_order: PageColumnOrder) -> &'a mut Self {
+    pub const fn page_column_order(mut self, page_column_order: PageColumnOrder) -> Self {
         self.page_column_order = page_column_order;
         self
     }
 
-    pub fn column_order<'a>(&'a mut self, column_order: ColumnAddressOrder) -> &'a mut Self {
+    pub const fn column_order(mut self, column_order: ColumnAddressOrder) -> Self {
         self.column_order = column_order;
         self
     }
 
+    pub fn get_color_order(&self) -> ColorOrder {
+        self.color_order
+    }
+
+    pub fn get_latch_order(&self) -> LatchOrder {
+        self.latch_order
+    }
+
+    pub fn get_line_order(&self) -> LineAddressOrder {
+        self.line_order
+    }
+
+    pub fn get_page_order(&self) -> PageAddressOrder {
+        self.page_order
+    }
+
+    pub fn get_page_column_order(&self) -> PageColumnOrder {
+        self.page_column_order
+    }
+
+    pub fn get_column_order(&self) -> ColumnAddressOrder {
+        self.column_order
+    }
+
+    /// Create from a MADCTL register value, the two unused low bits are ignored
+    pub fn from_value(value: u8) -> Self {
+        let bit = |order: u8| value & order != 0;
+        MemAccCtrlConfig {
+            color_order: if bit(ColorOrder::Bgr.value()) {
+                ColorOrder::Bgr
+            } else {
+                ColorOrder::Rgb
+            },
+            latch_order: if bit(LatchOrder::RightToLeft.value()) {
+                LatchOrder::RightToLeft
+            } else {
+                LatchOrder::LeftToRight
+            },
+            line_order: if bit(LineAddressOrder::BottomToTop.value()) {
+                LineAddressOrder::BottomToTop
+            } else {
+                LineAddressOrder::TopToBottom
+            },
+            page_order: if bit(PageAddressOrder::BottomToTop.value()) {
+                PageAddressOrder::BottomToTop
+            } else {
+                PageAddressOrder::TopToBottom
+            },
+            page_column_order: if bit(PageColumnOrder::ReverseMode.value()) {
+                PageColumnOrder::ReverseMode
+            } else {
+                PageColumnOrder::NormalMode
+            },
+            column_order: if bit(ColumnAddressOrder::RightToLeft.value()) {
+                ColumnAddressOrder::RightToLeft
+            } else {
+                ColumnAddressOrder::LeftToRight
+            },
+        }
+    }
+
+    /// Get as MADCTL register value
     pub fn value(self) -> u8 {
         self.color_order.value()
             | self.latch_order.value()
@@ -189,8 +571,22 @@ impl MemAccCtrlConfig {
     }
 }
 
+/// Level of the reset line which holds the display in reset
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ResetPolarity {
+    /// The display is reset while the line is low, like the RESX pin of the controller
+    #[default]
+    ActiveLow,
+    /// The display is reset while the line is high, e.g. if the board inverts the line
+    ActiveHigh,
+}
+
 /// ST7789V display driver config
-pub struct ST7789VConfig<CS, DC, RST>
+///
+/// `D` is the type of the delay provider owned by the driver, see
+/// [`with_delay`](#method.with_delay). It defaults to `()` if every timed command gets its
+/// delay provider passed in.
+pub struct ST7789VConfig<CS, DC, RST, D = ()>
 where
     CS: OutputPin,
     DC: OutputPin,
@@ -202,36 +598,216 @@ where
     dc: DC,
     /// Reset pin
     rst: RST,
+    /// Delay provider
+    delay: D,
+    /// Level of the reset line which resets the display
+    reset_polarity: ResetPolarity,
 }
 
-impl<CS, DC, RST> ST7789VConfig<CS, DC, RST>
+impl<DC, RST> ST7789VConfig<NoPin<DC::Error>, DC, RST>
 where
-    CS: OutputPin,
     DC: OutputPin,
     RST: OutputPin,
 {
-    /// Create a new display config
+    /// Create a new display config without chip select pin
+    ///
+    /// The chip select pin type is [`NoPin`](struct.NoPin.html).
     pub fn new(dc: DC, rst: RST) -> Self {
-        ST7789VConfig { cs: None, dc, rst }
+        ST7789VConfig {
+            cs: None,
+            dc,
+            rst,
+            delay: (),
+            reset_polarity: ResetPolarity::ActiveLow,
+        }
     }
+}
 
+impl<CS, DC, RST> ST7789VConfig<CS, DC, RST>
+where
+    CS: OutputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+{
     /// Create a new display config with chip select pin
     pub fn with_cs(cs: CS, dc: DC, rst: RST) -> Self {
         ST7789VConfig {
             cs: Some(cs),
             dc,
             rst,
+            delay: (),
+            reset_polarity: ResetPolarity::ActiveLow,
+        }
+    }
+}
+
+impl<CS, DC, RST, D> ST7789VConfig<CS, DC, RST, D>
+where
+    CS: OutputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// Hand a delay provider to the display config.
+    ///
+    /// A driver created from this config offers variants of the timed commands which use the
+    /// owned delay provider, e.g. [`ST7789V::init_with_own_delay`].
+    ///
+    /// [`ST7789V::init_with_own_delay`]: struct.ST7789V.html#method.init_with_own_delay
+    pub fn with_delay<D2>(self, delay: D2) -> ST7789VConfig<CS, DC, RST, D2> {
+        ST7789VConfig {
+            cs: self.cs,
+            dc: self.dc,
+            rst: self.rst,
+            delay,
+            reset_polarity: self.reset_polarity,
+        }
+    }
+
+    /// Sets the level of the reset line which resets the display, low by default.
+    ///
+    /// The hard reset and [`power_down`](struct.ST7789V.html#method.power_down) drive the line
+    /// accordingly, it idles at the opposite level. The pulse width and the wait after the
+    /// reset are set with [`Timings`](struct.Timings.html).
+    pub fn reset_polarity(mut self, polarity: ResetPolarity) -> Self {
+        self.reset_polarity = polarity;
+        self
+    }
+
+    /// Drives the reset line to hold the display in reset or to release it.
+    fn set_reset(&mut self, asserted: bool) -> Result<(), RST::Error> {
+        if asserted == (self.reset_polarity == ResetPolarity::ActiveHigh) {
+            self.rst.set_high()
+        } else {
+            self.rst.set_low()
         }
     }
 
     /// Release the data/command and reset pin
+    ///
+    /// The chip select pin and delay provider are dropped, use
+    /// [`into_parts`](#method.into_parts) to get them back as well.
     pub fn release(self) -> (DC, RST) {
         (self.dc, self.rst)
     }
+
+    /// Release the data/command pin, the reset pin and the delay provider
+    pub fn release_with_delay(self) -> (DC, RST, D) {
+        let (_, dc, rst, delay) = self.into_parts();
+        (dc, rst, delay)
+    }
+
+    /// Release every owned resource: the chip select pin if there is one, the data/command
+    /// pin, the reset pin and the delay provider
+    pub fn into_parts(self) -> (Option<CS>, DC, RST, D) {
+        (self.cs, self.dc, self.rst, self.delay)
+    }
+}
+
+/// Visible panel area in the frame memory
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Panel {
+    /// Width in pixels
+    width: u16,
+    /// Height in pixels
+    height: u16,
+    /// First column of the panel in the frame memory
+    x_offset: u16,
+    /// First row of the panel in the frame memory
+    y_offset: u16,
+}
+
+impl Default for Panel {
+    fn default() -> Self {
+        Panel {
+            width: 240,
+            height: 240,
+            x_offset: 0,
+            y_offset: 0,
+        }
+    }
+}
+
+/// Display settings as last written to the controller.
+///
+/// `None` means the setting was not written since the driver was created or the display was
+/// reset, so the controller runs with its reset default.
+#[derive(Default)]
+struct State {
+    /// Sleep mode was entered
+    asleep: bool,
+    /// Display on, the frame memory is shown
+    display_on: bool,
+    /// Interface color format (COLMOD)
+    color_format: Option<ColorFormat>,
+    /// Memory data access control (MADCTL)
+    madctl: Option<u8>,
+    /// Column address window (CASET)
+    column_address: Option<(u16, u16)>,
+    /// Row address window (RASET)
+    row_address: Option<(u16, u16)>,
+    /// Display inversion
+    inversion: Option<bool>,
+    /// Idle mode
+    idle: Option<bool>,
+    /// Display brightness (WRDISBV)
+    brightness: Option<u8>,
+    /// Partial mode, normal mode otherwise
+    partial_mode: Option<bool>,
+    /// Partial area rows (PTLAR)
+    partial_area: Option<(u16, u16)>,
+    /// Settings to restore when leaving low power mode
+    low_power: Option<LowPowerRestore>,
+    /// Vertical scroll area (VSCRDEF): top fixed, scroll and bottom fixed rows
+    scroll_area: Option<(u16, u16, u16)>,
+    /// Vertical scroll start address (VSCRSADD)
+    scroll_start: Option<u16>,
+    /// Command 2 was disabled (CMD2EN)
+    command2_locked: bool,
+}
+
+impl State {
+    /// State of the controller after a hard or soft reset
+    fn after_reset() -> Self {
+        State {
+            asleep: true,
+            ..State::default()
+        }
+    }
+}
+
+/// Returns `true` if the address range from `start` to `end` is in order and ends at `max` at
+/// the latest.
+fn valid_range(start: u16, end: u16, max: u16) -> bool {
+    start <= end && end <= max
+}
+
+/// Returns the number of pixels in the window from `xs`, `ys` to `xe`, `ye`.
+fn window_len(xs: u16, ys: u16, xe: u16, ye: u16) -> usize {
+    (usize::from(xe.saturating_sub(xs)) + 1) * (usize::from(ye.saturating_sub(ys)) + 1)
+}
+
+/// Packs a start and end address into the big endian parameters of CASET, RASET and PTLAR.
+fn address_range(start: u16, end: u16) -> [u8; 4] {
+    let [start_high, start_low] = start.to_be_bytes();
+    let [end_high, end_low] = end.to_be_bytes();
+    [start_high, start_low, end_high, end_low]
 }
 
 /// ST7789V display driver
-pub struct ST7789V<SPI, CS, DC, RST, PinError, SpiError>
+///
+/// `D` is the type of the owned delay provider, see [`with_delay`](#method.with_delay).
+///
+/// `O` is the type of the transfer observer, see [`with_observer`](#method.with_observer).
+///
+/// `BUF` is the size in bytes of the buffer pixel data is collected in before it is written
+/// to the SPI bus, which is also the longest single SPI transfer of pixel data. The buffer
+/// lives on the stack while pixels are written, so each bulk write costs `BUF` bytes of stack.
+/// The default of 128 bytes suits small MCUs, targets with DMA benefit from larger chunks,
+/// see [`with_chunk_size`](#method.with_chunk_size). `BUF` has to be at least 2.
+///
+/// `S` is the initialization state, see [`Uninitialized`](struct.Uninitialized.html). The
+/// default [`Ready`](struct.Ready.html) offers every command right away.
+pub struct ST7789V<SPI, CS, DC, RST, D = (), O = (), const BUF: usize = 128, S = Ready>
 where
     SPI: spi::Write<u8>,
     CS: OutputPin,
@@ -241,105 +817,392 @@ where
     /// SPI
     spi: SPI,
     /// Config
-    cfg: ST7789VConfig<CS, DC, RST>,
-
-    _pin_err: PhantomData<PinError>,
-    _spi_err: PhantomData<SpiError>,
+    cfg: ST7789VConfig<CS, DC, RST, D>,
+    /// Panel geometry
+    panel: Panel,
+    /// Level last set on the data/command pin, `None` if unknown
+    dc_level: Option<bool>,
+    /// The data/command pin is shared with other displays and set before every transfer
+    dc_shared: bool,
+    /// The chip select pin is only low during a transaction
+    cs_per_transaction: bool,
+    /// The chip select pin is low for the current transaction
+    cs_selected: bool,
+    /// Command delays
+    timings: Timings,
+    /// Display state
+    state: State,
+    /// Transfer observer
+    observer: O,
+    /// Retry policy and progress of the current command
+    retry: Retry,
+    /// Initialization state
+    typestate: PhantomData<S>,
 }
 
-impl<SPI, CS, DC, RST, PinError, SpiError> ST7789V<SPI, CS, DC, RST, PinError, SpiError>
+impl<SPI, DC, RST, PinError, SpiError> ST7789V<SPI, NoPin<PinError>, DC, RST>
 where
     SPI: spi::Write<u8, Error = SpiError>,
-    CS: OutputPin<Error = PinError>,
     DC: OutputPin<Error = PinError>,
-    RST: OutputPin<Error = PinError>,
+    RST: OutputPin,
 {
-    /// Creates a new display instance
+    /// Creates a new display instance without chip select pin
+    ///
+    /// The chip select pin type is [`NoPin`](struct.NoPin.html).
     pub fn new(spi: SPI, dc: DC, rst: RST) -> Self {
-        ST7789V {
-            spi,
-            cfg: ST7789VConfig::new(dc, rst),
-            _pin_err: PhantomData,
-            _spi_err: PhantomData,
-        }
+        Self::from_config(spi, ST7789VConfig::new(dc, rst))
     }
+}
 
+impl<SPI, DC, RST, PinError, SpiError, D> ST7789V<SPI, NoPin<PinError>, DC, RST, D>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+{
+    /// Creates a new display instance owning the delay provider
+    ///
+    /// The timed commands have variants which use the owned delay provider, e.g.
+    /// [`init_with_own_delay`](#method.init_with_own_delay).
+    pub fn with_delay(spi: SPI, dc: DC, rst: RST, delay: D) -> Self {
+        Self::from_config(spi, ST7789VConfig::new(dc, rst).with_delay(delay))
+    }
+}
+
+impl<SPI, CS, DC, RST, PinError, SpiError> ST7789V<SPI, CS, DC, RST>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+{
     /// Creates a new display instance with chip select pin
     pub fn with_cs(
         spi: SPI,
         mut cs: CS,
         dc: DC,
         rst: RST,
-    ) -> Result<Self, Error<PinError, SpiError>> {
-        cs.set_low().map_err(Error::Pin)?;
+    ) -> Result<Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        cs.set_low().map_err(Error::Cs)?;
 
-        let cfg = ST7789VConfig::with_cs(cs, dc, rst);
-        Ok(ST7789V {
-            spi,
-            cfg,
-            _pin_err: PhantomData,
-            _spi_err: PhantomData,
-        })
+        Ok(Self::from_config(spi, ST7789VConfig::with_cs(cs, dc, rst)))
+    }
+
+    /// Creates a new display instance on a bus shared with other devices, e.g. through a
+    /// `shared-bus` proxy
+    ///
+    /// Unlike [`with_cs`](#method.with_cs), which keeps the chip select pin low, the chip
+    /// select pin is raised right away and only lowered for a single transaction: a command
+    /// with its parameters or response, or a memory write with all of its pixel data. Other
+    /// devices can use the bus between two transactions, so the data/command pin is set again
+    /// at the start of each one.
+    ///
+    /// ```ignore
+    /// let bus = shared_bus::BusManagerSimple::new(spi);
+    /// let mut display = ST7789V::with_cs_per_transaction(bus.acquire_spi(), cs, dc, rst)?;
+    /// let mut sensor = Bme280::new(bus.acquire_spi(), sensor_cs);
+    ///
+    /// display.init(&mut delay)?;
+    /// let reading = sensor.measure()?;
+    /// display.clear(0x001F)?;
+    /// ```
+    pub fn with_cs_per_transaction(
+        spi: SPI,
+        mut cs: CS,
+        dc: DC,
+        rst: RST,
+    ) -> Result<Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        cs.set_high().map_err(Error::Cs)?;
+
+        let mut display = Self::from_config(spi, ST7789VConfig::with_cs(cs, dc, rst));
+        display.cs_per_transaction = true;
+
+        Ok(display)
     }
+}
 
+impl<SPI, CS, DC, RST, PinError, SpiError, D> ST7789V<SPI, CS, DC, RST, D>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+{
     /// Creates a new display instance using a previously build display config
     pub fn with_config(
         spi: SPI,
-        mut cfg: ST7789VConfig<CS, DC, RST>,
-    ) -> Result<Self, Error<PinError, SpiError>> {
+        mut cfg: ST7789VConfig<CS, DC, RST, D>,
+    ) -> Result<Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
         if let Some(cs) = cfg.cs.as_mut() {
-            cs.set_low().map_err(Error::Pin)?;
+            cs.set_low().map_err(Error::Cs)?;
         }
 
-        Ok(ST7789V {
+        Ok(Self::from_config(spi, cfg))
+    }
+
+    fn from_config(spi: SPI, cfg: ST7789VConfig<CS, DC, RST, D>) -> Self {
+        ST7789V {
             spi,
             cfg,
-            _pin_err: PhantomData,
-            _spi_err: PhantomData,
-        })
+            panel: Panel::default(),
+            dc_level: None,
+            dc_shared: false,
+            cs_per_transaction: false,
+            cs_selected: false,
+            timings: Timings::default(),
+            state: State::default(),
+            observer: (),
+            retry: Retry::default(),
+            typestate: PhantomData,
+        }
+    }
+}
+
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Rejects chunk sizes which cannot hold a single RGB565 color.
+    const CHUNK_SIZE_CHECK: () = assert!(BUF >= 2, "the chunk size has to be at least 2 bytes");
+
+    /// Changes the chunk size of pixel data writes to `N` bytes, see
+    /// [`ST7789V`](struct.ST7789V.html).
+    ///
+    /// ```ignore
+    /// let display = ST7789V::new(spi, dc, rst).with_chunk_size::<1024>();
+    /// ```
+    pub fn with_chunk_size<const N: usize>(self) -> ST7789V<SPI, CS, DC, RST, D, O, N> {
+        ST7789V {
+            spi: self.spi,
+            cfg: self.cfg,
+            panel: self.panel,
+            dc_level: self.dc_level,
+            dc_shared: self.dc_shared,
+            cs_per_transaction: self.cs_per_transaction,
+            cs_selected: self.cs_selected,
+            timings: self.timings,
+            state: self.state,
+            observer: self.observer,
+            retry: self.retry,
+            typestate: PhantomData,
+        }
+    }
+
+    /// Installs `observer`, which is called for every command, data transfer and
+    /// data/command pin write, see [`TransferObserver`](trait.TransferObserver.html).
+    ///
+    /// ```ignore
+    /// let mut display = ST7789V::new(spi, dc, rst).with_observer(CountingObserver::new());
+    /// ```
+    pub fn with_observer<O2>(self, observer: O2) -> ST7789V<SPI, CS, DC, RST, D, O2, BUF>
+    where
+        O2: TransferObserver,
+    {
+        ST7789V {
+            spi: self.spi,
+            cfg: self.cfg,
+            panel: self.panel,
+            dc_level: self.dc_level,
+            dc_shared: self.dc_shared,
+            cs_per_transaction: self.cs_per_transaction,
+            cs_selected: self.cs_selected,
+            timings: self.timings,
+            state: self.state,
+            observer,
+            retry: self.retry,
+            typestate: PhantomData,
+        }
+    }
+
+    /// Returns the installed transfer observer.
+    pub fn observer(&self) -> &O {
+        &self.observer
+    }
+
+    /// Returns the installed transfer observer mutably, e.g. to reset its counts.
+    pub fn observer_mut(&mut self) -> &mut O {
+        &mut self.observer
     }
 
     /// Release the SPI bus and display config. This will also raise the chip select pin.
+    ///
+    /// An owned delay provider is part of the config, see
+    /// [`ST7789VConfig::release_with_delay`](struct.ST7789VConfig.html#method.release_with_delay).
+    /// If raising the chip select pin fails the driver is lost, use
+    /// [`into_parts`](#method.into_parts) to get the hardware back in any case.
     pub fn release(
         mut self,
-    ) -> Result<(SPI, ST7789VConfig<CS, DC, RST>), Error<PinError, SpiError>> {
+    ) -> Result<
+        (SPI, ST7789VConfig<CS, DC, RST, D>),
+        Error<PinError, SpiError, CS::Error, RST::Error>,
+    > {
         if let Some(cs) = self.cfg.cs.as_mut() {
-            cs.set_high().map_err(Error::Pin)?;
+            cs.set_high().map_err(Error::Cs)?;
         }
 
         Ok((self.spi, self.cfg))
     }
 
+    /// Release the SPI bus and every pin and the delay provider.
+    ///
+    /// Unlike [`release`](#method.release) this does not touch the bus or any pin, the chip
+    /// select pin keeps its level. It cannot fail, so the hardware can be recovered after an
+    /// error or before the display supply is cut anyway.
+    ///
+    /// ```ignore
+    /// if let Err(error) = display.clear(BLACK) {
+    ///     let (spi, cs, dc, rst, delay) = display.into_parts();
+    ///     // reset the bus and start over
+    /// }
+    /// ```
+    pub fn into_parts(self) -> (SPI, Option<CS>, DC, RST, D) {
+        let (cs, dc, rst, delay) = self.cfg.into_parts();
+
+        (self.spi, cs, dc, rst, delay)
+    }
+
+    /// Lends the SPI bus to `f` and continues with the bus it returns, e.g. to rebuild the
+    /// peripheral at a higher clock after a slow initialization.
+    ///
+    /// The pins and the display state are kept. The chip select pin is high while `f` runs, so
+    /// the display ignores the bus while it is reconfigured, and it is lowered again afterwards
+    /// unless it is only low during a transaction. The data/command pin is set again before the
+    /// next transfer. The display keeps its address window, as it does not receive anything.
+    ///
+    /// ```ignore
+    /// display.init(&mut delay)?;
+    /// let mut display = display.swap_spi(|spi| {
+    ///     let (spi1, pins) = spi.free();
+    ///     Spi::spi1(spi1, pins, MODE_3, 62_500_000.hz(), clocks)
+    /// })?;
+    /// ```
+    pub fn swap_spi<SPI2, F>(
+        mut self,
+        f: F,
+    ) -> Result<
+        ST7789V<SPI2, CS, DC, RST, D, O, BUF>,
+        Error<PinError, SpiError, CS::Error, RST::Error>,
+    >
+    where
+        SPI2: spi::Write<u8>,
+        F: FnOnce(SPI) -> SPI2,
+    {
+        self.end_transaction()?;
+        let hold_cs = !self.cs_per_transaction;
+        if hold_cs {
+            if let Some(cs) = self.cfg.cs.as_mut() {
+                cs.set_high().map_err(Error::Cs)?;
+            }
+        }
+
+        let mut display = ST7789V {
+            spi: f(self.spi),
+            cfg: self.cfg,
+            panel: self.panel,
+            dc_level: None,
+            dc_shared: self.dc_shared,
+            cs_per_transaction: self.cs_per_transaction,
+            cs_selected: false,
+            timings: self.timings,
+            state: self.state,
+            observer: self.observer,
+            retry: self.retry,
+            typestate: PhantomData,
+        };
+        if hold_cs {
+            if let Some(cs) = display.cfg.cs.as_mut() {
+                cs.set_low().map_err(Error::Cs)?;
+            }
+        }
+
+        Ok(display)
+    }
+
     /// Initialize the display
-    pub fn init<DELAY>(&mut self, delay: &mut DELAY) -> Result<(), Error<PinError, SpiError>>
+    ///
+    /// Uses the default [`InitOptions`](struct.InitOptions.html), see
+    /// [`init_with`](#method.init_with) to change them.
+    pub fn init<DELAY>(
+        &mut self,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<PinError, SpiError, CS::Error, RST::Error>>
     where
         DELAY: DelayMs<u16>,
     {
-        self.hard_reset(delay)?
-            .soft_reset(delay)?
-            .sleep_out(delay)?
-            .color_mode(ColorFormat::RGB65K_CI16Bit, delay)?
-            .memory_access_control(MemAccCtrlConfig::default())?
-            .column_address(0, 240)?
-            .row_address(0, 240)?
-            .inversion_on()?
-            .normal_mode()?
-            .display_on()?;
+        self.init_with(InitOptions::default(), delay)
+    }
 
-        Ok(())
+    /// Sets the delays used by the timed commands.
+    ///
+    /// See [`Timings`](struct.Timings.html) for the defaults. To skip the soft reset during
+    /// initialization use [`InitOptions::soft_reset`](struct.InitOptions.html#method.soft_reset).
+    pub fn set_timings(&mut self, timings: Timings) -> &mut Self {
+        self.timings = timings;
+        self
+    }
+
+    /// Returns the delays used by the timed commands.
+    pub fn timings(&self) -> Timings {
+        self.timings
+    }
+
+    /// Returns the visible panel size in pixels as `(width, height)`.
+    ///
+    /// Width and height are exchanged while the memory access control exchanges page and
+    /// column order.
+    pub fn size(&self) -> (u16, u16) {
+        let view = self.view();
+        (view.width, view.height)
+    }
+
+    /// Returns `true` after [`display_on`](#method.display_on), `false` after
+    /// [`display_off`](#method.display_off) and every reset.
+    pub fn is_display_on(&self) -> bool {
+        self.state.display_on
+    }
+
+    /// Returns `true` while idle mode is on.
+    pub fn is_idle(&self) -> bool {
+        self.state.idle == Some(true)
+    }
+
+    /// Returns `true` while display inversion is on.
+    pub fn is_inverted(&self) -> bool {
+        self.state.inversion == Some(true)
+    }
+
+    /// Returns the color format set by [`color_mode`](#method.color_mode), `None` if it was
+    /// not set since the last reset.
+    pub fn color_format(&self) -> Option<ColorFormat> {
+        self.state.color_format
     }
 
     /// This sets the RGB interface and control interface color format.
-    pub fn color_mode<'a, DELAY>(
-        &'a mut self,
+    pub fn color_mode<DELAY>(
+        &mut self,
         color_format: ColorFormat,
         delay: &mut DELAY,
-    ) -> Result<&'a mut Self, Error<PinError, SpiError>>
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>>
     where
         DELAY: DelayMs<u16>,
     {
+        self.color_mode_waiting(color_format, delay)
+    }
+
+    /// Runs [`color_mode`](#method.color_mode) with the waits done by `wait`.
+    pub(crate) fn color_mode_waiting<W: Wait<D>>(
+        &mut self,
+        color_format: ColorFormat,
+        wait: &mut W,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
         self.command(Command::COLMOD, Some(&[color_format.value()]))?;
-        delay.delay_ms(10);
+        self.state.color_format = Some(color_format);
+        wait.wait_ms(&mut self.cfg.delay, self.timings.color_mode_ms);
 
         Ok(self)
     }
@@ -349,60 +1212,119 @@ where
     /// In this mode the DC/DC converter is stopped, the internal oscillator and the panel
     /// scanning is stopped. The MCU interface and memory are still working and the memory
     /// keeps its contents.
-    pub fn sleep_in<'a, DELAY>(
-        &'a mut self,
+    pub fn sleep_in<DELAY>(
+        &mut self,
         delay: &mut DELAY,
-    ) -> Result<&'a mut Self, Error<PinError, SpiError>>
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>>
     where
         DELAY: DelayMs<u16>,
     {
+        self.sleep_in_waiting(delay)
+    }
+
+    /// Runs [`sleep_in`](#method.sleep_in) with the waits done by `wait`.
+    pub(crate) fn sleep_in_waiting<W: Wait<D>>(
+        &mut self,
+        wait: &mut W,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
         self.command(Command::SLPIN, None)?;
-        delay.delay_ms(5);
+        self.state.asleep = true;
+        wait.wait_ms(&mut self.cfg.delay, self.timings.sleep_in_ms);
 
         Ok(self)
     }
 
     /// In this mode the DC/DC converter is enabled, internal display oscillator and the panel
     /// scanning is started.
-    pub fn sleep_out<'a, DELAY>(
-        &'a mut self,
+    pub fn sleep_out<DELAY>(
+        &mut self,
         delay: &mut DELAY,
-    ) -> Result<&'a mut Self, Error<PinError, SpiError>>
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>>
     where
         DELAY: DelayMs<u16>,
     {
+        self.sleep_out_waiting(delay)
+    }
+
+    /// Runs [`sleep_out`](#method.sleep_out) with the waits done by `wait`.
+    pub(crate) fn sleep_out_waiting<W: Wait<D>>(
+        &mut self,
+        wait: &mut W,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
         self.command(Command::SLPOUT, None)?;
-        delay.delay_ms(500);
+        self.state.asleep = false;
+        wait.wait_ms(&mut self.cfg.delay, self.timings.sleep_out_ms);
 
         Ok(self)
     }
 
     /// Leave normal mode and enter partial mode.
-    pub fn partial_display_mode<'a>(
-        &'a mut self,
-    ) -> Result<&'a mut Self, Error<PinError, SpiError>> {
+    ///
+    /// Returns [`Error::NoPartialArea`](enum.Error.html#variant.NoPartialArea) if no partial
+    /// area was set with [`partial_area`](#method.partial_area) since the last reset.
+    pub fn partial_display_mode(
+        &mut self,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        if self.state.partial_area.is_none() {
+            return Err(Error::NoPartialArea);
+        }
+
+        self.force_partial_display_mode()
+    }
+
+    /// Enter partial mode without checking that a partial area was set, the controller then
+    /// uses the partial area it currently holds.
+    pub fn force_partial_display_mode(
+        &mut self,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
         self.command(Command::PTLON, None)?;
+        self.state.partial_mode = Some(true);
 
         Ok(self)
     }
 
     /// Leave partial mode and enter normal mode.
-    pub fn normal_mode<'a>(&'a mut self) -> Result<&'a mut Self, Error<PinError, SpiError>> {
+    pub fn normal_mode(
+        &mut self,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
         self.command(Command::NORON, None)?;
+        self.state.partial_mode = Some(false);
+
+        Ok(self)
+    }
+
+    /// Sets the partial area from row `psl` to row `pel`.
+    ///
+    /// In partial mode only the rows inside the partial area are displayed, the remaining rows
+    /// show the non-display area color. If `psl` is greater than `pel` the partial area wraps
+    /// around the end of the frame memory.
+    pub fn partial_area(
+        &mut self,
+        psl: u16,
+        pel: u16,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.command(Command::PTLAR, Some(&address_range(psl, pel)))?;
+        self.state.partial_area = Some((psl, pel));
 
         Ok(self)
     }
 
     /// Display Inversion Off
-    pub fn inversion_off<'a>(&'a mut self) -> Result<&'a mut Self, Error<PinError, SpiError>> {
+    pub fn inversion_off(
+        &mut self,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
         self.command(Command::INVOFF, None)?;
+        self.state.inversion = Some(false);
 
         Ok(self)
     }
 
     /// Display Inversion On
-    pub fn inversion_on<'a>(&'a mut self) -> Result<&'a mut Self, Error<PinError, SpiError>> {
+    pub fn inversion_on(
+        &mut self,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
         self.command(Command::INVON, None)?;
+        self.state.inversion = Some(true);
 
         Ok(self)
     }
@@ -411,40 +1333,54 @@ where
     /// disabled and a blank page is inserted. This command does not change to the frame
     /// memory contents nor any other status. There will be no abnormal visible effect on the
     /// display.
-    pub fn display_off<'a>(&'a mut self) -> Result<&'a mut Self, Error<PinError, SpiError>> {
+    pub fn display_off(
+        &mut self,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
         self.command(Command::DISPOFF, None)?;
+        self.state.display_on = false;
 
         Ok(self)
     }
 
     /// The LCD enters DISPLAY ON mode. The output from the frame memory is enabled. This
     /// command does not change the frame memory content nor any other status.
-    pub fn display_on<'a>(&'a mut self) -> Result<&'a mut Self, Error<PinError, SpiError>> {
+    pub fn display_on(
+        &mut self,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
         self.command(Command::DISPON, None)?;
+        self.state.display_on = true;
 
         Ok(self)
     }
 
     /// Define read/write scanning direction of the frame memory.
-    pub fn memory_access_control<'a>(
-        &'a mut self,
+    pub fn memory_access_control(
+        &mut self,
         config: MemAccCtrlConfig,
-    ) -> Result<&'a mut Self, Error<PinError, SpiError>> {
-        self.command(Command::MADCTL, Some(&[config.value()]))?;
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        let madctl = config.value();
+        self.command(Command::MADCTL, Some(&[madctl]))?;
+        self.state.madctl = Some(madctl);
 
         Ok(self)
     }
 
     /// Idle mode off.
-    pub fn idle_off<'a>(&'a mut self) -> Result<&'a mut Self, Error<PinError, SpiError>> {
+    pub fn idle_off(
+        &mut self,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
         self.command(Command::IDMOFF, None)?;
+        self.state.idle = Some(false);
 
         Ok(self)
     }
 
     /// Idle mode on.
-    pub fn idle_on<'a>(&'a mut self) -> Result<&'a mut Self, Error<PinError, SpiError>> {
+    pub fn idle_on(
+        &mut self,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
         self.command(Command::IDMON, None)?;
+        self.state.idle = Some(true);
 
         Ok(self)
     }
@@ -452,26 +1388,25 @@ where
     /// Sets the column address window.
     /// Each value represents one column line in the frame memory.
     ///
-    /// `xs` must always be equal or less than `xe`. When `xs` or `xe` are greater than
-    /// the maximum address, all data outside the range will be ignored.
-    pub fn column_address<'a>(
-        &'a mut self,
+    /// `xs` must always be equal or less than `xe`, and `xe` must not be after the last column
+    /// of the frame memory: 239, or 319 with exchanged page and column order. Otherwise
+    /// `Error::InvalidColumnAddress` is returned.
+    pub fn column_address(
+        &mut self,
         xs: u16,
         xe: u16,
-    ) -> Result<&'a mut Self, Error<PinError, SpiError>> {
-        if xs > xe {
-            return Err(Error::InvalidColumnAddress);
-        }
-
-        self.command(
-            Command::CASET,
-            Some(&[
-                (xs >> 8) as u8,
-                (xs & 0xFF) as u8,
-                (xe >> 8) as u8,
-                (xe & 0xFF) as u8,
-            ]),
-        )?;
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        let (max, _) = self.frame_memory_max();
+        if !valid_range(xs, xe, max) {
+            return Err(Error::InvalidColumnAddress {
+                start: xs,
+                end: xe,
+                max,
+            });
+        }
+
+        self.command(Command::CASET, Some(&address_range(xs, xe)))?;
+        self.state.column_address = Some((xs, xe));
 
         Ok(self)
     }
@@ -479,57 +1414,155 @@ where
     /// Sets the row address window.
     /// Each value represents one page line in the frame memory.
     ///
-    /// `rs` must always be equal or greater than `re`. Data outside the addressable
-    /// space will be ignored.
-    pub fn row_address<'a>(
-        &'a mut self,
+    /// `rs` must always be equal or less than `re`, and `re` must not be after the last row of
+    /// the frame memory: 319, or 239 with exchanged page and column order. Otherwise
+    /// `Error::InvalidRowAddress` is returned.
+    pub fn row_address(
+        &mut self,
         rs: u16,
         re: u16,
-    ) -> Result<&'a mut Self, Error<PinError, SpiError>> {
-        if rs > re {
-            return Err(Error::InvalidRowAddress);
-        }
-
-        self.command(
-            Command::RASET,
-            Some(&[
-                (rs >> 8) as u8,
-                (rs & 0xFF) as u8,
-                (re >> 8) as u8,
-                (re & 0xFF) as u8,
-            ]),
-        )?;
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        let (_, max) = self.frame_memory_max();
+        if !valid_range(rs, re, max) {
+            return Err(Error::InvalidRowAddress {
+                start: rs,
+                end: re,
+                max,
+            });
+        }
+
+        self.command(Command::RASET, Some(&address_range(rs, re)))?;
+        self.state.row_address = Some((rs, re));
 
         Ok(self)
     }
 
     /// Sets the address window.
-    pub fn address_window<'a>(
-        &'a mut self,
+    ///
+    /// The coordinates are relative to the visible panel area, the panel offset configured
+    /// during init is added before the window is sent. The window has to lie within the panel
+    /// area, otherwise `Error::InvalidColumnAddress` or `Error::InvalidRowAddress` is returned
+    /// with the panel relative coordinates.
+    ///
+    /// With exchanged page and column order, `PageColumnOrder::ReverseMode`, columns run along
+    /// the panel height: the columns are checked against the panel height and get the row
+    /// offset, the rows are checked against the panel width and get the column offset.
+    pub fn address_window(
+        &mut self,
         xs: u16,
         rs: u16,
         xe: u16,
         re: u16,
-    ) -> Result<&'a mut Self, Error<PinError, SpiError>> {
-        self.column_address(xs, xe)?.row_address(rs, re)?;
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        let Panel {
+            width,
+            height,
+            x_offset,
+            y_offset,
+        } = self.view();
+        let max = width.saturating_sub(1);
+        if width == 0 || !valid_range(xs, xe, max) {
+            return Err(Error::InvalidColumnAddress {
+                start: xs,
+                end: xe,
+                max,
+            });
+        }
+        let max = height.saturating_sub(1);
+        if height == 0 || !valid_range(rs, re, max) {
+            return Err(Error::InvalidRowAddress {
+                start: rs,
+                end: re,
+                max,
+            });
+        }
+
+        self.column_address(xs.saturating_add(x_offset), xe.saturating_add(x_offset))?
+            .row_address(rs.saturating_add(y_offset), re.saturating_add(y_offset))?;
 
         Ok(self)
     }
 
+    /// Returns the panel geometry in column and row addresses. With exchanged page and
+    /// column order the columns run along the panel height, so width and height and the
+    /// offsets are swapped.
+    fn view(&self) -> Panel {
+        let panel = self.panel;
+        match self.madctl().get_page_column_order() {
+            PageColumnOrder::NormalMode => panel,
+            PageColumnOrder::ReverseMode => Panel {
+                width: panel.height,
+                height: panel.width,
+                x_offset: panel.y_offset,
+                y_offset: panel.x_offset,
+            },
+        }
+    }
+
+    /// Returns the last column and row address of the frame memory, which depend on the
+    /// page/column order.
+    fn frame_memory_max(&self) -> (u16, u16) {
+        match self.madctl().get_page_column_order() {
+            PageColumnOrder::NormalMode => (239, 319),
+            PageColumnOrder::ReverseMode => (319, 239),
+        }
+    }
+
     /// Performs a hard reset. The display has to be initialized afterwards.
-    pub fn hard_reset<'a, DELAY>(
-        &'a mut self,
+    ///
+    /// The reset line is released for [`Timings::reset_pulse_ms`], asserted for the same time
+    /// and released again, followed by a wait of [`Timings::reset_ms`]. The asserted level is
+    /// set with [`ST7789VConfig::reset_polarity`].
+    ///
+    /// [`Timings::reset_pulse_ms`]: struct.Timings.html#structfield.reset_pulse_ms
+    /// [`Timings::reset_ms`]: struct.Timings.html#structfield.reset_ms
+    /// [`ST7789VConfig::reset_polarity`]: struct.ST7789VConfig.html#method.reset_polarity
+    pub fn hard_reset<DELAY>(
+        &mut self,
         delay: &mut DELAY,
-    ) -> Result<&'a mut Self, Error<PinError, SpiError>>
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>>
     where
         DELAY: DelayMs<u16>,
     {
-        self.cfg.rst.set_high().map_err(Error::Pin)?;
-        delay.delay_ms(1);
-        self.cfg.rst.set_low().map_err(Error::Pin)?;
-        delay.delay_ms(1);
-        self.cfg.rst.set_high().map_err(Error::Pin)?;
-        delay.delay_ms(120);
+        self.hard_reset_waiting(delay)
+    }
+
+    /// Runs [`hard_reset`](#method.hard_reset) with the waits done by `wait`.
+    pub(crate) fn hard_reset_waiting<W: Wait<D>>(
+        &mut self,
+        wait: &mut W,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.cfg.set_reset(false).map_err(Error::Rst)?;
+        wait.wait_ms(&mut self.cfg.delay, self.timings.reset_pulse_ms);
+        self.cfg.set_reset(true).map_err(Error::Rst)?;
+        wait.wait_ms(&mut self.cfg.delay, self.timings.reset_pulse_ms);
+        self.cfg.set_reset(false).map_err(Error::Rst)?;
+        wait.wait_ms(&mut self.cfg.delay, self.timings.reset_ms);
+        self.state = State::after_reset();
+
+        Ok(self)
+    }
+
+    /// Performs a hard reset with microsecond resolution reset pulses.
+    ///
+    /// The reset pulse lasts [`Timings::reset_pulse_us`](struct.Timings.html#structfield.reset_pulse_us)
+    /// instead of whole milliseconds, the wait after releasing the reset line is still
+    /// [`Timings::reset_ms`](struct.Timings.html#structfield.reset_ms). The display has to be
+    /// initialized afterwards.
+    pub fn hard_reset_us<DELAY>(
+        &mut self,
+        delay: &mut DELAY,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>>
+    where
+        DELAY: DelayUs<u32> + DelayMs<u16>,
+    {
+        self.cfg.set_reset(false).map_err(Error::Rst)?;
+        delay.delay_us(self.timings.reset_pulse_us);
+        self.cfg.set_reset(true).map_err(Error::Rst)?;
+        delay.delay_us(self.timings.reset_pulse_us);
+        self.cfg.set_reset(false).map_err(Error::Rst)?;
+        delay.delay_ms(self.timings.reset_ms);
+        self.state = State::after_reset();
 
         Ok(self)
     }
@@ -538,75 +1571,399 @@ where
     ///
     /// Registers are written with their SW reset default values. Frame memory contens are
     /// unaffected by this command.
-    pub fn soft_reset<'a, DELAY>(
-        &'a mut self,
+    pub fn soft_reset<DELAY>(
+        &mut self,
         delay: &mut DELAY,
-    ) -> Result<&'a mut Self, Error<PinError, SpiError>>
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>>
     where
         DELAY: DelayMs<u16>,
     {
+        self.soft_reset_waiting(delay)
+    }
+
+    /// Runs [`soft_reset`](#method.soft_reset) with the waits done by `wait`.
+    pub(crate) fn soft_reset_waiting<W: Wait<D>>(
+        &mut self,
+        wait: &mut W,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
         self.command(Command::SWRESET, None)?;
-        delay.delay_ms(150);
+        wait.wait_ms(&mut self.cfg.delay, self.timings.soft_reset_ms);
+        self.state = State::after_reset();
 
         Ok(self)
     }
 
     /// Transfer data from MCU to the frame memory.
-    pub fn mem_write<'a>(&'a mut self, data: &[u8]) -> Result<&'a Self, Error<PinError, SpiError>> {
-        self.command(Command::RAMWR, Some(data))?;
+    ///
+    /// Returns [`Error::DisplayAsleep`](enum.Error.html#variant.DisplayAsleep) while the
+    /// display is in sleep mode.
+    pub fn mem_write(
+        &mut self,
+        data: &[u8],
+    ) -> Result<&Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.start_memory_write()?
+            .write_data(data)?
+            .end_transaction()?;
 
         Ok(self)
     }
 
-    /// Sets a single pixel to the given color
-    pub fn pixel<'a>(
-        &'a mut self,
+    /// Transfer data from MCU to the frame memory, also while the display is in sleep mode.
+    ///
+    /// The frame memory keeps the data during sleep, e.g. to prepare the first frame before
+    /// waking the display.
+    pub fn force_mem_write(
+        &mut self,
+        data: &[u8],
+    ) -> Result<&Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.write_command(Command::RAMWR)?
+            .write_data(data)?
+            .end_transaction()?;
+
+        Ok(self)
+    }
+
+    /// Sets a single pixel to the given RGB565 color
+    pub fn pixel(
+        &mut self,
         x: u16,
         y: u16,
         color: u16,
-    ) -> Result<&'a Self, Error<PinError, SpiError>> {
+    ) -> Result<&Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.check_pixel_format(PixelFormat::Rgb565)?;
         self.address_window(x, y, x, y)?;
         self.mem_write(&color.to_be_bytes())?;
 
         Ok(self)
     }
 
-    pub fn pixels<'a>(
-        &'a mut self,
+    /// Sets the pixels of a window to the given RGB565 colors
+    ///
+    /// Takes the colors as trait object, [`set_pixels`](#method.set_pixels) takes any iterable
+    /// and avoids the dynamic dispatch for every color.
+    pub fn pixels(
+        &mut self,
         xs: u16,
         ys: u16,
         xe: u16,
         ye: u16,
         colors: &mut dyn Iterator<Item = u16>,
-    ) -> Result<&'a mut Self, Error<PinError, SpiError>> {
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.set_pixels(xs, ys, xe, ye, colors)
+    }
+
+    /// Sets the pixels of the window from `xs`, `ys` to `xe`, `ye` to the given RGB565
+    /// colors.
+    ///
+    /// Takes anything iterating colors, e.g. an array, a slice iterator or a `map` chain. The
+    /// colors are collected in chunks of `BUF` bytes which are written as single transfers.
+    /// With the `graphics` feature, [`pixels_rgb`](#method.pixels_rgb) takes `Rgb565` colors.
+    ///
+    /// ```ignore
+    /// display.set_pixels(0, 0, 9, 0, (0..10).map(|i| i << 11))?;
+    /// ```
+    pub fn set_pixels<I>(
+        &mut self,
+        xs: u16,
+        ys: u16,
+        xe: u16,
+        ye: u16,
+        colors: I,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>>
+    where
+        I: IntoIterator<Item = u16>,
+    {
+        self.check_pixel_format(PixelFormat::Rgb565)?;
         self.address_window(xs, ys, xe, ye)?;
-        self.mem_write(&[])?;
+        self.start_memory_write()?.write_colors(colors)
+    }
 
-        for color in colors {
-            self.data(&color.to_be_bytes())?;
+    /// Sets the pixels of the window from `xs`, `ys` to `xe`, `ye` to the given RGB565 colors
+    /// like [`set_pixels`](#method.set_pixels) if there is exactly one color for each pixel.
+    ///
+    /// The length of `colors` is checked before anything is written. A wrong count, e.g. after
+    /// an off by one in the width, would otherwise shear the rest of the image without an
+    /// error. [`set_pixels_checked`](#method.set_pixels_checked) takes colors of unknown length.
+    ///
+    /// Returns `Error::PixelCountMismatch` if `colors` does not hold exactly one color for each
+    /// pixel of the window.
+    pub fn pixels_exact<I>(
+        &mut self,
+        xs: u16,
+        ys: u16,
+        xe: u16,
+        ye: u16,
+        colors: I,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>>
+    where
+        I: IntoIterator<Item = u16>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let colors = colors.into_iter();
+        let expected = window_len(xs, ys, xe, ye);
+        if colors.len() != expected {
+            return Err(Error::PixelCountMismatch {
+                expected: u32::try_from(expected).unwrap_or(u32::MAX),
+                written: u32::try_from(colors.len()).unwrap_or(u32::MAX),
+            });
+        }
+
+        self.set_pixels(xs, ys, xe, ye, colors)
+    }
+
+    /// Sets the pixels of the window from `xs`, `ys` to `xe`, `ye` to the given RGB565 colors
+    /// like [`set_pixels`](#method.set_pixels) and checks their count while they are written.
+    ///
+    /// Colors beyond the window are not written. Returns `Error::PixelCountMismatch` after
+    /// the memory write if `colors` ended early or holds more colors than the window, in the
+    /// latter case `written` is one more than the window size.
+    pub fn set_pixels_checked<I>(
+        &mut self,
+        xs: u16,
+        ys: u16,
+        xe: u16,
+        ye: u16,
+        colors: I,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>>
+    where
+        I: IntoIterator<Item = u16>,
+    {
+        let mut colors = colors.into_iter();
+        let expected = window_len(xs, ys, xe, ye);
+        let mut written = 0;
+        self.set_pixels(
+            xs,
+            ys,
+            xe,
+            ye,
+            colors.by_ref().take(expected).inspect(|_| written += 1),
+        )?;
+        if colors.next().is_some() {
+            written += 1;
+        }
+        if written != expected {
+            return Err(Error::PixelCountMismatch {
+                expected: u32::try_from(expected).unwrap_or(u32::MAX),
+                written: u32::try_from(written).unwrap_or(u32::MAX),
+            });
         }
 
         Ok(self)
     }
 
-    fn command<'a>(
-        &'a mut self,
+    /// Checks that pixel data in `pixel_format` matches the configured color format.
+    ///
+    /// Any pixel format is accepted as long as the color format was not set.
+    fn check_pixel_format(
+        &self,
+        pixel_format: PixelFormat,
+    ) -> Result<(), Error<PinError, SpiError, CS::Error, RST::Error>> {
+        match self.state.color_format {
+            Some(color_format) if color_format.pixel_format() != pixel_format => {
+                Err(Error::PixelFormatMismatch)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Fills the visible panel area with the RGB565 `color`.
+    pub fn clear(
+        &mut self,
+        color: u16,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.check_pixel_format(PixelFormat::Rgb565)?;
+        let (width, height) = self.size();
+        let count = usize::from(width) * usize::from(height);
+        self.address_window(0, 0, width.saturating_sub(1), height.saturating_sub(1))?
+            .start_memory_write()?
+            .write_colors(core::iter::repeat(color).take(count))
+    }
+
+    /// Writes RGB565 colors MSB first, up to `BUF` bytes per SPI transfer.
+    fn write_colors<I>(
+        &mut self,
+        colors: I,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>>
+    where
+        I: IntoIterator<Item = u16>,
+    {
+        self.write_colors_with_progress(colors, 0, |_, _| ControlFlow::Continue(()))
+    }
+
+    /// Writes RGB565 colors like [`write_colors`](#method.write_colors) and calls `progress`
+    /// with the number of written colors and `total` after every transfer.
+    ///
+    /// The transaction is ended and `Error::Aborted` returned if `progress` breaks before all
+    /// `total` colors are written.
+    fn write_colors_with_progress<I, P>(
+        &mut self,
+        colors: I,
+        total: usize,
+        mut progress: P,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>>
+    where
+        I: IntoIterator<Item = u16>,
+        P: FnMut(usize, usize) -> ControlFlow<()>,
+    {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::CHUNK_SIZE_CHECK;
+
+        let mut buffer = [0u8; BUF];
+        let mut len = 0;
+        let mut written = 0;
+        for color in colors {
+            buffer[len..len + 2].copy_from_slice(&color.to_be_bytes());
+            len += 2;
+            if len + 2 > BUF {
+                self.write_data(&buffer[..len])?;
+                written += len / 2;
+                len = 0;
+                if progress(written, total).is_break() && written < total {
+                    self.end_transaction()?;
+                    return Err(Error::Aborted);
+                }
+            }
+        }
+
+        if len > 0 {
+            self.write_data(&buffer[..len])?;
+            // nothing is left to abort
+            let _ = progress(written + len / 2, total);
+        }
+
+        self.end_transaction()
+    }
+
+    /// Sends RAMWR, the following data is written to the frame memory.
+    ///
+    /// Returns [`Error::DisplayAsleep`](enum.Error.html#variant.DisplayAsleep) while the
+    /// display is in sleep mode.
+    fn start_memory_write(
+        &mut self,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        if self.state.asleep {
+            return Err(Error::DisplayAsleep);
+        }
+
+        self.write_command(Command::RAMWR)
+    }
+
+    /// Sends a command followed by its parameters.
+    fn command(
+        &mut self,
         cmd: Command,
         params: Option<&[u8]>,
-    ) -> Result<&'a mut Self, Error<PinError, SpiError>> {
-        self.cfg.dc.set_low().map_err(Error::Pin)?;
-        self.spi.write(&[cmd.value()]).map_err(Error::Spi)?;
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.open_command(cmd, params.unwrap_or(&[]))?
+            .end_transaction()
+    }
 
-        if let Some(params) = params {
-            self.data(params)?;
+    /// Sends a command followed by its parameters and keeps the transaction open for a
+    /// response.
+    fn open_command(
+        &mut self,
+        cmd: Command,
+        params: &[u8],
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        if self.state.command2_locked && cmd.is_command2() {
+            return Err(Error::Command2Locked);
         }
 
+        cmd.debug_check_parameters(params.len());
+        self.observer.on_command(cmd.value(), params.len());
+        self.send_command(cmd)?.write_data(params)
+    }
+
+    /// Sends a command byte without parameters. The transaction stays open for the data
+    /// following the command, the caller ends it.
+    fn write_command(
+        &mut self,
+        cmd: Command,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.observer.on_command(cmd.value(), 0);
+        self.send_command(cmd)
+    }
+
+    /// Sends a command byte in a new transaction, the caller reports it to the observer.
+    fn send_command(
+        &mut self,
+        cmd: Command,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.end_transaction()?.select()?;
+        self.set_dc(false)?;
+        self.write_command_byte(cmd)?;
+
         Ok(self)
     }
 
-    fn data<'a>(&'a mut self, data: &[u8]) -> Result<&'a mut Self, Error<PinError, SpiError>> {
-        self.cfg.dc.set_high().map_err(Error::Pin)?;
-        self.spi.write(data).map_err(Error::Spi)?;
+    /// Sends data bytes. Nothing is sent for empty data.
+    fn write_data(
+        &mut self,
+        data: &[u8],
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        if data.is_empty() {
+            return Ok(self);
+        }
+
+        self.observer.on_data(data.len());
+        self.write_stream(data)?;
+
         Ok(self)
     }
+
+    /// Lowers the chip select pin at the start of a transaction, see
+    /// [`with_cs_per_transaction`](#method.with_cs_per_transaction). The data/command pin
+    /// level is unknown as other devices may have used the bus since the last transaction.
+    fn select(&mut self) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        if !self.cs_per_transaction || self.cs_selected {
+            return Ok(self);
+        }
+
+        if let Some(cs) = self.cfg.cs.as_mut() {
+            cs.set_low().map_err(Error::Cs)?;
+        }
+        self.cs_selected = true;
+        self.dc_level = None;
+
+        Ok(self)
+    }
+
+    /// Raises the chip select pin at the end of a transaction, see
+    /// [`with_cs_per_transaction`](#method.with_cs_per_transaction).
+    pub(crate) fn end_transaction(
+        &mut self,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        if !self.cs_selected {
+            return Ok(self);
+        }
+
+        if let Some(cs) = self.cfg.cs.as_mut() {
+            cs.set_high().map_err(Error::Cs)?;
+        }
+        self.cs_selected = false;
+
+        Ok(self)
+    }
+
+    /// Sets the data/command pin high for data or low for commands. Unless the pin is shared,
+    /// it is only written when its level changes.
+    fn set_dc(
+        &mut self,
+        high: bool,
+    ) -> Result<(), Error<PinError, SpiError, CS::Error, RST::Error>> {
+        if !self.dc_shared && self.dc_level == Some(high) {
+            return Ok(());
+        }
+
+        self.dc_level = None;
+        self.observer.on_dc_toggle();
+        if high {
+            self.cfg.dc.set_high().map_err(Error::Dc)?;
+        } else {
+            self.cfg.dc.set_low().map_err(Error::Dc)?;
+        }
+        self.dc_level = Some(high);
+
+        Ok(())
+    }
 }
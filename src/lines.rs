@@ -0,0 +1,84 @@
+use core::convert::TryFrom;
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::command::Command;
+use crate::{Error, PixelFormat, TransferObserver, ST7789V};
+
+/// Longest line `render_by_lines` can render, the frame memory height which is the width
+/// with exchanged page and column order.
+const MAX_LINE_LEN: usize = 320;
+
+/// Line by line rendering for renderers without a frame buffer
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Writes the RGB565 `pixels` to row `y` starting at column `x0`.
+    ///
+    /// The colors are sent MSB first like in [`pixels`](#method.pixels).
+    pub fn write_line(
+        &mut self,
+        y: u16,
+        x0: u16,
+        pixels: &[u16],
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        if pixels.is_empty() {
+            return Ok(self);
+        }
+
+        self.check_pixel_format(PixelFormat::Rgb565)?;
+        let xe = u16::try_from(usize::from(x0) + pixels.len() - 1).unwrap_or(u16::MAX);
+        self.address_window(x0, y, xe, y)?;
+        self.start_memory_write()?;
+        self.write_colors(pixels.iter().copied())
+    }
+
+    /// Renders the window from `x0`, `y0` to `x1`, `y1` line by line.
+    ///
+    /// The address window is set once, then `render` is called for every row with its `y`
+    /// coordinate and a buffer for the RGB565 colors of the row, which is streamed to the
+    /// display before the next row is rendered. The window is validated like in
+    /// [`address_window`](#method.address_window).
+    pub fn render_by_lines<F>(
+        &mut self,
+        x0: u16,
+        y0: u16,
+        x1: u16,
+        y1: u16,
+        mut render: F,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>>
+    where
+        F: FnMut(u16, &mut [u16]),
+    {
+        if self.state.asleep {
+            return Err(Error::DisplayAsleep);
+        }
+
+        self.check_pixel_format(PixelFormat::Rgb565)?;
+        // The window fits into the frame memory, so its rows into the line buffer
+        self.address_window(x0, y0, x1, y1)?;
+
+        let mut buffer = [0u16; MAX_LINE_LEN];
+        let line = &mut buffer[..usize::from(x1 - x0) + 1];
+        for y in y0..=y1 {
+            render(y, line);
+
+            let cmd = if y == y0 {
+                Command::RAMWR
+            } else {
+                Command::RAMWRC
+            };
+            self.write_command(cmd)?;
+            self.write_colors(line.iter().copied())?;
+        }
+
+        Ok(self)
+    }
+}
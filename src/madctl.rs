@@ -0,0 +1,76 @@
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{
+    ColorOrder, ColumnAddressOrder, Error, LatchOrder, LineAddressOrder, MemAccCtrlConfig,
+    PageAddressOrder, PageColumnOrder, TransferObserver, ST7789V,
+};
+
+/// Read-modify-write of single memory data access control fields
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Returns the memory data access control config last written to the controller, or the
+    /// reset default if MADCTL was not written since the driver was created or the display
+    /// was reset.
+    pub fn madctl(&self) -> MemAccCtrlConfig {
+        self.state
+            .madctl
+            .map(MemAccCtrlConfig::from_value)
+            .unwrap_or_default()
+    }
+
+    /// Sets the color order and resends MADCTL, the other fields are kept.
+    pub fn set_color_order(
+        &mut self,
+        color_order: ColorOrder,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.memory_access_control(self.madctl().color_order(color_order))
+    }
+
+    /// Sets the display data latch order and resends MADCTL, the other fields are kept.
+    pub fn set_latch_order(
+        &mut self,
+        latch_order: LatchOrder,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.memory_access_control(self.madctl().latch_order(latch_order))
+    }
+
+    /// Sets the line address order and resends MADCTL, the other fields are kept.
+    pub fn set_line_order(
+        &mut self,
+        line_order: LineAddressOrder,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.memory_access_control(self.madctl().line_order(line_order))
+    }
+
+    /// Sets the page address order and resends MADCTL, the other fields are kept.
+    pub fn set_page_order(
+        &mut self,
+        page_order: PageAddressOrder,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.memory_access_control(self.madctl().page_order(page_order))
+    }
+
+    /// Sets the page/column order and resends MADCTL, the other fields are kept.
+    pub fn set_page_column_order(
+        &mut self,
+        page_column_order: PageColumnOrder,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.memory_access_control(self.madctl().page_column_order(page_column_order))
+    }
+
+    /// Sets the column address order and resends MADCTL, the other fields are kept.
+    pub fn set_column_order(
+        &mut self,
+        column_order: ColumnAddressOrder,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.memory_access_control(self.madctl().column_order(column_order))
+    }
+}
@@ -0,0 +1,210 @@
+#![deny(clippy::cast_sign_loss)]
+
+use embedded_graphics::drawable::Pixel;
+use embedded_graphics::image::{Image, ImageDimensions, IntoPixelIter};
+use embedded_graphics::pixelcolor::raw::{RawData, RawU16};
+use embedded_graphics::pixelcolor::{BinaryColor, Rgb565};
+use embedded_graphics::prelude::{DrawTarget, Size};
+use embedded_graphics::primitives::{Circle, Line, Rectangle, Triangle};
+use embedded_graphics::style::{PrimitiveStyle, PrimitiveStyleBuilder, Styled};
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{Error, TransferObserver, ST7789V};
+
+/// Draw target for 1 bit per pixel graphics
+///
+/// Created by [`ST7789V::monochrome`]. [`BinaryColor::On`] is drawn in the foreground and
+/// [`BinaryColor::Off`] in the background color. Primitives and images are drawn with the
+/// same window based paths as their `Rgb565` counterparts, e.g. a filled rectangle takes a
+/// single memory write.
+///
+/// In [`transparent`](#method.transparent) mode `Off` pixels are skipped, so icons and text
+/// can be drawn on top of existing content.
+///
+/// ```ignore
+/// let style = TextStyle::new(Font6x8, BinaryColor::On);
+/// Text::new("Hello", Point::new(10, 10))
+///     .into_styled(style)
+///     .draw(&mut display.monochrome(Rgb565::WHITE, Rgb565::BLACK))?;
+/// ```
+///
+/// [`ST7789V::monochrome`]: struct.ST7789V.html#method.monochrome
+/// [`BinaryColor::On`]: https://docs.rs/embedded-graphics/0.6/embedded_graphics/pixelcolor/enum.BinaryColor.html
+/// [`BinaryColor::Off`]: https://docs.rs/embedded-graphics/0.6/embedded_graphics/pixelcolor/enum.BinaryColor.html
+pub struct Monochrome<'a, SPI, CS, DC, RST, D = (), O = (), const BUF: usize = 128>
+where
+    SPI: spi::Write<u8>,
+    CS: OutputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    display: &'a mut ST7789V<SPI, CS, DC, RST, D, O, BUF>,
+    fg: Rgb565,
+    bg: Rgb565,
+    transparent: bool,
+}
+
+impl<'a, SPI, CS, DC, RST, D, O, const BUF: usize> Monochrome<'a, SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8>,
+    CS: OutputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// Skips `Off` pixels instead of drawing them in the background color.
+    pub fn transparent(mut self) -> Self {
+        self.transparent = true;
+        self
+    }
+
+    /// Maps a binary color to its RGB565 color, `None` for transparent pixels.
+    fn color(&self, color: BinaryColor) -> Option<Rgb565> {
+        match color {
+            BinaryColor::On => Some(self.fg),
+            BinaryColor::Off if self.transparent => None,
+            BinaryColor::Off => Some(self.bg),
+        }
+    }
+
+    /// Maps the colors of a primitive style.
+    fn style(&self, style: &PrimitiveStyle<BinaryColor>) -> PrimitiveStyle<Rgb565> {
+        let mut builder = PrimitiveStyleBuilder::new().stroke_width(style.stroke_width);
+        if let Some(color) = style.fill_color.and_then(|c| self.color(c)) {
+            builder = builder.fill_color(color);
+        }
+        if let Some(color) = style.stroke_color.and_then(|c| self.color(c)) {
+            builder = builder.stroke_color(color);
+        }
+
+        builder.build()
+    }
+}
+
+impl<'a, SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize> DrawTarget<BinaryColor>
+    for Monochrome<'a, SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    type Error = Error<PinError, SpiError, CS::Error, RST::Error>;
+
+    fn draw_pixel(&mut self, pixel: Pixel<BinaryColor>) -> Result<(), Self::Error> {
+        match self.color(pixel.1) {
+            Some(color) => self.display.draw_pixel(Pixel(pixel.0, color)),
+            None => Ok(()),
+        }
+    }
+
+    fn draw_iter<T>(&mut self, item: T) -> Result<(), Self::Error>
+    where
+        T: IntoIterator<Item = Pixel<BinaryColor>>,
+    {
+        let (fg, bg, transparent) = (self.fg, self.bg, self.transparent);
+        let pixels = item
+            .into_iter()
+            .filter_map(move |Pixel(point, color)| match color {
+                BinaryColor::On => Some(Pixel(point, fg)),
+                BinaryColor::Off if transparent => None,
+                BinaryColor::Off => Some(Pixel(point, bg)),
+            });
+
+        self.display.draw_iter(pixels)
+    }
+
+    fn draw_line(
+        &mut self,
+        item: &Styled<Line, PrimitiveStyle<BinaryColor>>,
+    ) -> Result<(), Self::Error> {
+        self.display
+            .draw_line(&Styled::new(item.primitive, self.style(&item.style)))
+    }
+
+    fn draw_triangle(
+        &mut self,
+        item: &Styled<Triangle, PrimitiveStyle<BinaryColor>>,
+    ) -> Result<(), Self::Error> {
+        self.display
+            .draw_triangle(&Styled::new(item.primitive, self.style(&item.style)))
+    }
+
+    fn draw_rectangle(
+        &mut self,
+        item: &Styled<Rectangle, PrimitiveStyle<BinaryColor>>,
+    ) -> Result<(), Self::Error> {
+        self.display
+            .draw_rectangle(&Styled::new(item.primitive, self.style(&item.style)))
+    }
+
+    fn draw_circle(
+        &mut self,
+        item: &Styled<Circle, PrimitiveStyle<BinaryColor>>,
+    ) -> Result<(), Self::Error> {
+        self.display
+            .draw_circle(&Styled::new(item.primitive, self.style(&item.style)))
+    }
+
+    /// Opaque images are written with a single memory write, transparent ones in runs of
+    /// `On` pixels.
+    fn draw_image<'b, 'c, I>(
+        &mut self,
+        item: &'b Image<'c, I, BinaryColor>,
+    ) -> Result<(), Self::Error>
+    where
+        &'c I: IntoPixelIter<BinaryColor>,
+        I: ImageDimensions,
+    {
+        if self.transparent {
+            return self.draw_iter(item);
+        }
+
+        // clipped images are drawn in runs
+        let (sx, sy, ex, ey) = match self.display.image_window(item) {
+            Some(window) => window,
+            None => return self.draw_iter(item),
+        };
+        let fg = RawU16::from(self.fg).into_inner();
+        let bg = RawU16::from(self.bg).into_inner();
+        let colors = item.into_iter().map(|p| match p.1 {
+            BinaryColor::On => fg,
+            BinaryColor::Off => bg,
+        });
+
+        self.display.set_pixels_checked(sx, sy, ex, ey, colors)?;
+
+        Ok(())
+    }
+
+    fn size(&self) -> Size {
+        DrawTarget::<Rgb565>::size(self.display)
+    }
+}
+
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Returns a draw target for `BinaryColor` graphics drawing `On` in `fg` and `Off` in
+    /// `bg`, see [`Monochrome`](struct.Monochrome.html).
+    pub fn monochrome(
+        &mut self,
+        fg: Rgb565,
+        bg: Rgb565,
+    ) -> Monochrome<'_, SPI, CS, DC, RST, D, O, BUF> {
+        Monochrome {
+            display: self,
+            fg,
+            bg,
+            transparent: false,
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use core::convert::Infallible;
+use core::fmt;
+use core::marker::PhantomData;
+
+use embedded_hal::digital::v2::OutputPin;
+
+/// Output pin which does nothing, used in place of an unconnected pin
+///
+/// Drivers created without a chip select pin, e.g. by [`ST7789V::new`], use it as their `CS`
+/// type. Its error type `E` never occurs, [`new`](#method.new) creates a pin with the error
+/// type `Infallible`, use `NoPin::<E>::default()` for another one.
+///
+/// [`ST7789V::new`]: struct.ST7789V.html#method.new
+pub struct NoPin<E = Infallible> {
+    _error: PhantomData<E>,
+}
+
+impl NoPin {
+    /// Creates a new dummy pin
+    pub const fn new() -> Self {
+        NoPin {
+            _error: PhantomData,
+        }
+    }
+}
+
+impl<E> Default for NoPin<E> {
+    fn default() -> Self {
+        NoPin {
+            _error: PhantomData,
+        }
+    }
+}
+
+impl<E> fmt::Debug for NoPin<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("NoPin")
+    }
+}
+
+impl<E> OutputPin for NoPin<E> {
+    type Error = E;
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
@@ -0,0 +1,65 @@
+use embedded_hal::blocking::delay::DelayMs;
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::command::Command;
+use crate::{Error, TransferObserver, ST7789V};
+
+/// PROMEN key enabling program mode
+const PROMEN_KEY: [u8; 4] = [0x5A, 0x69, 0xEE, 0x04];
+
+/// PROMACT key starting the programming of the NVM cell set by NVMSET
+const PROMACT_KEY: [u8; 2] = [0x29, 0xA5];
+
+/// Time to wait for the programming of one NVM cell in milliseconds
+const PROGRAM_MS: u16 = 100;
+
+/// Acknowledgement that NVM programming is irreversible
+///
+/// The NVM of the ST7789V is one-time programmable, a value burnt into it can not be changed
+/// or erased again. [`nvm_write`](struct.ST7789V.html#method.nvm_write) only runs with this
+/// token.
+#[derive(Clone, Copy, Debug)]
+pub struct OneTimeProgrammable(());
+
+impl OneTimeProgrammable {
+    /// Acknowledges that NVM writes can not be undone.
+    pub fn i_understand_this_is_one_time_programmable() -> Self {
+        OneTimeProgrammable(())
+    }
+}
+
+/// NVM programming
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Burns `value` into the NVM cell at `address`.
+    ///
+    /// Enables program mode (PROMEN), selects the cell and value (NVMSET), starts the
+    /// programming (PROMACT) and waits 100 ms for it to finish. The programming voltage has to
+    /// be applied to the VPP pin of the module, which most breakout boards do not expose. The
+    /// NVM cells and their addresses are listed in the datasheet.
+    pub fn nvm_write<DELAY>(
+        &mut self,
+        address: u8,
+        value: u8,
+        _otp: OneTimeProgrammable,
+        delay: &mut DELAY,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>>
+    where
+        DELAY: DelayMs<u16>,
+    {
+        self.command(Command::PROMEN, Some(&PROMEN_KEY))?
+            .command(Command::NVMSET, Some(&[address, value]))?
+            .command(Command::PROMACT, Some(&PROMACT_KEY))?;
+        delay.delay_ms(PROGRAM_MS);
+
+        Ok(self)
+    }
+}
@@ -0,0 +1,73 @@
+use core::convert::TryFrom;
+
+/// Hooks called by the driver for every transfer on the bus
+///
+/// Installed with [`ST7789V::with_observer`], e.g. to find out what a screen update costs.
+/// All methods do nothing by default. The default observer `()` implements none of them, so
+/// the calls are compiled away when no observer is installed.
+///
+/// [`ST7789V::with_observer`]: struct.ST7789V.html#method.with_observer
+pub trait TransferObserver {
+    /// Called for every command byte sent, `params` is the number of parameter bytes that
+    /// follow it.
+    ///
+    /// Memory writes report no parameters, the pixel data is reported by
+    /// [`on_data`](#method.on_data).
+    fn on_command(&mut self, _cmd: u8, _params: usize) {}
+
+    /// Called for every data transfer with its length in bytes, including command parameters
+    /// and data read from the display.
+    fn on_data(&mut self, _len: usize) {}
+
+    /// Called whenever the data/command pin is written.
+    fn on_dc_toggle(&mut self) {}
+}
+
+impl TransferObserver for () {}
+
+/// Observer counting commands, data bytes and data/command pin writes
+///
+/// ```ignore
+/// let mut display = display.with_observer(CountingObserver::new());
+/// display.clear(0x0000)?;
+/// let cost = display.observer_mut().take();
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CountingObserver {
+    /// Command bytes sent
+    pub commands: u32,
+    /// Data bytes transferred
+    pub data_bytes: u32,
+    /// Data transfers
+    pub data_transfers: u32,
+    /// Data/command pin writes
+    pub dc_toggles: u32,
+}
+
+impl CountingObserver {
+    /// Creates an observer with all counts at zero.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the counts and resets them to zero.
+    pub fn take(&mut self) -> Self {
+        core::mem::take(self)
+    }
+}
+
+impl TransferObserver for CountingObserver {
+    fn on_command(&mut self, _cmd: u8, _params: usize) {
+        self.commands = self.commands.saturating_add(1);
+    }
+
+    fn on_data(&mut self, len: usize) {
+        let len = u32::try_from(len).unwrap_or(u32::MAX);
+        self.data_bytes = self.data_bytes.saturating_add(len);
+        self.data_transfers = self.data_transfers.saturating_add(1);
+    }
+
+    fn on_dc_toggle(&mut self) {
+        self.dc_toggles = self.dc_toggles.saturating_add(1);
+    }
+}
@@ -0,0 +1,116 @@
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+/// 8 bit data bus of the parallel interface
+///
+/// Implemented for arrays of eight output pins, `D0` first. HALs which can write a whole port
+/// at once can implement it for their port type, which is considerably faster than setting
+/// eight pins one by one.
+pub trait OutputBus {
+    /// Bus error
+    type Error;
+
+    /// Drives `byte` onto the data lines.
+    fn write_byte(&mut self, byte: u8) -> Result<(), Self::Error>;
+}
+
+impl<P> OutputBus for [P; 8]
+where
+    P: OutputPin,
+{
+    type Error = P::Error;
+
+    fn write_byte(&mut self, byte: u8) -> Result<(), Self::Error> {
+        for (bit, pin) in self.iter_mut().enumerate() {
+            if byte & (1 << bit) != 0 {
+                pin.set_high()?;
+            } else {
+                pin.set_low()?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Errors of a [`Parallel8080`](struct.Parallel8080.html) write
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParallelError<BusError, PinError> {
+    /// Data bus error
+    Bus(BusError),
+    /// Write strobe pin error
+    Wr(PinError),
+}
+
+/// 8 bit 8080 parallel interface
+///
+/// Stands in for the SPI bus of the driver, so every driver function works over the parallel
+/// interface as well. Each byte is put on the data bus and latched by a rising edge of the
+/// `WR` strobe, chip select and data/command pin are driven by the driver as for SPI:
+///
+/// ```ignore
+/// let bus = Parallel8080::new([d0, d1, d2, d3, d4, d5, d6, d7], wr)?;
+/// let mut display = ST7789V::with_cs(bus, cs, dc, rst)?;
+/// display.init(&mut delay)?;
+/// ```
+///
+/// The module has to be strapped to the 8 bit 8080 interface (IM pins). The read strobe is not
+/// driven, tie `RD` high. Frame memory and register reads are not supported.
+pub struct Parallel8080<BUS, WR> {
+    bus: BUS,
+    wr: WR,
+}
+
+impl<BUS, WR> Parallel8080<BUS, WR>
+where
+    BUS: OutputBus,
+    WR: OutputPin,
+{
+    /// Creates the interface from the data bus and the write strobe pin, which is raised
+    /// right away.
+    pub fn new(bus: BUS, mut wr: WR) -> Result<Self, ParallelError<BUS::Error, WR::Error>> {
+        wr.set_high().map_err(ParallelError::Wr)?;
+
+        Ok(Parallel8080 { bus, wr })
+    }
+
+    /// Releases the data bus and the write strobe pin.
+    pub fn release(self) -> (BUS, WR) {
+        (self.bus, self.wr)
+    }
+
+    /// Writes a single byte.
+    fn write_byte(&mut self, byte: u8) -> Result<(), ParallelError<BUS::Error, WR::Error>> {
+        self.wr.set_low().map_err(ParallelError::Wr)?;
+        self.bus.write_byte(byte).map_err(ParallelError::Bus)?;
+        self.wr.set_high().map_err(ParallelError::Wr)
+    }
+}
+
+impl<BUS, WR> spi::Write<u8> for Parallel8080<BUS, WR>
+where
+    BUS: OutputBus,
+    WR: OutputPin,
+{
+    type Error = ParallelError<BUS::Error, WR::Error>;
+
+    fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+        words.iter().try_for_each(|&byte| self.write_byte(byte))
+    }
+}
+
+/// 16 bit words are written MSB first, so the pixel writes for 16 bit SPI frames work as well.
+impl<BUS, WR> spi::Write<u16> for Parallel8080<BUS, WR>
+where
+    BUS: OutputBus,
+    WR: OutputPin,
+{
+    type Error = ParallelError<BUS::Error, WR::Error>;
+
+    fn write(&mut self, words: &[u16]) -> Result<(), Self::Error> {
+        words
+            .iter()
+            .flat_map(|word| word.to_be_bytes())
+            .try_for_each(|byte| self.write_byte(byte))
+    }
+}
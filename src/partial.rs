@@ -0,0 +1,95 @@
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::command::Command;
+use crate::{Error, TransferObserver, ST7789V};
+
+/// Source output level in the non-display area of partial mode (NDL)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NdsOutput {
+    /// V63, the reset default
+    V63 = 0b0000_0000,
+    /// V0
+    V0 = 0b1000_0000,
+}
+
+impl NdsOutput {
+    pub fn value(self) -> u8 {
+        self as u8
+    }
+}
+
+/// Interval scan of the non-display area in partial mode (ISC)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IntervalScan {
+    /// The non-display area is not scanned
+    Off = 0x00,
+    /// The non-display area is scanned every 3 frames
+    Frames3 = 0x01,
+    /// The non-display area is scanned every 5 frames
+    Frames5 = 0x02,
+    /// The non-display area is scanned every 7 frames
+    Frames7 = 0x03,
+    /// The non-display area is scanned every 9 frames
+    Frames9 = 0x04,
+    /// The non-display area is scanned every 11 frames
+    Frames11 = 0x05,
+    /// The non-display area is scanned every 13 frames
+    Frames13 = 0x06,
+    /// The non-display area is scanned every 15 frames
+    Frames15 = 0x07,
+    /// The non-display area is scanned every 17 frames
+    Frames17 = 0x08,
+    /// The non-display area is scanned every 19 frames
+    Frames19 = 0x09,
+    /// The non-display area is scanned every 21 frames
+    Frames21 = 0x0A,
+    /// The non-display area is scanned every 23 frames
+    Frames23 = 0x0B,
+    /// The non-display area is scanned every 25 frames
+    Frames25 = 0x0C,
+    /// The non-display area is scanned every 27 frames
+    Frames27 = 0x0D,
+    /// The non-display area is scanned every 29 frames
+    Frames29 = 0x0E,
+    /// The non-display area is scanned every 31 frames
+    Frames31 = 0x0F,
+}
+
+impl IntervalScan {
+    pub fn value(self) -> u8 {
+        self as u8
+    }
+}
+
+/// Partial mode control
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Sets how the non-display area is driven in partial mode (PARCTRL).
+    ///
+    /// The non-display area is every row outside the partial area set by
+    /// [`partial_area`](#method.partial_area). Scanning it only every few frames saves power,
+    /// the source outputs are held at `non_display_source_output` in between. The setting can
+    /// be changed before or after entering partial mode with
+    /// [`partial_display_mode`](#method.partial_display_mode), it has no effect in normal
+    /// mode.
+    pub fn set_partial_control(
+        &mut self,
+        non_display_source_output: NdsOutput,
+        interval: IntervalScan,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.command(
+            Command::PARCTRL,
+            Some(&[non_display_source_output.value() | interval.value()]),
+        )?;
+
+        Ok(self)
+    }
+}
@@ -0,0 +1,156 @@
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::color::{BLACK, BLUE, CYAN, GREEN, MAGENTA, RED, WHITE, YELLOW};
+use crate::{Error, TransferObserver, ST7789V};
+
+/// Colors of the color bars from left to right: white, yellow, cyan, green, magenta, red,
+/// blue and black
+const COLOR_BARS: [u16; 8] = [WHITE, YELLOW, CYAN, GREEN, MAGENTA, RED, BLUE, BLACK];
+
+/// Direction of a gradient
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GradientDirection {
+    /// From the left to the right edge
+    Horizontal,
+    /// From the top to the bottom edge
+    Vertical,
+}
+
+/// Test patterns
+///
+/// Every pattern covers the visible panel area set by the init options, so wrong sizes or
+/// offsets show up as cut off or shifted patterns. Each pattern is written as a single window,
+/// the color bars as one window per bar.
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Draws eight vertical color bars: white, yellow, cyan, green, magenta, red, blue and
+    /// black.
+    ///
+    /// The bars have the same width, the last one takes the remaining columns.
+    pub fn draw_color_bars(
+        &mut self,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        let (width, height) = self.size();
+        let bar_width = width / COLOR_BARS.len() as u16;
+        if bar_width == 0 || height == 0 {
+            return Err(Error::InvalidParameter);
+        }
+
+        for (i, &color) in COLOR_BARS.iter().enumerate() {
+            let x = i as u16 * bar_width;
+            let bar_width = if i == COLOR_BARS.len() - 1 {
+                width - x
+            } else {
+                bar_width
+            };
+            let count = usize::from(bar_width) * usize::from(height);
+            self.set_pixels(
+                x,
+                0,
+                x + bar_width - 1,
+                height - 1,
+                core::iter::repeat(color).take(count),
+            )?;
+        }
+
+        Ok(self)
+    }
+
+    /// Draws a gradient from the RGB565 color `from` at one edge to `to` at the opposite edge.
+    ///
+    /// Each color component is interpolated on its own and rounded to the nearest value, so
+    /// the steps between neighboring values are spread evenly across the panel.
+    pub fn draw_gradient(
+        &mut self,
+        direction: GradientDirection,
+        from: u16,
+        to: u16,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        let (width, height) = self.size();
+        let steps = match direction {
+            GradientDirection::Horizontal => width,
+            GradientDirection::Vertical => height,
+        };
+
+        self.write_pixels_from_fn(0, 0, width, height, |x, y| {
+            let position = match direction {
+                GradientDirection::Horizontal => x,
+                GradientDirection::Vertical => y,
+            };
+            interpolate_rgb565(from, to, position, steps)
+        })
+    }
+
+    /// Draws a checkerboard of `cell_size` pixel squares, starting with `first` in the top
+    /// left corner.
+    ///
+    /// Returns `Error::InvalidParameter` for a cell size of 0.
+    pub fn draw_checkerboard(
+        &mut self,
+        cell_size: u16,
+        first: u16,
+        second: u16,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        if cell_size == 0 {
+            return Err(Error::InvalidParameter);
+        }
+
+        let (width, height) = self.size();
+        self.write_pixels_from_fn(0, 0, width, height, |x, y| {
+            if (x / cell_size + y / cell_size) % 2 == 0 {
+                first
+            } else {
+                second
+            }
+        })
+    }
+
+    /// Draws grid lines every `spacing` pixels in `color` on `background`, starting at the
+    /// top left corner. The right and bottom edges get a line as well, so the grid outlines
+    /// the whole panel.
+    ///
+    /// Returns `Error::InvalidParameter` for a spacing of 0.
+    pub fn draw_grid(
+        &mut self,
+        spacing: u16,
+        color: u16,
+        background: u16,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        if spacing == 0 {
+            return Err(Error::InvalidParameter);
+        }
+
+        let (width, height) = self.size();
+        self.write_pixels_from_fn(0, 0, width, height, |x, y| {
+            let on_line = x % spacing == 0 || y % spacing == 0;
+            if on_line || x == width - 1 || y == height - 1 {
+                color
+            } else {
+                background
+            }
+        })
+    }
+}
+
+/// Interpolates between two RGB565 colors, `position` counts from 0 at `from` to `steps - 1`
+/// at `to`.
+fn interpolate_rgb565(from: u16, to: u16, position: u16, steps: u16) -> u16 {
+    let last = u32::from(steps.saturating_sub(1)).max(1);
+    let position = u32::from(position);
+    let channel = |shift: u32, mask: u16| {
+        let a = u32::from(from >> shift & mask);
+        let b = u32::from(to >> shift & mask);
+        let value = (a * (last - position) + b * position + last / 2) / last;
+        (value as u16) << shift
+    };
+
+    channel(11, 0x1F) | channel(5, 0x3F) | channel(0, 0x1F)
+}
@@ -0,0 +1,244 @@
+use embedded_hal::blocking::delay::DelayMs;
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::command::Command;
+use crate::{Error, ST7789VConfig, TransferObserver, ST7789V};
+
+/// WRDISBV value after reset
+const BRIGHTNESS_RESET: u8 = 0x00;
+
+/// Low power mode configuration
+///
+/// Idle mode is always used in low power mode. Optionally the brightness is reduced and only a
+/// partial area of the display is scanned.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LowPowerOptions {
+    brightness: Option<u8>,
+    partial_area: Option<(u16, u16)>,
+}
+
+impl LowPowerOptions {
+    /// Creates low power options which only enable idle mode.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reduces the display brightness to `brightness` while in low power mode.
+    pub fn brightness(mut self, brightness: u8) -> Self {
+        self.brightness = Some(brightness);
+        self
+    }
+
+    /// Only displays the rows from `psl` to `pel` while in low power mode.
+    pub fn partial_area(mut self, psl: u16, pel: u16) -> Self {
+        self.partial_area = Some((psl, pel));
+        self
+    }
+}
+
+/// Settings replaced by low power mode
+pub(crate) struct LowPowerRestore {
+    idle: Option<bool>,
+    brightness: u8,
+    partial_mode: Option<bool>,
+    partial_area: Option<(u16, u16)>,
+}
+
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Returns `true` while the display is in sleep mode.
+    ///
+    /// The display enters sleep mode on [`sleep`](#method.sleep), [`sleep_in`](#method.sleep_in)
+    /// and on every reset.
+    pub fn is_asleep(&self) -> bool {
+        self.state.asleep
+    }
+
+    /// Turns the display off and enters sleep mode.
+    ///
+    /// Waits 120 ms after entering sleep mode, so [`wake`](#method.wake) can be called right
+    /// away.
+    pub fn sleep<DELAY>(
+        &mut self,
+        delay: &mut DELAY,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>>
+    where
+        DELAY: DelayMs<u16>,
+    {
+        self.display_off()?.sleep_in(delay)?;
+        delay.delay_ms(120);
+
+        Ok(self)
+    }
+
+    /// Shuts the display down before its supply is cut and releases the SPI bus and config.
+    ///
+    /// The display is turned off and enters sleep mode after the last frame was scanned, which
+    /// avoids a white flash when the supply drops. Waits 120 ms after entering sleep mode,
+    /// then raises the chip select pin. If `hold_reset` is `true` the reset line is asserted
+    /// afterwards, see [`ST7789VConfig::reset_polarity`].
+    ///
+    /// [`ST7789VConfig::reset_polarity`]: struct.ST7789VConfig.html#method.reset_polarity
+    pub fn power_down<DELAY>(
+        mut self,
+        delay: &mut DELAY,
+        hold_reset: bool,
+    ) -> Result<
+        (SPI, ST7789VConfig<CS, DC, RST, D>),
+        Error<PinError, SpiError, CS::Error, RST::Error>,
+    >
+    where
+        DELAY: DelayMs<u16>,
+    {
+        self.display_off()?;
+        delay.delay_ms(self.timings.display_off_ms);
+        self.sleep_in(delay)?;
+        delay.delay_ms(120);
+
+        let (spi, mut cfg) = self.release()?;
+        if hold_reset {
+            cfg.set_reset(true).map_err(Error::Rst)?;
+        }
+
+        Ok((spi, cfg))
+    }
+
+    /// Leaves sleep mode and restores the display configuration, see
+    /// [`resume`](#method.resume).
+    pub fn wake<DELAY>(
+        &mut self,
+        delay: &mut DELAY,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>>
+    where
+        DELAY: DelayMs<u16>,
+    {
+        self.resume(delay)
+    }
+
+    /// Leaves sleep mode and sends the recorded display configuration again, without a reset.
+    ///
+    /// Color mode, memory access control, address window, inversion, idle and partial mode,
+    /// brightness and the vertical scroll area are sent as last configured, then the display
+    /// is turned on. Settings which were not written since the last reset are left at their
+    /// reset default. The frame memory keeps its content during sleep, so it does not have to
+    /// be redrawn.
+    pub fn resume<DELAY>(
+        &mut self,
+        delay: &mut DELAY,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>>
+    where
+        DELAY: DelayMs<u16>,
+    {
+        self.sleep_out(delay)?;
+
+        if let Some(color_format) = self.state.color_format {
+            self.color_mode(color_format, delay)?;
+        }
+        if let Some(madctl) = self.state.madctl {
+            self.command(Command::MADCTL, Some(&[madctl]))?;
+        }
+        if let Some((xs, xe)) = self.state.column_address {
+            self.column_address(xs, xe)?;
+        }
+        if let Some((rs, re)) = self.state.row_address {
+            self.row_address(rs, re)?;
+        }
+        match self.state.inversion {
+            Some(true) => self.inversion_on()?,
+            Some(false) => self.inversion_off()?,
+            None => self,
+        };
+        match self.state.idle {
+            Some(true) => self.idle_on()?,
+            Some(false) => self.idle_off()?,
+            None => self,
+        };
+        if let Some((psl, pel)) = self.state.partial_area {
+            self.partial_area(psl, pel)?;
+        }
+        match self.state.partial_mode {
+            Some(true) => self.force_partial_display_mode()?,
+            Some(false) => self.normal_mode()?,
+            None => self,
+        };
+        if let Some(brightness) = self.state.brightness {
+            self.brightness(brightness)?;
+        }
+        if let Some((tfa, vsa, bfa)) = self.state.scroll_area {
+            self.vertical_scroll_area(tfa, vsa, bfa)?;
+        }
+        if let Some(vsp) = self.state.scroll_start {
+            self.vertical_scroll_start(vsp)?;
+        }
+
+        self.display_on()
+    }
+
+    /// Enters the low power mode described by `options`.
+    ///
+    /// Idle mode is turned on, which reduces the color depth to 8 colors: only the MSB of each
+    /// color component is used. Use a high contrast color scheme while in low power mode.
+    ///
+    /// The replaced settings are restored by
+    /// [`exit_low_power_mode`](#method.exit_low_power_mode). Calling this again while already
+    /// in low power mode applies the new options but keeps the settings to restore.
+    pub fn low_power_mode(
+        &mut self,
+        options: LowPowerOptions,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        if self.state.low_power.is_none() {
+            self.state.low_power = Some(LowPowerRestore {
+                idle: self.state.idle,
+                brightness: self.state.brightness.unwrap_or(BRIGHTNESS_RESET),
+                partial_mode: self.state.partial_mode,
+                partial_area: self.state.partial_area,
+            });
+        }
+
+        if let Some(brightness) = options.brightness {
+            self.brightness(brightness)?;
+        }
+        if let Some((psl, pel)) = options.partial_area {
+            self.partial_area(psl, pel)?.partial_display_mode()?;
+        }
+
+        self.idle_on()
+    }
+
+    /// Leaves low power mode and restores idle mode, brightness and partial mode as they were
+    /// before entering it. A brightness which was not set before entering low power mode is
+    /// restored to the reset value `0x00`. Does nothing if the display is not in low power mode.
+    pub fn exit_low_power_mode(
+        &mut self,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        let restore = match self.state.low_power.take() {
+            Some(restore) => restore,
+            None => return Ok(self),
+        };
+
+        if restore.idle != Some(true) {
+            self.idle_off()?;
+        }
+        if self.state.brightness.unwrap_or(BRIGHTNESS_RESET) != restore.brightness {
+            self.brightness(restore.brightness)?;
+        }
+        if restore.partial_mode == Some(true) {
+            if let Some((psl, pel)) = restore.partial_area {
+                self.partial_area(psl, pel)?;
+            }
+            self.force_partial_display_mode()?;
+        } else if self.state.partial_mode == Some(true) {
+            self.normal_mode()?;
+        }
+
+        Ok(self)
+    }
+}
@@ -0,0 +1,140 @@
+//! Panel profiles of common boards
+//!
+//! A [`PanelProfile`] bundles the panel size, its offset in the frame memory, the inversion
+//! and the memory access control a board needs, and turns them into
+//! [`InitOptions`](../struct.InitOptions.html). A board missing here is a new constant away:
+//!
+//! ```
+//! use st7789v::presets::PanelProfile;
+//! use st7789v::MemAccCtrlConfig;
+//!
+//! const MY_BOARD: PanelProfile = PanelProfile {
+//!     name: "My board 1.14\"",
+//!     width: 135,
+//!     height: 240,
+//!     x_offset: 52,
+//!     y_offset: 40,
+//!     inversion: true,
+//!     memory_access_control: MemAccCtrlConfig::new(),
+//! };
+//! ```
+//!
+//! [`PanelProfile`]: struct.PanelProfile.html
+
+use embedded_hal::blocking::delay::DelayMs;
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{Error, InitOptions, MemAccCtrlConfig, TransferObserver, ST7789V};
+
+/// Geometry and settings of a panel
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PanelProfile {
+    /// Board name
+    pub name: &'static str,
+    /// Visible width in pixels
+    pub width: u16,
+    /// Visible height in pixels
+    pub height: u16,
+    /// First column of the panel in the frame memory
+    pub x_offset: u16,
+    /// First row of the panel in the frame memory
+    pub y_offset: u16,
+    /// Display inversion, on for most IPS panels
+    pub inversion: bool,
+    /// Memory access control set during initialization
+    pub memory_access_control: MemAccCtrlConfig,
+}
+
+impl PanelProfile {
+    /// Returns the init options for this panel, with the default color format.
+    pub fn init_options(&self) -> InitOptions {
+        InitOptions::new()
+            .size(self.width, self.height)
+            .offset(self.x_offset, self.y_offset)
+            .inversion(self.inversion)
+            .memory_access_control(self.memory_access_control)
+    }
+}
+
+/// Adafruit 1.54" 240x240 wide angle TFT
+pub const ADAFRUIT_1_54_240X240: PanelProfile = PanelProfile {
+    name: "Adafruit 1.54\" 240x240",
+    width: 240,
+    height: 240,
+    x_offset: 0,
+    y_offset: 0,
+    inversion: true,
+    memory_access_control: MemAccCtrlConfig::new(),
+};
+
+/// Waveshare 1.3" 240x240 IPS LCD module
+pub const WAVESHARE_1_3_240X240: PanelProfile = PanelProfile {
+    name: "Waveshare 1.3\" 240x240",
+    width: 240,
+    height: 240,
+    x_offset: 0,
+    y_offset: 0,
+    inversion: true,
+    memory_access_control: MemAccCtrlConfig::new(),
+};
+
+/// Pimoroni Pirate Audio 1.3" 240x240 IPS LCD
+pub const PIMORONI_PIRATE_AUDIO: PanelProfile = PanelProfile {
+    name: "Pimoroni Pirate Audio",
+    width: 240,
+    height: 240,
+    x_offset: 0,
+    y_offset: 0,
+    inversion: true,
+    memory_access_control: MemAccCtrlConfig::new(),
+};
+
+/// LilyGO TTGO T-Display 1.14" 135x240 IPS LCD in portrait orientation
+pub const TTGO_T_DISPLAY: PanelProfile = PanelProfile {
+    name: "TTGO T-Display 135x240",
+    width: 135,
+    height: 240,
+    x_offset: 52,
+    y_offset: 40,
+    inversion: true,
+    memory_access_control: MemAccCtrlConfig::new(),
+};
+
+/// Generic 2" 240x320 IPS module using the whole frame memory
+pub const GENERIC_240X320: PanelProfile = PanelProfile {
+    name: "Generic 240x320",
+    width: 240,
+    height: 320,
+    x_offset: 0,
+    y_offset: 0,
+    inversion: true,
+    memory_access_control: MemAccCtrlConfig::new(),
+};
+
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Initialize the display for the panel `profile`, see
+    /// [`init_with`](#method.init_with).
+    ///
+    /// ```ignore
+    /// display.init_with_profile(&presets::TTGO_T_DISPLAY, &mut delay)?;
+    /// ```
+    pub fn init_with_profile<DELAY>(
+        &mut self,
+        profile: &PanelProfile,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<PinError, SpiError, CS::Error, RST::Error>>
+    where
+        DELAY: DelayMs<u16>,
+    {
+        self.init_with(profile.init_options(), delay)
+    }
+}
@@ -0,0 +1,105 @@
+use core::ops::ControlFlow;
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{window_len, Error, PixelFormat, TransferObserver, ST7789V};
+
+/// Long operations reporting their progress
+///
+/// The `progress` callback is called with the number of written pixels and the total number of
+/// pixels after every transfer of up to `BUF` bytes, e.g. to feed a watchdog or poll other
+/// peripherals during a full screen update. Returning `ControlFlow::Break(())` ends the memory
+/// write and returns `Error::Aborted`, the pixels written so far stay on the display.
+///
+/// The memory write stays open while the callback runs, so the callback must not use the
+/// display. With [`with_cs_per_transaction`](struct.ST7789V.html#method.with_cs_per_transaction)
+/// the chip select pin stays low, so other devices on the bus have to wait as well.
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Fills the visible panel area with the RGB565 `color` like [`clear`](#method.clear) and
+    /// reports the progress.
+    ///
+    /// ```ignore
+    /// display.clear_with_progress(BLACK, |_, _| {
+    ///     watchdog.feed();
+    ///     ControlFlow::Continue(())
+    /// })?;
+    /// ```
+    pub fn clear_with_progress<P>(
+        &mut self,
+        color: u16,
+        progress: P,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>>
+    where
+        P: FnMut(usize, usize) -> ControlFlow<()>,
+    {
+        let (width, height) = self.size();
+        self.fill_with_progress(
+            0,
+            0,
+            width.saturating_sub(1),
+            height.saturating_sub(1),
+            color,
+            progress,
+        )
+    }
+
+    /// Fills the window from `xs`, `ys` to `xe`, `ye` with the RGB565 `color` and reports the
+    /// progress.
+    pub fn fill_with_progress<P>(
+        &mut self,
+        xs: u16,
+        ys: u16,
+        xe: u16,
+        ye: u16,
+        color: u16,
+        progress: P,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>>
+    where
+        P: FnMut(usize, usize) -> ControlFlow<()>,
+    {
+        let count = window_len(xs, ys, xe, ye);
+        self.set_pixels_with_progress(
+            xs,
+            ys,
+            xe,
+            ye,
+            core::iter::repeat(color).take(count),
+            progress,
+        )
+    }
+
+    /// Sets the pixels of the window from `xs`, `ys` to `xe`, `ye` to the given RGB565 colors
+    /// like [`set_pixels`](#method.set_pixels) and reports the progress.
+    ///
+    /// Images are drawn by passing their pixels, e.g. `image.iter().copied()`.
+    pub fn set_pixels_with_progress<I, P>(
+        &mut self,
+        xs: u16,
+        ys: u16,
+        xe: u16,
+        ye: u16,
+        colors: I,
+        progress: P,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>>
+    where
+        I: IntoIterator<Item = u16>,
+        P: FnMut(usize, usize) -> ControlFlow<()>,
+    {
+        self.check_pixel_format(PixelFormat::Rgb565)?;
+        self.address_window(xs, ys, xe, ye)?;
+        self.start_memory_write()?.write_colors_with_progress(
+            colors,
+            window_len(xs, ys, xe, ye),
+            progress,
+        )
+    }
+}
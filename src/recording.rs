@@ -0,0 +1,424 @@
+//! Recording backend for testing without hardware
+//!
+//! [`RecordingInterface`] stands in for the SPI bus and the data/command pin and captures
+//! every command and data byte the driver sends. [`FrameMemory`] interprets a capture like the
+//! controller does, so drawing code can be checked against the resulting frame memory
+//! content.
+//!
+//! ```
+//! use st7789v::recording::{replay, RecordingInterface};
+//!
+//! let interface = RecordingInterface::new();
+//! let mut display = interface.display();
+//! display.init(&mut interface.delay()).unwrap();
+//! display.pixel(1, 0, 0xF800).unwrap();
+//!
+//! let frame = replay(&interface.transfers(), 240, 240);
+//! assert_eq!(frame[1], 0xF800);
+//! ```
+//!
+//! [`RecordingInterface`]: struct.RecordingInterface.html
+//! [`FrameMemory`]: struct.FrameMemory.html
+
+use core::convert::Infallible;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::vec;
+use std::vec::Vec;
+
+use embedded_hal::blocking::delay::{DelayMs, DelayUs};
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::command::Command;
+use crate::{NoPin, ST7789V};
+
+/// Frame memory columns of the ST7789V
+pub const COLUMNS: u16 = 240;
+/// Frame memory rows of the ST7789V
+pub const ROWS: u16 = 320;
+
+/// A driver writing to a [`RecordingInterface`](struct.RecordingInterface.html)
+pub type RecordingDisplay = ST7789V<RecordingSpi, NoPin, RecordingDc, NoPin>;
+
+/// A captured transfer
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Transfer {
+    /// A command byte, sent with the data/command pin low
+    Command(u8),
+    /// Data bytes, sent with the data/command pin high. Consecutive data writes are merged.
+    Data(Vec<u8>),
+}
+
+#[derive(Default)]
+struct Recorder {
+    dc_high: bool,
+    transfers: Vec<Transfer>,
+    bus_calls: usize,
+}
+
+impl Recorder {
+    fn record(&mut self, words: &[u8]) {
+        if self.dc_high {
+            match self.transfers.last_mut() {
+                Some(Transfer::Data(data)) => data.extend_from_slice(words),
+                _ => self.transfers.push(Transfer::Data(words.to_vec())),
+            }
+        } else {
+            self.transfers
+                .extend(words.iter().map(|&cmd| Transfer::Command(cmd)));
+        }
+    }
+}
+
+/// Captures the traffic of a driver
+///
+/// Cloning the interface shares the capture.
+#[derive(Clone, Default)]
+pub struct RecordingInterface {
+    recorder: Rc<RefCell<Recorder>>,
+}
+
+impl RecordingInterface {
+    /// Creates an interface with an empty capture.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a recording SPI bus.
+    pub fn spi(&self) -> RecordingSpi {
+        RecordingSpi {
+            recorder: self.recorder.clone(),
+        }
+    }
+
+    /// Returns a recording data/command pin.
+    pub fn dc(&self) -> RecordingDc {
+        RecordingDc {
+            recorder: self.recorder.clone(),
+        }
+    }
+
+    /// Returns a delay provider which does not wait.
+    pub fn delay(&self) -> NoDelay {
+        NoDelay
+    }
+
+    /// Creates a driver writing to this interface.
+    pub fn display(&self) -> RecordingDisplay {
+        ST7789V::new(self.spi(), self.dc(), NoPin::new())
+    }
+
+    /// Returns the captured transfers.
+    pub fn transfers(&self) -> Vec<Transfer> {
+        self.recorder.borrow().transfers.clone()
+    }
+
+    /// Returns the number of SPI bus calls, i.e. calls to `write` or `exec`.
+    pub fn bus_calls(&self) -> usize {
+        self.recorder.borrow().bus_calls
+    }
+
+    /// Returns the captured transfers and clears the capture.
+    pub fn take_transfers(&self) -> Vec<Transfer> {
+        core::mem::take(&mut self.recorder.borrow_mut().transfers)
+    }
+}
+
+/// SPI bus of a [`RecordingInterface`](struct.RecordingInterface.html)
+pub struct RecordingSpi {
+    recorder: Rc<RefCell<Recorder>>,
+}
+
+impl spi::Write<u8> for RecordingSpi {
+    type Error = Infallible;
+
+    fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+        let mut recorder = self.recorder.borrow_mut();
+        recorder.bus_calls += 1;
+        recorder.record(words);
+
+        Ok(())
+    }
+}
+
+impl spi::Transactional<u8> for RecordingSpi {
+    type Error = Infallible;
+
+    fn exec(&mut self, operations: &mut [spi::Operation<'_, u8>]) -> Result<(), Self::Error> {
+        let mut recorder = self.recorder.borrow_mut();
+        recorder.bus_calls += 1;
+        for operation in operations {
+            match operation {
+                spi::Operation::Write(words) => recorder.record(words),
+                spi::Operation::Transfer(words) => recorder.record(words),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Data/command pin of a [`RecordingInterface`](struct.RecordingInterface.html)
+pub struct RecordingDc {
+    recorder: Rc<RefCell<Recorder>>,
+}
+
+impl OutputPin for RecordingDc {
+    type Error = Infallible;
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.recorder.borrow_mut().dc_high = false;
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.recorder.borrow_mut().dc_high = true;
+        Ok(())
+    }
+}
+
+/// Delay provider which returns immediately
+pub struct NoDelay;
+
+impl DelayMs<u16> for NoDelay {
+    fn delay_ms(&mut self, _ms: u16) {}
+}
+
+impl DelayUs<u32> for NoDelay {
+    fn delay_us(&mut self, _us: u32) {}
+}
+
+/// MADCTL row address order bit
+const MADCTL_MY: u8 = 0b1000_0000;
+/// MADCTL column address order bit
+const MADCTL_MX: u8 = 0b0100_0000;
+/// MADCTL row/column exchange bit
+const MADCTL_MV: u8 = 0b0010_0000;
+/// COLMOD after reset: 18 bit per pixel
+const COLMOD_RESET: u8 = 0x66;
+
+/// Model of the controller frame memory
+///
+/// Interprets CASET, RASET, MADCTL, COLMOD, RAMWR, RAMWRC and SWRESET, every other command is
+/// ignored. The address pointer wraps like on the controller: at the end of a column window
+/// it moves to the start of the next row, at the end of the row window back to the first row.
+/// Pixels are stored as RGB565 for every color format.
+pub struct FrameMemory {
+    columns: u16,
+    rows: u16,
+    memory: Vec<u16>,
+    command: Option<u8>,
+    pending: Vec<u8>,
+    column_window: (u16, u16),
+    row_window: (u16, u16),
+    madctl: u8,
+    colmod: u8,
+    column: u16,
+    row: u16,
+}
+
+impl Default for FrameMemory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FrameMemory {
+    /// Creates a 240x320 frame memory in its reset state, filled with black.
+    pub fn new() -> Self {
+        Self::with_size(COLUMNS, ROWS)
+    }
+
+    /// Creates a frame memory of a different size, filled with black.
+    pub fn with_size(columns: u16, rows: u16) -> Self {
+        let mut memory = FrameMemory {
+            columns,
+            rows,
+            memory: vec![0; usize::from(columns) * usize::from(rows)],
+            command: None,
+            pending: Vec::new(),
+            column_window: (0, 0),
+            row_window: (0, 0),
+            madctl: 0,
+            colmod: 0,
+            column: 0,
+            row: 0,
+        };
+        memory.reset_registers();
+
+        memory
+    }
+
+    /// Sets the registers to their reset values. The frame memory content is kept.
+    fn reset_registers(&mut self) {
+        self.column_window = (0, self.columns.saturating_sub(1));
+        self.row_window = (0, self.rows.saturating_sub(1));
+        self.madctl = 0;
+        self.colmod = COLMOD_RESET;
+        self.column = 0;
+        self.row = 0;
+    }
+
+    /// Applies the captured transfers.
+    pub fn replay(&mut self, transfers: &[Transfer]) -> &mut Self {
+        for transfer in transfers {
+            match transfer {
+                Transfer::Command(cmd) => self.command(*cmd),
+                Transfer::Data(data) => self.data(data),
+            }
+        }
+
+        self
+    }
+
+    /// Applies a command byte.
+    pub fn command(&mut self, cmd: u8) {
+        self.command = Some(cmd);
+        self.pending.clear();
+
+        if cmd == Command::RAMWR.value() {
+            self.column = self.column_window.0;
+            self.row = self.row_window.0;
+        } else if cmd == Command::SWRESET.value() {
+            self.reset_registers();
+        }
+    }
+
+    /// Applies data bytes.
+    pub fn data(&mut self, data: &[u8]) {
+        let cmd = match self.command {
+            Some(cmd) => cmd,
+            None => return,
+        };
+
+        for &byte in data {
+            self.pending.push(byte);
+
+            if cmd == Command::CASET.value() && self.pending.len() == 4 {
+                self.column_window = self.address_pair();
+            } else if cmd == Command::RASET.value() && self.pending.len() == 4 {
+                self.row_window = self.address_pair();
+            } else if cmd == Command::MADCTL.value() && self.pending.len() == 1 {
+                self.madctl = byte;
+            } else if cmd == Command::COLMOD.value() && self.pending.len() == 1 {
+                self.colmod = byte;
+            } else if cmd == Command::RAMWR.value() || cmd == Command::RAMWRC.value() {
+                self.pixel_data();
+            }
+        }
+    }
+
+    /// Returns the RGB565 color at frame memory column `x` and row `y`.
+    pub fn pixel(&self, x: u16, y: u16) -> u16 {
+        self.memory[usize::from(y) * usize::from(self.columns) + usize::from(x)]
+    }
+
+    /// Returns the frame memory content row by row.
+    pub fn pixels(&self) -> &[u16] {
+        &self.memory
+    }
+
+    /// Returns the `width` by `height` area at column `x` and row `y` row by row.
+    ///
+    /// Panics if the area is not within the frame memory.
+    pub fn area(&self, x: u16, y: u16, width: u16, height: u16) -> Vec<u16> {
+        assert!(
+            self.contains(x, y, width, height),
+            "area outside the frame memory"
+        );
+        let mut area = Vec::with_capacity(usize::from(width) * usize::from(height));
+        for row in y..y + height {
+            for column in x..x + width {
+                area.push(self.pixel(column, row));
+            }
+        }
+
+        area
+    }
+
+    /// Returns `true` if the `width` by `height` area at column `x` and row `y` lies within the
+    /// frame memory.
+    pub(crate) fn contains(&self, x: u16, y: u16, width: u16, height: u16) -> bool {
+        u32::from(x) + u32::from(width) <= u32::from(self.columns)
+            && u32::from(y) + u32::from(height) <= u32::from(self.rows)
+    }
+
+    fn address_pair(&self) -> (u16, u16) {
+        let p = &self.pending;
+        (
+            u16::from_be_bytes([p[0], p[1]]),
+            u16::from_be_bytes([p[2], p[3]]),
+        )
+    }
+
+    /// Stores a pixel once enough bytes for the color format are pending.
+    fn pixel_data(&mut self) {
+        let p = &self.pending;
+        match self.colmod & 0b111 {
+            0b101 if p.len() == 2 => {
+                let color = u16::from_be_bytes([p[0], p[1]]);
+                self.pending.clear();
+                self.write_pixel(color);
+            }
+            0b011 if p.len() == 3 => {
+                let first = u16::from(p[0]) << 4 | u16::from(p[1]) >> 4;
+                let second = (u16::from(p[1]) & 0x0F) << 8 | u16::from(p[2]);
+                self.pending.clear();
+                self.write_pixel(rgb444_to_rgb565(first));
+                self.write_pixel(rgb444_to_rgb565(second));
+            }
+            0b110 | 0b111 if p.len() == 3 => {
+                let color =
+                    u16::from(p[0] >> 3) << 11 | u16::from(p[1] >> 2) << 5 | u16::from(p[2] >> 3);
+                self.pending.clear();
+                self.write_pixel(color);
+            }
+            _ => {}
+        }
+    }
+
+    fn write_pixel(&mut self, color: u16) {
+        let (mut x, mut y) = if self.madctl & MADCTL_MV != 0 {
+            (self.row, self.column)
+        } else {
+            (self.column, self.row)
+        };
+        if self.madctl & MADCTL_MX != 0 {
+            x = self.columns.wrapping_sub(1).wrapping_sub(x);
+        }
+        if self.madctl & MADCTL_MY != 0 {
+            y = self.rows.wrapping_sub(1).wrapping_sub(y);
+        }
+        if x < self.columns && y < self.rows {
+            self.memory[usize::from(y) * usize::from(self.columns) + usize::from(x)] = color;
+        }
+
+        if self.column >= self.column_window.1 {
+            self.column = self.column_window.0;
+            if self.row >= self.row_window.1 {
+                self.row = self.row_window.0;
+            } else {
+                self.row += 1;
+            }
+        } else {
+            self.column += 1;
+        }
+    }
+}
+
+fn rgb444_to_rgb565(color: u16) -> u16 {
+    let r = (color >> 8) & 0x0F;
+    let g = (color >> 4) & 0x0F;
+    let b = color & 0x0F;
+
+    (r << 1 | r >> 3) << 11 | (g << 2 | g >> 2) << 5 | (b << 1 | b >> 3)
+}
+
+/// Replays `transfers` into a 240x320 frame memory and returns the `width` by `height` area at
+/// its origin row by row.
+pub fn replay(transfers: &[Transfer], width: u16, height: u16) -> Vec<u16> {
+    FrameMemory::new()
+        .replay(transfers)
+        .area(0, 0, width, height)
+}
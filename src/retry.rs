@@ -0,0 +1,255 @@
+use core::convert::TryFrom;
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::command::Command;
+use crate::{Error, PixelFormat, TransferObserver, ST7789V};
+
+/// Retrying of SPI writes which failed with a transient error
+///
+/// The default policy does not retry, the first SPI error is returned.
+///
+/// ```ignore
+/// display.set_retry(RetryPolicy {
+///     attempts: 3,
+///     ..RetryPolicy::default()
+/// });
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Retries of a failed write before the error is returned, 0 disables retrying
+    pub attempts: u8,
+    /// Resume a failed frame memory write at the first pixel of the failed chunk by sending
+    /// the remaining window again. Otherwise only writes right after their command are
+    /// retried.
+    pub resume_memory_writes: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy::new(0)
+    }
+}
+
+impl RetryPolicy {
+    /// Retries a failed write up to `attempts` times and resumes frame memory writes.
+    pub const fn new(attempts: u8) -> Self {
+        RetryPolicy {
+            attempts,
+            resume_memory_writes: true,
+        }
+    }
+}
+
+/// Progress of the current command, to know where a failed write continues
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Retry {
+    /// Retry policy
+    pub(crate) policy: RetryPolicy,
+    /// Last command sent
+    command: Option<u8>,
+    /// Data bytes written since the command
+    written: usize,
+    /// Column and row range of the frame memory write started by the command
+    window: Option<((u16, u16), (u16, u16))>,
+    /// Bytes left in the partial row a frame memory write was resumed in
+    row_rest: Option<usize>,
+}
+
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Sets how SPI writes failing with a transient error, e.g. an overrun on a long bus, are
+    /// retried.
+    ///
+    /// A failed write is repeated after the chip select and data/command pins were driven
+    /// again. A failed command byte and the first data after a command are written again
+    /// together with the command. A failed chunk of a frame memory write continues at its
+    /// first pixel: the remaining window is sent again, so the address pointer of the display
+    /// stays in sync with the data. The error is returned once the attempts are used up or the
+    /// write cannot be resumed, e.g. with the 12 bit color format. The address window of the
+    /// display is unknown after a failed CASET or RASET.
+    ///
+    /// Transactional writes and [`WindowWriter`](struct.WindowWriter.html) are not retried.
+    pub fn set_retry(&mut self, policy: RetryPolicy) -> &mut Self {
+        self.retry.policy = policy;
+        self
+    }
+
+    /// Returns the retry policy, see [`set_retry`](#method.set_retry).
+    pub fn retry_policy(&self) -> RetryPolicy {
+        self.retry.policy
+    }
+
+    /// Writes a command byte, the data/command pin has to be low already.
+    pub(crate) fn write_command_byte(
+        &mut self,
+        cmd: Command,
+    ) -> Result<(), Error<PinError, SpiError, CS::Error, RST::Error>> {
+        let window =
+            if cmd.value() == Command::RAMWR.value() && self.retry.policy.resume_memory_writes {
+                self.state.column_address.zip(self.state.row_address)
+            } else {
+                None
+            };
+        self.retry = Retry {
+            policy: self.retry.policy,
+            command: Some(cmd.value()),
+            written: 0,
+            window,
+            row_rest: None,
+        };
+
+        self.write_byte_retried(cmd.value())
+    }
+
+    /// Writes the command byte `cmd` again until it succeeds or the attempts are used up.
+    fn write_byte_retried(
+        &mut self,
+        cmd: u8,
+    ) -> Result<(), Error<PinError, SpiError, CS::Error, RST::Error>> {
+        let mut attempts = self.retry.policy.attempts;
+        loop {
+            match self.spi.write(&[cmd]) {
+                Ok(()) => return Ok(()),
+                Err(error) if attempts == 0 => {
+                    self.invalidate_window();
+                    return Err(Error::Spi(error));
+                }
+                Err(_) => {
+                    attempts -= 1;
+                    self.reselect()?;
+                    self.set_dc(false)?;
+                }
+            }
+        }
+    }
+
+    /// Writes data bytes following the last command and retries failed chunks.
+    pub(crate) fn write_stream(
+        &mut self,
+        mut data: &[u8],
+    ) -> Result<(), Error<PinError, SpiError, CS::Error, RST::Error>> {
+        let mut attempts = self.retry.policy.attempts;
+        while !data.is_empty() {
+            let len = self
+                .retry
+                .row_rest
+                .map_or(data.len(), |rest| rest.min(data.len()));
+            self.select()?.set_dc(true)?;
+            match self.spi.write(&data[..len]) {
+                Ok(()) => {
+                    data = &data[len..];
+                    self.retry.written += len;
+                    if let Some(rest) = self.retry.row_rest {
+                        self.retry.row_rest = Some(rest - len).filter(|&rest| rest > 0);
+                        if rest == len {
+                            // the partial row is full, continue with the rows below
+                            self.resume_memory_write()?;
+                        }
+                    }
+                }
+                Err(error) => {
+                    if attempts == 0 || !self.recover()? {
+                        self.invalidate_window();
+                        return Err(Error::Spi(error));
+                    }
+                    attempts -= 1;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Prepares writing the failed data again. Returns `false` if the write cannot continue.
+    fn recover(&mut self) -> Result<bool, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.reselect()?;
+        if self.resume_memory_write()? {
+            return Ok(true);
+        }
+
+        match self.retry.command {
+            Some(cmd) if self.retry.written == 0 => {
+                self.set_dc(false)?;
+                self.write_byte_retried(cmd)?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// Sends the part of the frame memory write window starting at the next pixel and
+    /// RAMWR. A pixel in the middle of a row gets a window of the rest of its row first.
+    /// Returns `false` if the next pixel is unknown.
+    fn resume_memory_write(
+        &mut self,
+    ) -> Result<bool, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        let ((xs, xe), (rs, re)) = match self.retry.window {
+            Some(window) => window,
+            None => return Ok(false),
+        };
+        let pixel_len = match self.state.color_format.map(|format| format.pixel_format()) {
+            Some(PixelFormat::Rgb565) => 2,
+            Some(PixelFormat::Rgb666) => 3,
+            _ => return Ok(false),
+        };
+        if self.retry.written % pixel_len != 0 {
+            return Ok(false);
+        }
+
+        let width = usize::from(xe - xs) + 1;
+        let pixel = self.retry.written / pixel_len;
+        let row = match u16::try_from(usize::from(rs) + pixel / width) {
+            Ok(row) if row <= re => row,
+            _ => return Ok(false),
+        };
+        let column = xs + (pixel % width) as u16;
+
+        let progress = self.retry;
+        let row_rest = if column == xs {
+            self.column_address(xs, xe)?.row_address(row, re)?;
+            None
+        } else {
+            self.column_address(column, xe)?.row_address(row, row)?;
+            Some(usize::from(xe - column + 1) * pixel_len)
+        };
+        self.write_command(Command::RAMWR)?;
+        self.retry = Retry {
+            row_rest,
+            ..progress
+        };
+
+        Ok(true)
+    }
+
+    /// Drives the chip select and data/command pins again after a failed write. A chip select
+    /// pin which stays low is pulsed to reset the serial interface of the display.
+    fn reselect(&mut self) -> Result<(), Error<PinError, SpiError, CS::Error, RST::Error>> {
+        if self.cs_per_transaction {
+            self.end_transaction()?.select()?;
+        } else if let Some(cs) = self.cfg.cs.as_mut() {
+            cs.set_high().map_err(Error::Cs)?;
+            cs.set_low().map_err(Error::Cs)?;
+        }
+        self.dc_level = None;
+
+        Ok(())
+    }
+
+    /// Forgets the address window written by a failed CASET or RASET.
+    fn invalidate_window(&mut self) {
+        match self.retry.command {
+            Some(cmd) if cmd == Command::CASET.value() => self.state.column_address = None,
+            Some(cmd) if cmd == Command::RASET.value() => self.state.row_address = None,
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::command::Command;
+use crate::{Error, TransferObserver, ST7789V};
+
+/// RAMCTRL first parameter selecting the RGB interface for RAM access and display operation
+const RAMCTRL_RGB: u8 = 0b0001_0001;
+/// RAMCTRL first parameter selecting the MCU interface, the reset default
+const RAMCTRL_MCU: u8 = 0b0000_0000;
+/// RAMCTRL second parameter, the reset default
+const RAMCTRL_FRAME: u8 = 0xF0;
+
+/// RGB interface transfer mode (RCM)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RgbTransferMode {
+    /// Pixel data is valid while the DE signal is active
+    DataEnable = 0b0100_0000,
+    /// Pixel data is timed by the VSYNC and HSYNC signals and the porch settings
+    Sync = 0b0110_0000,
+}
+
+impl RgbTransferMode {
+    pub fn value(self) -> u8 {
+        self as u8
+    }
+}
+
+/// RGB interface signal configuration (RGBCTRL)
+///
+/// The defaults match the reset values of the controller: DE mode, low active sync signals,
+/// data latched on the rising PCLK edge, high active DE, a vertical back porch of 2 lines and
+/// a horizontal back porch of 20 clocks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RgbInterfaceConfig {
+    mode: RgbTransferMode,
+    vsync_active_high: bool,
+    hsync_active_high: bool,
+    pclk_falling_edge: bool,
+    de_active_low: bool,
+    vbp: u8,
+    hbp: u8,
+}
+
+impl Default for RgbInterfaceConfig {
+    fn default() -> Self {
+        RgbInterfaceConfig {
+            mode: RgbTransferMode::DataEnable,
+            vsync_active_high: false,
+            hsync_active_high: false,
+            pclk_falling_edge: false,
+            de_active_low: false,
+            vbp: 0x02,
+            hbp: 0x14,
+        }
+    }
+}
+
+impl RgbInterfaceConfig {
+    /// Creates the default configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the transfer mode.
+    pub fn mode(mut self, mode: RgbTransferMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Sets the VSYNC polarity (VSPL).
+    pub fn vsync_active_high(mut self, active_high: bool) -> Self {
+        self.vsync_active_high = active_high;
+        self
+    }
+
+    /// Sets the HSYNC polarity (HSPL).
+    pub fn hsync_active_high(mut self, active_high: bool) -> Self {
+        self.hsync_active_high = active_high;
+        self
+    }
+
+    /// Latches the pixel data on the falling instead of the rising PCLK edge (DPL).
+    pub fn pclk_falling_edge(mut self, falling_edge: bool) -> Self {
+        self.pclk_falling_edge = falling_edge;
+        self
+    }
+
+    /// Sets the DE polarity (EPL).
+    pub fn de_active_low(mut self, active_low: bool) -> Self {
+        self.de_active_low = active_low;
+        self
+    }
+
+    /// Sets the vertical back porch in lines, `2..=127`. Only used in sync mode.
+    pub fn vertical_back_porch(mut self, vbp: u8) -> Self {
+        self.vbp = vbp;
+        self
+    }
+
+    /// Sets the horizontal back porch in PCLK cycles, `2..=31`. Only used in sync mode.
+    pub fn horizontal_back_porch(mut self, hbp: u8) -> Self {
+        self.hbp = hbp;
+        self
+    }
+
+    /// Get as RGBCTRL parameters
+    fn params(self) -> [u8; 3] {
+        let flag = |set: bool, bit: u8| if set { bit } else { 0 };
+        [
+            self.mode.value()
+                | flag(self.vsync_active_high, 0b1000)
+                | flag(self.hsync_active_high, 0b0100)
+                | flag(self.pclk_falling_edge, 0b0010)
+                | flag(self.de_active_low, 0b0001),
+            self.vbp,
+            self.hbp,
+        ]
+    }
+}
+
+/// RGB interface
+///
+/// While the RGB interface owns the frame memory, pixel data written through the SPI
+/// interface, e.g. with [`pixels`](#method.pixels) or the embedded-graphics draw target, is
+/// not displayed. Commands still work as usual.
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Configures the RGB interface signals and selects the RGB interface as the path for
+    /// pixel data.
+    ///
+    /// Returns `Error::InvalidParameter` if a porch setting is out of range.
+    pub fn configure_rgb_interface(
+        &mut self,
+        config: RgbInterfaceConfig,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        if !(2..=127).contains(&config.vbp) || !(2..=31).contains(&config.hbp) {
+            return Err(Error::InvalidParameter);
+        }
+
+        self.command(Command::RGBCTRL, Some(&config.params()))?
+            .command(Command::RAMCTRL, Some(&[RAMCTRL_RGB, RAMCTRL_FRAME]))?;
+
+        Ok(self)
+    }
+
+    /// Selects the MCU interface as the path for pixel data again.
+    pub fn mcu_interface(
+        &mut self,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.command(Command::RAMCTRL, Some(&[RAMCTRL_MCU, RAMCTRL_FRAME]))?;
+
+        Ok(self)
+    }
+}
@@ -0,0 +1,230 @@
+#![deny(clippy::cast_sign_loss)]
+
+use embedded_graphics::drawable::Pixel;
+use embedded_graphics::geometry::Dimensions;
+use embedded_graphics::image::{Image, ImageDimensions, IntoPixelIter};
+use embedded_graphics::pixelcolor::raw::{RawData, RawU16};
+use embedded_graphics::pixelcolor::{Rgb565, Rgb888, RgbColor};
+use embedded_graphics::prelude::Point;
+use embedded_graphics::prelude::{DrawTarget, Size};
+use embedded_graphics::primitives::{Circle, Line, Rectangle, Triangle};
+use embedded_graphics::style::{PrimitiveStyle, PrimitiveStyleBuilder, Styled};
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{Error, TransferObserver, ST7789V};
+
+/// Draw target for 24 bit RGB graphics
+///
+/// Created by [`ST7789V::rgb888`]. Colors are converted to RGB565 while the pixels are
+/// streamed, so drawing takes no extra buffer. Primitives and images are drawn with the same
+/// window based paths as their `Rgb565` counterparts, and pixel iterators are clipped and
+/// coalesced into runs the same way.
+///
+/// Colors are truncated to RGB565 unless [`with_dithering`](#method.with_dithering) is used.
+///
+/// ```ignore
+/// Image::new(&bmp, Point::zero()).draw(&mut display.rgb888())?;
+/// Image::new(&sky, Point::zero()).draw(&mut display.rgb888().with_dithering())?;
+/// ```
+///
+/// [`ST7789V::rgb888`]: struct.ST7789V.html#method.rgb888
+pub struct Rgb888Target<'a, SPI, CS, DC, RST, D = (), O = (), const BUF: usize = 128>
+where
+    SPI: spi::Write<u8>,
+    CS: OutputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    display: &'a mut ST7789V<SPI, CS, DC, RST, D, O, BUF>,
+    dither: bool,
+}
+
+impl<'a, SPI, CS, DC, RST, D, O, const BUF: usize> Rgb888Target<'a, SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8>,
+    CS: OutputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// Applies a 4x4 ordered dither before colors are reduced to RGB565, which hides the
+    /// banding of smooth gradients.
+    ///
+    /// The dither pattern depends on the screen position of each pixel, so primitives are
+    /// drawn pixel by pixel in runs instead of with the single color fast paths. Filled
+    /// rectangles and images still take a single memory write.
+    pub fn with_dithering(mut self) -> Self {
+        self.dither = true;
+        self
+    }
+}
+
+/// 4x4 Bayer matrix, indexed by `[y % 4][x % 4]`
+const BAYER_4X4: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// Converts `color` at `point` to RGB565, adding the Bayer threshold of the position scaled to
+/// the quantization step of each channel if `dither` is set.
+fn to_rgb565(color: Rgb888, point: Point, dither: bool) -> Rgb565 {
+    if !dither {
+        return Rgb565::from(color);
+    }
+
+    let threshold =
+        BAYER_4X4[(point.y & 3).unsigned_abs() as usize][(point.x & 3).unsigned_abs() as usize];
+    // red and blue lose 3 bits, green 2 bits
+    let red = color.r().saturating_add(threshold / 2);
+    let green = color.g().saturating_add(threshold / 4);
+    let blue = color.b().saturating_add(threshold / 2);
+
+    Rgb565::new(red >> 3, green >> 2, blue >> 3)
+}
+
+/// Maps the colors of a primitive style.
+fn style(style: &PrimitiveStyle<Rgb888>) -> PrimitiveStyle<Rgb565> {
+    let mut builder = PrimitiveStyleBuilder::new().stroke_width(style.stroke_width);
+    if let Some(color) = style.fill_color {
+        builder = builder.fill_color(Rgb565::from(color));
+    }
+    if let Some(color) = style.stroke_color {
+        builder = builder.stroke_color(Rgb565::from(color));
+    }
+
+    builder.build()
+}
+
+impl<'a, SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize> DrawTarget<Rgb888>
+    for Rgb888Target<'a, SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    type Error = Error<PinError, SpiError, CS::Error, RST::Error>;
+
+    fn draw_pixel(&mut self, pixel: Pixel<Rgb888>) -> Result<(), Self::Error> {
+        let Pixel(point, color) = pixel;
+        self.display
+            .draw_pixel(Pixel(point, to_rgb565(color, point, self.dither)))
+    }
+
+    fn draw_iter<T>(&mut self, item: T) -> Result<(), Self::Error>
+    where
+        T: IntoIterator<Item = Pixel<Rgb888>>,
+    {
+        let dither = self.dither;
+        let pixels = item
+            .into_iter()
+            .map(move |Pixel(point, color)| Pixel(point, to_rgb565(color, point, dither)));
+
+        self.display.draw_iter(pixels)
+    }
+
+    fn draw_line(
+        &mut self,
+        item: &Styled<Line, PrimitiveStyle<Rgb888>>,
+    ) -> Result<(), Self::Error> {
+        if self.dither {
+            return self.draw_iter(item);
+        }
+
+        self.display
+            .draw_line(&Styled::new(item.primitive, style(&item.style)))
+    }
+
+    fn draw_triangle(
+        &mut self,
+        item: &Styled<Triangle, PrimitiveStyle<Rgb888>>,
+    ) -> Result<(), Self::Error> {
+        if self.dither {
+            return self.draw_iter(item);
+        }
+
+        self.display
+            .draw_triangle(&Styled::new(item.primitive, style(&item.style)))
+    }
+
+    fn draw_rectangle(
+        &mut self,
+        item: &Styled<Rectangle, PrimitiveStyle<Rgb888>>,
+    ) -> Result<(), Self::Error> {
+        if !self.dither {
+            return self
+                .display
+                .draw_rectangle(&Styled::new(item.primitive, style(&item.style)));
+        }
+
+        let window = self
+            .display
+            .display_window(item.top_left(), item.bottom_right());
+        if let (Some((sx, sy, ex, ey)), Some(_)) = (window, item.style.fill_color) {
+            let dither = self.dither;
+            let colors = item
+                .into_iter()
+                .map(|p| RawU16::from(to_rgb565(p.1, p.0, dither)).into_inner());
+
+            self.display.set_pixels_checked(sx, sy, ex, ey, colors)?;
+
+            Ok(())
+        } else {
+            self.draw_iter(item)
+        }
+    }
+
+    fn draw_circle(
+        &mut self,
+        item: &Styled<Circle, PrimitiveStyle<Rgb888>>,
+    ) -> Result<(), Self::Error> {
+        if self.dither {
+            return self.draw_iter(item);
+        }
+
+        self.display
+            .draw_circle(&Styled::new(item.primitive, style(&item.style)))
+    }
+
+    fn draw_image<'b, 'c, I>(&mut self, item: &'b Image<'c, I, Rgb888>) -> Result<(), Self::Error>
+    where
+        &'c I: IntoPixelIter<Rgb888>,
+        I: ImageDimensions,
+    {
+        // clipped images are drawn in runs
+        let (sx, sy, ex, ey) = match self.display.image_window(item) {
+            Some(window) => window,
+            None => return self.draw_iter(item),
+        };
+        let dither = self.dither;
+        let colors = item
+            .into_iter()
+            .map(|p| RawU16::from(to_rgb565(p.1, p.0, dither)).into_inner());
+
+        self.display.set_pixels_checked(sx, sy, ex, ey, colors)?;
+
+        Ok(())
+    }
+
+    fn size(&self) -> Size {
+        DrawTarget::<Rgb565>::size(self.display)
+    }
+}
+
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Returns a draw target for `Rgb888` graphics, see
+    /// [`Rgb888Target`](struct.Rgb888Target.html).
+    pub fn rgb888(&mut self) -> Rgb888Target<'_, SPI, CS, DC, RST, D, O, BUF> {
+        Rgb888Target {
+            display: self,
+            dither: false,
+        }
+    }
+}
@@ -0,0 +1,193 @@
+#![deny(clippy::cast_sign_loss)]
+
+use embedded_graphics::drawable::Pixel;
+use embedded_graphics::pixelcolor::raw::{RawData, RawU16};
+use embedded_graphics::pixelcolor::Rgb565;
+use embedded_graphics::prelude::Point;
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{Error, TransferObserver, ST7789V};
+
+/// Maximum number of pixels of different colors coalesced into a single memory write, runs of
+/// a single color are not limited
+const RUN_LEN: usize = 64;
+
+/// Direction in which adjacent pixels are coalesced
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Direction {
+    /// Pixels on the same row
+    Horizontal,
+    /// Pixels in the same column
+    Vertical,
+}
+
+/// Run of adjacent pixels on a row or column
+///
+/// A run grows forwards or backwards from its first pixel, so lines drawn right to left or
+/// bottom to top are coalesced as well. While all pixels have the same color the run keeps
+/// growing past `RUN_LEN`, only the first `RUN_LEN` colors are stored.
+struct Run {
+    direction: Direction,
+    x: u16,
+    y: u16,
+    len: usize,
+    reversed: bool,
+    solid: bool,
+    colors: [u16; RUN_LEN],
+}
+
+impl Run {
+    fn new(direction: Direction) -> Self {
+        Run {
+            direction,
+            x: 0,
+            y: 0,
+            len: 0,
+            reversed: false,
+            solid: true,
+            colors: [0; RUN_LEN],
+        }
+    }
+
+    /// Appends the pixel if it continues the run, returns `false` otherwise.
+    fn push(&mut self, x: u16, y: u16, color: u16) -> bool {
+        if self.len == 0 {
+            self.x = x;
+            self.y = y;
+            self.reversed = false;
+            self.solid = true;
+        } else {
+            let (along, across, first, first_across) = match self.direction {
+                Direction::Horizontal => (x, y, self.x, self.y),
+                Direction::Vertical => (y, x, self.y, self.x),
+            };
+            let solid = self.solid && color == self.colors[0];
+            if (self.len >= RUN_LEN && !solid) || across != first_across {
+                return false;
+            }
+
+            let along = usize::from(along);
+            let first = usize::from(first);
+            if self.len == 1 && along + 1 == first {
+                self.reversed = true;
+            } else if self.reversed {
+                if along + self.len != first {
+                    return false;
+                }
+            } else if along != first + self.len {
+                return false;
+            }
+        }
+
+        if self.len < RUN_LEN {
+            self.colors[self.len] = color;
+        }
+        self.solid = self.len == 0 || (self.solid && color == self.colors[0]);
+        self.len += 1;
+        true
+    }
+
+    /// Returns the window covered by the run as `(xs, ys, xe, ye)`.
+    fn window(&self) -> (u16, u16, u16, u16) {
+        let len = (self.len - 1) as u16;
+        let (xs, ys) = match (self.direction, self.reversed) {
+            (_, false) => (self.x, self.y),
+            (Direction::Horizontal, true) => (self.x - len, self.y),
+            (Direction::Vertical, true) => (self.x, self.y - len),
+        };
+
+        match self.direction {
+            Direction::Horizontal => (xs, ys, xs + len, ys),
+            Direction::Vertical => (xs, ys, xs, ys + len),
+        }
+    }
+}
+
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Draws pixels, coalescing adjacent pixels in `direction` into runs which are each
+    /// written with a single address window and memory write. Pixels outside the display are
+    /// skipped.
+    pub(crate) fn draw_pixel_runs<I>(
+        &mut self,
+        pixels: I,
+        direction: Direction,
+    ) -> Result<(), Error<PinError, SpiError, CS::Error, RST::Error>>
+    where
+        I: IntoIterator<Item = Pixel<Rgb565>>,
+    {
+        let mut run = Run::new(direction);
+        for Pixel(point, color) in pixels {
+            let (x, y) = match self.display_point(point) {
+                Some(point) => point,
+                None => continue,
+            };
+            let color = RawU16::from(color).into_inner();
+            if !run.push(x, y, color) {
+                self.draw_run(&mut run)?;
+                run.push(x, y, color);
+            }
+        }
+
+        self.draw_run(&mut run)
+    }
+
+    /// Fills the rectangle from `xs`, `ys` to `xe`, `ye` with a single address window and
+    /// memory write. The rectangle is clipped to the display, nothing is drawn if it lies
+    /// outside.
+    pub(crate) fn fill_span(
+        &mut self,
+        xs: i32,
+        ys: i32,
+        xe: i32,
+        ye: i32,
+        color: u16,
+    ) -> Result<(), Error<PinError, SpiError, CS::Error, RST::Error>> {
+        let (width, height) = self.size();
+        let top_left = Point::new(xs.max(0), ys.max(0));
+        let bottom_right = Point::new(xe.min(i32::from(width) - 1), ye.min(i32::from(height) - 1));
+        let (xs, ys, xe, ye) = match self.display_window(top_left, bottom_right) {
+            Some(window) => window,
+            None => return Ok(()),
+        };
+
+        let count = usize::from(xe - xs + 1) * usize::from(ye - ys + 1);
+        self.set_pixels(xs, ys, xe, ye, core::iter::repeat(color).take(count))?;
+
+        Ok(())
+    }
+
+    /// Writes a run with one address window and memory write, then empties it.
+    fn draw_run(
+        &mut self,
+        run: &mut Run,
+    ) -> Result<(), Error<PinError, SpiError, CS::Error, RST::Error>> {
+        if run.len == 0 {
+            return Ok(());
+        }
+
+        let (xs, ys, xe, ye) = run.window();
+        let colors = &run.colors[..run.len.min(RUN_LEN)];
+        if run.len == 1 {
+            self.pixel(xs, ys, colors[0])?;
+        } else if run.solid {
+            self.set_pixels(xs, ys, xe, ye, core::iter::repeat(colors[0]).take(run.len))?;
+        } else if run.reversed {
+            self.set_pixels(xs, ys, xe, ye, colors.iter().rev().copied())?;
+        } else {
+            self.set_pixels(xs, ys, xe, ye, colors.iter().copied())?;
+        }
+        run.len = 0;
+
+        Ok(())
+    }
+}
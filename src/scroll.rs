@@ -0,0 +1,136 @@
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::command::Command;
+use crate::{Error, PageColumnOrder, TransferObserver, ST7789V};
+
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Defines the vertical scroll area.
+    ///
+    /// `tfa` rows at the top and `bfa` rows at the bottom stay fixed, the `vsa` rows between
+    /// them are scrolled. The three values have to add up to 320, the frame memory height,
+    /// otherwise `Error::InvalidParameter` is returned.
+    pub fn vertical_scroll_area(
+        &mut self,
+        tfa: u16,
+        vsa: u16,
+        bfa: u16,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        if u32::from(tfa) + u32::from(vsa) + u32::from(bfa) != 320 {
+            return Err(Error::InvalidParameter);
+        }
+
+        let mut params = [0u8; 6];
+        params[..2].copy_from_slice(&tfa.to_be_bytes());
+        params[2..4].copy_from_slice(&vsa.to_be_bytes());
+        params[4..].copy_from_slice(&bfa.to_be_bytes());
+        self.command(Command::VSCRDEF, Some(&params))?;
+        self.state.scroll_area = Some((tfa, vsa, bfa));
+
+        Ok(self)
+    }
+
+    /// Sets the frame memory row shown in the first row of the vertical scroll area.
+    pub fn vertical_scroll_start(
+        &mut self,
+        vsp: u16,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.command(Command::VSCRSADD, Some(&vsp.to_be_bytes()))?;
+        self.state.scroll_start = Some(vsp);
+
+        Ok(self)
+    }
+
+    /// Scrolls the content of the vertical scroll area up by `lines` rows and fills the rows
+    /// exposed at the bottom with the RGB565 color `fill`.
+    ///
+    /// The scroll start address advances by `lines`, wrapping within the scroll area. The rows
+    /// scrolled out at the top are filled before the scroll, as they are the ones shown at the
+    /// bottom afterwards. They are filled with a single memory write unless they wrap around
+    /// the end of the scroll area.
+    ///
+    /// The scroll always moves the panel rows along the gate lines. With exchanged page and
+    /// column order these run across the rotated picture, which then scrolls sideways.
+    ///
+    /// Returns `Error::NoScrollArea` if no scroll area was defined by
+    /// [`vertical_scroll_area`](#method.vertical_scroll_area).
+    pub fn scroll_up(
+        &mut self,
+        lines: u16,
+        fill: u16,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        let (tfa, vsa, _) = self.state.scroll_area.ok_or(Error::NoScrollArea)?;
+        if vsa == 0 {
+            return Ok(self);
+        }
+
+        let offset = self.scroll_offset();
+        let lines = lines.min(vsa);
+        let first = (vsa - offset).min(lines);
+        self.fill_rows(tfa + offset, first, fill)?;
+        self.fill_rows(tfa, lines - first, fill)?;
+
+        self.vertical_scroll_start(tfa + (offset + lines) % vsa)
+    }
+
+    /// Returns how many rows the vertical scroll area is currently scrolled up.
+    ///
+    /// Something drawn to row `y` of the scroll area before scrolling has to be drawn to row
+    /// `(y + offset) % vsa` to end up at the same position on the display, see
+    /// [`scroll_up`](#method.scroll_up).
+    pub fn scroll_offset(&self) -> u16 {
+        match (self.state.scroll_area, self.state.scroll_start) {
+            (Some((tfa, vsa, _)), Some(start)) if vsa > 0 => start.saturating_sub(tfa) % vsa,
+            _ => 0,
+        }
+    }
+
+    /// Fills `count` frame memory rows starting at row `rs` with the RGB565 `color`. The full
+    /// panel width is filled.
+    ///
+    /// The rows are the gate lines the vertical scroll moves. With exchanged page and column
+    /// order they are addressed by the column address, so the two ranges are swapped.
+    pub(crate) fn fill_rows(
+        &mut self,
+        rs: u16,
+        count: u16,
+        color: u16,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        if count == 0 {
+            return Ok(self);
+        }
+
+        let xs = self.panel.x_offset;
+        let width = self.panel.width;
+        let xe = xs.saturating_add(width.saturating_sub(1));
+        let re = rs.saturating_add(count - 1);
+        match self.madctl().get_page_column_order() {
+            PageColumnOrder::NormalMode => self.column_address(xs, xe)?.row_address(rs, re)?,
+            PageColumnOrder::ReverseMode => self.column_address(rs, re)?.row_address(xs, xe)?,
+        }
+        .start_memory_write()?;
+
+        let len = usize::from(width) * usize::from(count);
+        self.write_colors(core::iter::repeat(color).take(len))
+    }
+
+    /// Returns the frame memory row shown in panel row `y`, taking the vertical scroll area
+    /// and start address into account.
+    pub(crate) fn scrolled_row(&self, y: u16) -> u16 {
+        let row = self.panel.y_offset + y;
+        match self.state.scroll_area {
+            Some((tfa, vsa, _)) if row >= tfa && row - tfa < vsa => {
+                tfa + (row - tfa + self.scroll_offset()) % vsa
+            }
+            _ => row,
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use embedded_hal::blocking::delay::DelayMs;
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::color::{BLACK, BLUE, GREEN, RED, WHITE};
+use crate::{Error, TransferObserver, ST7789V};
+
+/// Full screen colors of the self test: red, green, blue and white
+const SELF_TEST_COLORS: [u16; 4] = [RED, GREEN, BLUE, WHITE];
+/// Time each full screen color is shown
+const SELF_TEST_STEP_MS: u16 = 500;
+/// Color of the border
+const BORDER_COLOR: u16 = WHITE;
+/// Color of the corner marker
+const MARKER_COLOR: u16 = RED;
+/// Side length of the corner marker
+const MARKER_SIZE: u16 = 16;
+
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Shows a test pattern to check a new board.
+    ///
+    /// Fills the screen red, green, blue and white for half a second each, then leaves a black
+    /// screen with a one pixel white border and a red square in the top left corner:
+    ///
+    /// * missing or colored edges of the border point to wrong panel size or offset
+    /// * the square in another corner points to wrong memory access control settings
+    /// * a blue square and swapped full screen colors point to a wrong color order
+    ///
+    /// The display has to be initialized with a 16 bit color format.
+    pub fn self_test<DELAY>(
+        &mut self,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<PinError, SpiError, CS::Error, RST::Error>>
+    where
+        DELAY: DelayMs<u16>,
+    {
+        for &color in SELF_TEST_COLORS.iter() {
+            self.clear(color)?;
+            delay.delay_ms(SELF_TEST_STEP_MS);
+        }
+
+        self.clear(BLACK)?;
+        let (width, height) = self.size();
+        if width == 0 || height == 0 {
+            return Ok(());
+        }
+        let (right, bottom) = (width - 1, height - 1);
+        self.fill_window(0, 0, right, 0, BORDER_COLOR)?
+            .fill_window(0, bottom, right, bottom, BORDER_COLOR)?
+            .fill_window(0, 0, 0, bottom, BORDER_COLOR)?
+            .fill_window(right, 0, right, bottom, BORDER_COLOR)?;
+
+        let marker_right = MARKER_SIZE.min(right.saturating_sub(1));
+        let marker_bottom = MARKER_SIZE.min(bottom.saturating_sub(1));
+        if marker_right >= 2 && marker_bottom >= 2 {
+            self.fill_window(2, 2, marker_right, marker_bottom, MARKER_COLOR)?;
+        }
+
+        Ok(())
+    }
+
+    /// Fills the window from `xs`, `ys` to `xe`, `ye` with the RGB565 `color`.
+    fn fill_window(
+        &mut self,
+        xs: u16,
+        ys: u16,
+        xe: u16,
+        ye: u16,
+        color: u16,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        let count = usize::from(xe - xs + 1) * usize::from(ye - ys + 1);
+        self.set_pixels(xs, ys, xe, ye, core::iter::repeat(color).take(count))
+    }
+}
+
+/// Self test with status read back
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError> + spi::Transfer<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Shows the [`self_test`](#method.self_test) pattern and [`verify`](#method.verify)s the
+    /// display status afterwards.
+    pub fn self_test_and_verify<DELAY>(
+        &mut self,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<PinError, SpiError, CS::Error, RST::Error>>
+    where
+        DELAY: DelayMs<u16>,
+    {
+        self.self_test(delay)?;
+        self.verify()
+    }
+}
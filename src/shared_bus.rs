@@ -0,0 +1,141 @@
+use core::cell::RefCell;
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{Error, NoPin, ST7789V};
+
+/// SPI bus and data/command pin shared by several displays
+///
+/// Each display has its own chip select pin and is created with
+/// [`ST7789V::with_shared_bus`], which borrows the bus. The drivers keep their own state,
+/// e.g. the address window, so they can be used alternately:
+///
+/// ```ignore
+/// let bus = SharedBus::new(spi, dc);
+/// let mut left = ST7789V::with_shared_bus(&bus, left_cs, left_rst)?;
+/// let mut right = ST7789V::with_shared_bus(&bus, right_cs, right_rst)?;
+///
+/// left.init(&mut delay)?;
+/// right.init(&mut delay)?;
+/// left.clear(0xF800)?;
+/// right.clear(0x001F)?;
+/// ```
+///
+/// The bus is borrowed for the duration of each transfer only, sharing it across interrupt
+/// handlers or threads needs additional locking.
+///
+/// [`ST7789V::with_shared_bus`]: struct.ST7789V.html#method.with_shared_bus
+pub struct SharedBus<SPI, DC> {
+    spi: RefCell<SPI>,
+    dc: RefCell<DC>,
+}
+
+impl<SPI, DC> SharedBus<SPI, DC> {
+    /// Creates a shared bus from the SPI bus and the data/command pin.
+    pub fn new(spi: SPI, dc: DC) -> Self {
+        SharedBus {
+            spi: RefCell::new(spi),
+            dc: RefCell::new(dc),
+        }
+    }
+
+    /// Releases the SPI bus and the data/command pin.
+    pub fn release(self) -> (SPI, DC) {
+        (self.spi.into_inner(), self.dc.into_inner())
+    }
+}
+
+/// Errors of a [`SharedSpi`](struct.SharedSpi.html) transfer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SharedSpiError<SpiError, PinError> {
+    /// SPI bus error
+    Spi(SpiError),
+    /// Chip select pin error
+    Cs(PinError),
+}
+
+/// SPI bus of a display on a [`SharedBus`](struct.SharedBus.html)
+///
+/// Selects the display with its chip select pin for every write.
+pub struct SharedSpi<'a, SPI, CS> {
+    spi: &'a RefCell<SPI>,
+    cs: CS,
+}
+
+impl<'a, SPI, CS> SharedSpi<'a, SPI, CS> {
+    /// Releases the chip select pin.
+    pub fn release(self) -> CS {
+        self.cs
+    }
+}
+
+impl<'a, SPI, CS> spi::Write<u8> for SharedSpi<'a, SPI, CS>
+where
+    SPI: spi::Write<u8>,
+    CS: OutputPin,
+{
+    type Error = SharedSpiError<SPI::Error, CS::Error>;
+
+    fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+        self.cs.set_low().map_err(SharedSpiError::Cs)?;
+        let result = self
+            .spi
+            .borrow_mut()
+            .write(words)
+            .map_err(SharedSpiError::Spi);
+        self.cs.set_high().map_err(SharedSpiError::Cs)?;
+
+        result
+    }
+}
+
+/// Data/command pin of a display on a [`SharedBus`](struct.SharedBus.html)
+pub struct SharedDc<'a, DC> {
+    dc: &'a RefCell<DC>,
+}
+
+impl<'a, DC> OutputPin for SharedDc<'a, DC>
+where
+    DC: OutputPin,
+{
+    type Error = DC::Error;
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.dc.borrow_mut().set_low()
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.dc.borrow_mut().set_high()
+    }
+}
+
+impl<'a, SPI, DC, CS, RST, PinError, SpiError>
+    ST7789V<SharedSpi<'a, SPI, CS>, NoPin<PinError>, SharedDc<'a, DC>, RST>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+{
+    /// Creates a new display instance on a shared SPI bus and data/command pin, selected by
+    /// its own chip select pin `cs`.
+    ///
+    /// The chip select pin is raised right away and only lowered while the display is
+    /// written to. As other displays change the data/command pin, the driver sets it before
+    /// every transfer.
+    pub fn with_shared_bus(
+        bus: &'a SharedBus<SPI, DC>,
+        mut cs: CS,
+        rst: RST,
+    ) -> Result<Self, Error<PinError, SharedSpiError<SpiError, CS::Error>, CS::Error, RST::Error>>
+    {
+        cs.set_high().map_err(Error::Cs)?;
+
+        let spi = SharedSpi { spi: &bus.spi, cs };
+        let mut display = Self::new(spi, SharedDc { dc: &bus.dc }, rst);
+        display.dc_shared = true;
+
+        Ok(display)
+    }
+}
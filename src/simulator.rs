@@ -0,0 +1,323 @@
+//! Desktop simulator for developing without hardware
+//!
+//! [`Simulator`] stands in for the SPI bus and the data/command pin like the
+//! [`recording`](../recording/index.html) backend, but interprets every transfer right away
+//! with a [`FrameMemory`]. The display is the regular driver, so drawing runs through the same
+//! code as on the target and the frame memory shows the effect of offsets, memory access
+//! control and clipping exactly as the SPI stream produces it.
+//!
+//! The content can be exported as PNG or drawn into any embedded-graphics `DrawTarget`, e.g.
+//! the display of `embedded-graphics-simulator` for a live window.
+//!
+//! ```
+//! use st7789v::simulator::Simulator;
+//!
+//! let simulator = Simulator::new();
+//! let mut display = simulator.display();
+//! display.init(&mut simulator.delay()).unwrap();
+//! display.pixel(1, 0, 0xF800).unwrap();
+//! assert_eq!(simulator.pixel(1, 0), 0xF800);
+//!
+//! let mut png = Vec::new();
+//! simulator.write_png(0, 0, 240, 240, &mut png).unwrap();
+//! ```
+//!
+//! [`Simulator`]: struct.Simulator.html
+//! [`FrameMemory`]: ../recording/struct.FrameMemory.html
+
+use core::convert::Infallible;
+use std::cell::RefCell;
+use std::io;
+use std::rc::Rc;
+use std::vec::Vec;
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::color;
+use crate::recording::{FrameMemory, NoDelay};
+use crate::{NoPin, ST7789V};
+
+/// A driver writing to a [`Simulator`](struct.Simulator.html)
+pub type SimulatorDisplay = ST7789V<SimulatorSpi, NoPin, SimulatorDc, NoPin>;
+
+/// Largest payload of a stored deflate block
+const STORED_BLOCK_LEN: usize = 0xFFFF;
+
+struct SimulatorState {
+    dc_high: bool,
+    memory: FrameMemory,
+}
+
+impl SimulatorState {
+    fn write(&mut self, words: &[u8]) {
+        if self.dc_high {
+            self.memory.data(words);
+        } else {
+            words.iter().for_each(|&cmd| self.memory.command(cmd));
+        }
+    }
+}
+
+/// In-memory display controller
+///
+/// Cloning the simulator shares the frame memory.
+#[derive(Clone)]
+pub struct Simulator {
+    state: Rc<RefCell<SimulatorState>>,
+}
+
+impl Default for Simulator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Simulator {
+    /// Creates a simulator with a 240x320 frame memory in its reset state, filled with black.
+    pub fn new() -> Self {
+        Self::with_memory(FrameMemory::new())
+    }
+
+    /// Creates a simulator with a frame memory of a different size, filled with black.
+    pub fn with_size(columns: u16, rows: u16) -> Self {
+        Self::with_memory(FrameMemory::with_size(columns, rows))
+    }
+
+    fn with_memory(memory: FrameMemory) -> Self {
+        Simulator {
+            state: Rc::new(RefCell::new(SimulatorState {
+                dc_high: false,
+                memory,
+            })),
+        }
+    }
+
+    /// Returns a simulated SPI bus.
+    pub fn spi(&self) -> SimulatorSpi {
+        SimulatorSpi {
+            state: self.state.clone(),
+        }
+    }
+
+    /// Returns a simulated data/command pin.
+    pub fn dc(&self) -> SimulatorDc {
+        SimulatorDc {
+            state: self.state.clone(),
+        }
+    }
+
+    /// Returns a delay provider which does not wait.
+    pub fn delay(&self) -> NoDelay {
+        NoDelay
+    }
+
+    /// Creates a driver writing to this simulator.
+    pub fn display(&self) -> SimulatorDisplay {
+        ST7789V::new(self.spi(), self.dc(), NoPin::new())
+    }
+
+    /// Returns the RGB565 color at frame memory column `x` and row `y`.
+    pub fn pixel(&self, x: u16, y: u16) -> u16 {
+        self.state.borrow().memory.pixel(x, y)
+    }
+
+    /// Returns the `width` by `height` area at column `x` and row `y` row by row.
+    ///
+    /// Panics if the area is not within the frame memory.
+    pub fn area(&self, x: u16, y: u16, width: u16, height: u16) -> Vec<u16> {
+        self.state.borrow().memory.area(x, y, width, height)
+    }
+
+    /// Writes the `width` by `height` area at column `x` and row `y` as 24 bit RGB PNG image.
+    ///
+    /// The image data is stored uncompressed, so the file is about three bytes per pixel.
+    /// Returns an `io::ErrorKind::InvalidInput` error if the area is empty or not within the
+    /// frame memory, nothing is written then.
+    pub fn write_png<W: io::Write>(
+        &self,
+        x: u16,
+        y: u16,
+        width: u16,
+        height: u16,
+        mut writer: W,
+    ) -> io::Result<()> {
+        if width == 0 || height == 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "empty area"));
+        }
+        if !self.state.borrow().memory.contains(x, y, width, height) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "area outside the frame memory",
+            ));
+        }
+
+        let mut scanlines = Vec::with_capacity((3 * usize::from(width) + 1) * usize::from(height));
+        for row in self.area(x, y, width, height).chunks(usize::from(width)) {
+            // filter type none
+            scanlines.push(0);
+            for &pixel in row {
+                let (r, g, b) = color::to_rgb888(pixel);
+                scanlines.extend_from_slice(&[r, g, b]);
+            }
+        }
+
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&u32::from(width).to_be_bytes());
+        header.extend_from_slice(&u32::from(height).to_be_bytes());
+        // 8 bit truecolor, deflate, no filters, no interlacing
+        header.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+        writer.write_all(b"\x89PNG\r\n\x1a\n")?;
+        write_chunk(&mut writer, b"IHDR", &header)?;
+        write_chunk(&mut writer, b"IDAT", &zlib_stored(&scanlines))?;
+        write_chunk(&mut writer, b"IEND", &[])
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl Simulator {
+    /// Draws the `width` by `height` area at column `x` and row `y` into `target`, with the
+    /// top left corner of the area at the origin.
+    ///
+    /// This hands the frame memory to any embedded-graphics target, e.g. the display of
+    /// `embedded-graphics-simulator` to show it in a window. Nothing is drawn for an empty
+    /// area.
+    ///
+    /// Panics if the area is not within the frame memory.
+    pub fn draw_into<T>(
+        &self,
+        x: u16,
+        y: u16,
+        width: u16,
+        height: u16,
+        target: &mut T,
+    ) -> Result<(), T::Error>
+    where
+        T: embedded_graphics::prelude::DrawTarget<embedded_graphics::pixelcolor::Rgb565>,
+    {
+        use embedded_graphics::drawable::Pixel;
+        use embedded_graphics::pixelcolor::raw::RawU16;
+        use embedded_graphics::prelude::Point;
+
+        let area = self.area(x, y, width, height);
+        let width = usize::from(width);
+        if area.is_empty() {
+            return Ok(());
+        }
+        target.draw_iter(area.into_iter().enumerate().map(|(i, color)| {
+            let point = Point::new((i % width) as i32, (i / width) as i32);
+            Pixel(point, RawU16::new(color).into())
+        }))
+    }
+}
+
+/// Writes a PNG chunk with its length and CRC.
+fn write_chunk<W: io::Write>(writer: &mut W, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    writer.write_all(&(data.len() as u32).to_be_bytes())?;
+    writer.write_all(kind)?;
+    writer.write_all(data)?;
+    let crc = crc32(crc32(0, kind), data);
+    writer.write_all(&crc.to_be_bytes())
+}
+
+/// Wraps `data` into a zlib stream of stored deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let blocks = data.len() / STORED_BLOCK_LEN + 1;
+    let mut stream = Vec::with_capacity(data.len() + 5 * blocks + 6);
+    // deflate with a 32 KiB window, no preset dictionary
+    stream.extend_from_slice(&[0x78, 0x01]);
+
+    let mut chunks = data.chunks(STORED_BLOCK_LEN).peekable();
+    if chunks.peek().is_none() {
+        stream.extend_from_slice(&[1, 0, 0, 0xFF, 0xFF]);
+    }
+    while let Some(chunk) = chunks.next() {
+        let last = chunks.peek().is_none();
+        let len = chunk.len() as u16;
+        stream.push(u8::from(last));
+        stream.extend_from_slice(&len.to_le_bytes());
+        stream.extend_from_slice(&(!len).to_le_bytes());
+        stream.extend_from_slice(chunk);
+    }
+
+    stream.extend_from_slice(&adler32(data).to_be_bytes());
+    stream
+}
+
+/// Continues the CRC-32 `crc` of the PNG specification over `data`.
+fn crc32(crc: u32, data: &[u8]) -> u32 {
+    let mut crc = !crc;
+    for &byte in data {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                crc >> 1 ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+
+    !crc
+}
+
+/// Returns the Adler-32 checksum of `data`.
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + u32::from(byte)) % 65521;
+        b = (b + a) % 65521;
+    }
+
+    b << 16 | a
+}
+
+/// SPI bus of a [`Simulator`](struct.Simulator.html)
+pub struct SimulatorSpi {
+    state: Rc<RefCell<SimulatorState>>,
+}
+
+impl spi::Write<u8> for SimulatorSpi {
+    type Error = Infallible;
+
+    fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+        self.state.borrow_mut().write(words);
+        Ok(())
+    }
+}
+
+impl spi::Transactional<u8> for SimulatorSpi {
+    type Error = Infallible;
+
+    fn exec(&mut self, operations: &mut [spi::Operation<'_, u8>]) -> Result<(), Self::Error> {
+        let mut state = self.state.borrow_mut();
+        for operation in operations {
+            match operation {
+                spi::Operation::Write(words) => state.write(words),
+                spi::Operation::Transfer(words) => state.write(words),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Data/command pin of a [`Simulator`](struct.Simulator.html)
+pub struct SimulatorDc {
+    state: Rc<RefCell<SimulatorState>>,
+}
+
+impl OutputPin for SimulatorDc {
+    type Error = Infallible;
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.state.borrow_mut().dc_high = false;
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.state.borrow_mut().dc_high = true;
+        Ok(())
+    }
+}
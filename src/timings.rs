@@ -0,0 +1,59 @@
+/// Delays used by the timed display commands in milliseconds
+///
+/// The defaults are conservative and work with most modules. [`Timings::minimal`] uses the
+/// minimum delays required by the datasheet, which speeds up initialization considerably.
+///
+/// [`Timings::minimal`]: #method.minimal
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Timings {
+    /// Reset pulse width and the time the reset line is held high before the pulse
+    pub reset_pulse_ms: u16,
+    /// Reset pulse width used by `hard_reset_us` in microseconds
+    pub reset_pulse_us: u32,
+    /// Wait after releasing the reset line
+    pub reset_ms: u16,
+    /// Wait after a soft reset
+    pub soft_reset_ms: u16,
+    /// Wait after turning the display off in `power_down`, at least one frame
+    pub display_off_ms: u16,
+    /// Wait after entering sleep mode
+    pub sleep_in_ms: u16,
+    /// Wait after leaving sleep mode
+    pub sleep_out_ms: u16,
+    /// Wait after setting the color mode
+    pub color_mode_ms: u16,
+}
+
+impl Default for Timings {
+    fn default() -> Self {
+        Timings {
+            reset_pulse_ms: 1,
+            reset_pulse_us: 10,
+            reset_ms: 120,
+            soft_reset_ms: 150,
+            display_off_ms: 20,
+            sleep_in_ms: 5,
+            sleep_out_ms: 500,
+            color_mode_ms: 10,
+        }
+    }
+}
+
+impl Timings {
+    /// Minimum delays required by the datasheet.
+    ///
+    /// Some clone controllers need longer delays than the datasheet specifies, fall back to the
+    /// defaults if the display does not come up reliably.
+    pub fn minimal() -> Self {
+        Timings {
+            reset_pulse_ms: 1,
+            reset_pulse_us: 10,
+            reset_ms: 120,
+            soft_reset_ms: 120,
+            display_off_ms: 17,
+            sleep_in_ms: 5,
+            sleep_out_ms: 120,
+            color_mode_ms: 0,
+        }
+    }
+}
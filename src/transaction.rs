@@ -0,0 +1,101 @@
+use embedded_hal::blocking::spi::{self, Operation};
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::command::Command;
+use crate::{Error, PixelFormat, TransferObserver, ST7789V};
+
+/// Grouped bus operations for SPI buses implementing `Transactional`
+///
+/// The controller samples the data/command pin with every byte, so a command byte and its
+/// parameters can never share a bus operation: the pin has to change in between. Every phase
+/// with a constant data/command level is issued as a single `exec` call instead, which merges
+/// any number of buffers into one transaction. This pays off on HALs where each bus call has
+/// setup cost, e.g. taking a mutex or preparing a DMA descriptor.
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError> + spi::Transactional<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Sets the address window and writes the RGB565 pixel data of all `operations` with a
+    /// single transaction.
+    ///
+    /// Returns [`Error::DisplayAsleep`](enum.Error.html#variant.DisplayAsleep) while the
+    /// display is in sleep mode.
+    ///
+    /// The data is sent as is, so it has to be big endian RGB565. A frame buffer held in
+    /// several slices, e.g. one per line, thereby costs a single bus call for the data phase.
+    ///
+    /// ```ignore
+    /// use embedded_hal::blocking::spi::Operation;
+    ///
+    /// let mut rows = [Operation::Write(&top), Operation::Write(&bottom)];
+    /// display.write_window_transaction(0, 0, 239, 1, &mut rows)?;
+    /// ```
+    pub fn write_window_transaction(
+        &mut self,
+        xs: u16,
+        ys: u16,
+        xe: u16,
+        ye: u16,
+        operations: &mut [Operation<'_, u8>],
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.check_pixel_format(PixelFormat::Rgb565)?;
+        self.address_window(xs, ys, xe, ye)?;
+        if self.state.asleep {
+            return Err(Error::DisplayAsleep);
+        }
+
+        self.command_transaction(Command::RAMWR, operations)
+    }
+
+    /// Sends a command and all of its parameter `operations` as two transactions.
+    fn command_transaction(
+        &mut self,
+        cmd: Command,
+        operations: &mut [Operation<'_, u8>],
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        if self.state.command2_locked && cmd.is_command2() {
+            return Err(Error::Command2Locked);
+        }
+
+        let params = operations.iter().map(operation_len).sum();
+        cmd.debug_check_parameters(params);
+        self.observer.on_command(cmd.value(), params);
+        self.end_transaction()?;
+        self.exec_phase(false, &mut [Operation::Write(&[cmd.value()])])?;
+        if !operations.is_empty() {
+            self.observer.on_data(params);
+        }
+
+        self.exec_phase(true, operations)?.end_transaction()
+    }
+
+    /// Sets the data/command pin and issues `operations` as a single transaction. Nothing is
+    /// sent if there are no operations.
+    fn exec_phase(
+        &mut self,
+        dc_high: bool,
+        operations: &mut [Operation<'_, u8>],
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        if operations.is_empty() {
+            return Ok(self);
+        }
+
+        self.select()?.set_dc(dc_high)?;
+        self.spi.exec(operations).map_err(Error::Spi)?;
+
+        Ok(self)
+    }
+}
+
+/// Returns the number of bytes an operation transfers.
+fn operation_len(operation: &Operation<'_, u8>) -> usize {
+    match operation {
+        Operation::Write(words) => words.len(),
+        Operation::Transfer(words) => words.len(),
+    }
+}
@@ -0,0 +1,196 @@
+use core::marker::PhantomData;
+
+use embedded_hal::blocking::delay::DelayMs;
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{Error, InitOptions, ST7789VConfig, TransferObserver, ST7789V};
+
+/// State of a display which offers every command, the default
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Ready;
+
+/// State of a display which has to be initialized before it is used
+///
+/// A display in this state only offers `init`, `init_with`, `release`, `into_parts` and
+/// `power_down`. Initializing it consumes the display and returns it in the
+/// [`Ready`](struct.Ready.html) state, so drawing before the controller left sleep mode and got
+/// its color format is caught at compile time:
+///
+/// ```no_run
+/// use embedded_hal_mock::delay::MockNoop;
+/// use embedded_hal_mock::pin::Mock as PinMock;
+/// use embedded_hal_mock::spi::Mock as SpiMock;
+/// use st7789v::ST7789V;
+///
+/// let display = ST7789V::new(SpiMock::new(&[]), PinMock::new(&[]), PinMock::new(&[]));
+/// let mut display = display.into_uninitialized().init(&mut MockNoop::new()).unwrap();
+/// display.pixel(0, 0, 0xF800).unwrap();
+/// ```
+///
+/// ```compile_fail,E0599
+/// use embedded_hal_mock::pin::Mock as PinMock;
+/// use embedded_hal_mock::spi::Mock as SpiMock;
+/// use st7789v::ST7789V;
+///
+/// let display = ST7789V::new(SpiMock::new(&[]), PinMock::new(&[]), PinMock::new(&[]));
+/// let mut display = display.into_uninitialized();
+/// display.pixel(0, 0, 0xF800).unwrap();
+/// ```
+///
+/// A hard reset returns the display to this state, see
+/// [`reset_into_uninitialized`](struct.ST7789V.html#method.reset_into_uninitialized):
+///
+/// ```compile_fail,E0599
+/// use embedded_hal_mock::delay::MockNoop;
+/// use embedded_hal_mock::pin::Mock as PinMock;
+/// use embedded_hal_mock::spi::Mock as SpiMock;
+/// use st7789v::ST7789V;
+///
+/// let mut display = ST7789V::new(SpiMock::new(&[]), PinMock::new(&[]), PinMock::new(&[]));
+/// display.init(&mut MockNoop::new()).unwrap();
+/// let mut display = display.reset_into_uninitialized(&mut MockNoop::new()).unwrap();
+/// display.clear(0).unwrap();
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Uninitialized;
+
+impl<SPI, CS, DC, RST, D, O, const BUF: usize, S> ST7789V<SPI, CS, DC, RST, D, O, BUF, S>
+where
+    SPI: spi::Write<u8>,
+    CS: OutputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// Changes the state marker, the driver state is kept.
+    fn into_state<S2>(self) -> ST7789V<SPI, CS, DC, RST, D, O, BUF, S2> {
+        ST7789V {
+            spi: self.spi,
+            cfg: self.cfg,
+            panel: self.panel,
+            dc_level: self.dc_level,
+            dc_shared: self.dc_shared,
+            cs_per_transaction: self.cs_per_transaction,
+            cs_selected: self.cs_selected,
+            timings: self.timings,
+            state: self.state,
+            observer: self.observer,
+            retry: self.retry,
+            typestate: PhantomData,
+        }
+    }
+}
+
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Marks the display as [`Uninitialized`](struct.Uninitialized.html), so it has to be
+    /// initialized before it can be used. Nothing is sent to the display.
+    ///
+    /// ```ignore
+    /// let display = ST7789V::new(spi, dc, rst).into_uninitialized();
+    /// let mut display = display.init(&mut delay)?;
+    /// ```
+    pub fn into_uninitialized(self) -> ST7789V<SPI, CS, DC, RST, D, O, BUF, Uninitialized> {
+        self.into_state()
+    }
+
+    /// Performs a [`hard_reset`](#method.hard_reset) and returns the display in the
+    /// [`Uninitialized`](struct.Uninitialized.html) state.
+    pub fn reset_into_uninitialized<DELAY>(
+        mut self,
+        delay: &mut DELAY,
+    ) -> Result<
+        ST7789V<SPI, CS, DC, RST, D, O, BUF, Uninitialized>,
+        Error<PinError, SpiError, CS::Error, RST::Error>,
+    >
+    where
+        DELAY: DelayMs<u16>,
+    {
+        self.hard_reset(delay)?;
+
+        Ok(self.into_state())
+    }
+}
+
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF, Uninitialized>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Initializes the display with the default [`InitOptions`](struct.InitOptions.html) and
+    /// returns it in the [`Ready`](struct.Ready.html) state.
+    pub fn init<DELAY>(
+        self,
+        delay: &mut DELAY,
+    ) -> Result<
+        ST7789V<SPI, CS, DC, RST, D, O, BUF>,
+        Error<PinError, SpiError, CS::Error, RST::Error>,
+    >
+    where
+        DELAY: DelayMs<u16>,
+    {
+        self.init_with(InitOptions::default(), delay)
+    }
+
+    /// Initializes the display with `options` and returns it in the
+    /// [`Ready`](struct.Ready.html) state.
+    pub fn init_with<DELAY>(
+        self,
+        options: InitOptions,
+        delay: &mut DELAY,
+    ) -> Result<
+        ST7789V<SPI, CS, DC, RST, D, O, BUF>,
+        Error<PinError, SpiError, CS::Error, RST::Error>,
+    >
+    where
+        DELAY: DelayMs<u16>,
+    {
+        let mut display = self.into_state::<Ready>();
+        display.init_with(options, delay)?;
+
+        Ok(display)
+    }
+
+    /// Release the SPI bus and display config. This will also raise the chip select pin.
+    pub fn release(
+        self,
+    ) -> Result<
+        (SPI, ST7789VConfig<CS, DC, RST, D>),
+        Error<PinError, SpiError, CS::Error, RST::Error>,
+    > {
+        self.into_state::<Ready>().release()
+    }
+
+    /// Release the SPI bus and every pin and the delay provider without touching them, like
+    /// the `into_parts` of a ready display.
+    pub fn into_parts(self) -> (SPI, Option<CS>, DC, RST, D) {
+        self.into_state::<Ready>().into_parts()
+    }
+
+    /// Shuts the display down before its supply is cut and releases the SPI bus and config,
+    /// like the `power_down` of a ready display.
+    pub fn power_down<DELAY>(
+        self,
+        delay: &mut DELAY,
+        hold_reset: bool,
+    ) -> Result<
+        (SPI, ST7789VConfig<CS, DC, RST, D>),
+        Error<PinError, SpiError, CS::Error, RST::Error>,
+    >
+    where
+        DELAY: DelayMs<u16>,
+    {
+        self.into_state::<Ready>().power_down(delay, hold_reset)
+    }
+}
@@ -0,0 +1,164 @@
+use embedded_hal::blocking::delay::DelayMs;
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::command::Command;
+use crate::{Error, InitOptions, TransferObserver, ST7789V};
+
+/// RDDPM booster voltage status
+const RDDPM_BOOSTER_ON: u8 = 0b1000_0000;
+/// RDDPM sleep out
+const RDDPM_SLEEP_OUT: u8 = 0b0001_0000;
+/// RDDPM display on
+const RDDPM_DISPLAY_ON: u8 = 0b0000_0100;
+
+/// RDDST booster voltage status
+const RDDST_BOOSTER_ON: u32 = 1 << 31;
+/// RDDST sleep out
+const RDDST_SLEEP_OUT: u32 = 1 << 17;
+/// RDDST display on
+const RDDST_DISPLAY_ON: u32 = 1 << 10;
+
+/// MADCTL bits returned by RDDMADCTL, the two lowest bits are not read back
+const RDD_MADCTL_MASK: u8 = 0b1111_1100;
+/// COLMOD bits returned by RDDCOLMOD
+const RDD_COLMOD_MASK: u8 = 0b0111_0111;
+
+/// Status verification
+///
+/// Reading needs the SPI bus to receive data from the controller, see
+/// [`copy_area`](struct.ST7789V.html#method.copy_area).
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError> + spi::Transfer<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Reads the power mode (RDDPM) and the display status (RDDST) and checks that the display
+    /// left sleep mode, is on and its booster runs.
+    ///
+    /// Returns `Error::VerificationFailed` with both responses otherwise, e.g. if the display
+    /// did not receive the initialization because of a wiring problem.
+    pub fn verify(&mut self) -> Result<(), Error<PinError, SpiError, CS::Error, RST::Error>> {
+        let mut power_mode = [0; 1];
+        self.read_command(Command::RDDPM, &mut power_mode)?;
+        let power_mode = power_mode[0];
+
+        // RDDST starts with a dummy clock cycle, so the status is off by one bit
+        let mut response = [0; 5];
+        self.read_command(Command::RDDST, &mut response)?;
+        let status = (u64::from_be_bytes([
+            0,
+            0,
+            0,
+            response[0],
+            response[1],
+            response[2],
+            response[3],
+            response[4],
+        ]) >> 7) as u32;
+
+        let power_mode_ok = power_mode & (RDDPM_BOOSTER_ON | RDDPM_SLEEP_OUT | RDDPM_DISPLAY_ON)
+            == RDDPM_BOOSTER_ON | RDDPM_SLEEP_OUT | RDDPM_DISPLAY_ON;
+        let status_ok = status & (RDDST_BOOSTER_ON | RDDST_SLEEP_OUT | RDDST_DISPLAY_ON)
+            == RDDST_BOOSTER_ON | RDDST_SLEEP_OUT | RDDST_DISPLAY_ON;
+        if !power_mode_ok || !status_ok {
+            return Err(Error::VerificationFailed { power_mode, status });
+        }
+
+        Ok(())
+    }
+
+    /// Initializes the display like [`init`](#method.init) and [`verify`](#method.verify)s
+    /// the result.
+    pub fn init_and_verify<DELAY>(
+        &mut self,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<PinError, SpiError, CS::Error, RST::Error>>
+    where
+        DELAY: DelayMs<u16>,
+    {
+        self.init(delay)?;
+        self.verify()
+    }
+
+    /// Reads the memory data access control (RDDMADCTL) and compares it with the last value
+    /// written with [`memory_access_control`](#method.memory_access_control).
+    ///
+    /// Returns `Error::ReadbackMismatch` if they differ, e.g. because a glitch on the
+    /// data/command line corrupted the MADCTL command. Nothing is compared if the driver did
+    /// not write MADCTL yet.
+    pub fn verify_madctl(
+        &mut self,
+    ) -> Result<(), Error<PinError, SpiError, CS::Error, RST::Error>> {
+        match self.state.madctl {
+            Some(expected) => self.verify_register(Command::RDD_MADCTL, expected, RDD_MADCTL_MASK),
+            None => Ok(()),
+        }
+    }
+
+    /// Reads the interface color format (RDDCOLMOD) and compares it with the last value
+    /// written with [`set_color_format`](#method.set_color_format).
+    ///
+    /// Returns `Error::ReadbackMismatch` if they differ. Nothing is compared if the driver did
+    /// not write COLMOD yet.
+    pub fn verify_colmod(
+        &mut self,
+    ) -> Result<(), Error<PinError, SpiError, CS::Error, RST::Error>> {
+        match self.state.color_format {
+            Some(color_format) => {
+                self.verify_register(Command::RDD_COLMOD, color_format.value(), RDD_COLMOD_MASK)
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Checks that the display uses the memory access control and color format the driver
+    /// wrote, see [`verify_madctl`](#method.verify_madctl) and
+    /// [`verify_colmod`](#method.verify_colmod).
+    pub fn verify_config(
+        &mut self,
+    ) -> Result<(), Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.verify_madctl()?;
+        self.verify_colmod()
+    }
+
+    /// Initializes the display like [`init_with`](#method.init_with), [`verify`](#method.verify)s
+    /// its status and [`verify_config`](#method.verify_config)s the written registers, to
+    /// detect marginal wiring right at startup.
+    pub fn init_with_and_verify<DELAY>(
+        &mut self,
+        options: InitOptions,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<PinError, SpiError, CS::Error, RST::Error>>
+    where
+        DELAY: DelayMs<u16>,
+    {
+        self.init_with(options, delay)?;
+        self.verify()?;
+        self.verify_config()
+    }
+
+    /// Reads the single byte register `cmd` and compares the bits in `mask` with `expected`.
+    fn verify_register(
+        &mut self,
+        cmd: Command,
+        expected: u8,
+        mask: u8,
+    ) -> Result<(), Error<PinError, SpiError, CS::Error, RST::Error>> {
+        let mut response = [0; 1];
+        self.read_command(cmd, &mut response)?;
+        if response[0] & mask != expected & mask {
+            return Err(Error::ReadbackMismatch {
+                register: cmd.value(),
+                expected,
+                got: response[0],
+            });
+        }
+
+        Ok(())
+    }
+}
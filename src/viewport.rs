@@ -0,0 +1,106 @@
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{Error, Panel, TransferObserver, ST7789V};
+
+/// Viewport panning
+///
+/// The frame memory has 320 rows while many panels show only 240 of them. The rows outside the
+/// panel can hold content which is panned into view with the vertical scroll start address,
+/// e.g. to render the next screen off-screen and show it without a visible redraw.
+///
+/// Three coordinate systems take part:
+///
+/// * panel coordinates, used by all drawing functions: row 0 is the first visible row and the
+///   rows and columns are shifted by the offsets set with
+///   [`InitOptions::offset`](struct.InitOptions.html#method.offset)
+/// * frame memory rows, used by [`draw_to_ram`](#method.draw_to_ram), the scroll area and the
+///   viewport origin, independent of the offsets
+/// * display lines, the physical rows of the glass
+///
+/// The vertical scroll area defined with
+/// [`vertical_scroll_area`](#method.vertical_scroll_area) splits the display lines into a top
+/// fixed area of `tfa` lines, the scroll area of `vsa` lines and a bottom fixed area. Lines
+/// in the fixed areas always show the frame memory row with their number. Line `tfa + i` of
+/// the scroll area shows row `tfa + (i + offset) % vsa`, where the offset is set by the scroll
+/// start address. Rows of the scroll area wrap around: panning past its last row shows its
+/// first rows again, not the bottom fixed area.
+///
+/// Drawing functions do not follow the scroll offset, a pixel drawn to panel row `y` is
+/// written to frame memory row `y + y_offset`, which is only shown in panel row `y` while the
+/// viewport origin is at `y_offset`.
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Pans the viewport so the first panel row shows frame memory row `line`.
+    ///
+    /// The scroll start address is computed from the panel row offset and wraps around within
+    /// the scroll area, so `line` and the following panel height rows may cross its end. If no
+    /// scroll area was defined, the whole frame memory becomes the scroll area.
+    ///
+    /// ```ignore
+    /// // prepare 80 rows below the visible 240 rows and pan them into view
+    /// display.draw_to_ram(|display| display.set_pixels(0, 240, 239, 319, image))?;
+    /// for line in 1..=80 {
+    ///     display.set_viewport_origin(line)?;
+    /// }
+    /// ```
+    ///
+    /// Returns `Error::InvalidParameter` if `line` or the first panel row lies in a fixed area.
+    pub fn set_viewport_origin(
+        &mut self,
+        line: u16,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        if self.state.scroll_area.is_none() {
+            self.vertical_scroll_area(0, 320, 0)?;
+        }
+        let (tfa, vsa, _) = self.state.scroll_area.ok_or(Error::NoScrollArea)?;
+        let in_scroll_area = |row: u16| row >= tfa && row - tfa < vsa;
+        let first_row = self.panel.y_offset;
+        if !in_scroll_area(line) || !in_scroll_area(first_row) {
+            return Err(Error::InvalidParameter);
+        }
+
+        let offset = (vsa + (line - tfa) - (first_row - tfa)) % vsa;
+        self.vertical_scroll_start(tfa + offset)
+    }
+
+    /// Returns the frame memory row shown in the first panel row, see
+    /// [`set_viewport_origin`](#method.set_viewport_origin).
+    pub fn viewport_origin(&self) -> u16 {
+        self.scrolled_row(0)
+    }
+
+    /// Runs `draw` with coordinates addressing the whole frame memory instead of the panel.
+    ///
+    /// Inside `draw` the offsets are zero and the size is the frame memory size, 240x320 or
+    /// 320x240 with exchanged page and column order, so every drawing function writes to
+    /// absolute frame memory coordinates, e.g. to rows outside the visible panel. The panel
+    /// geometry is restored afterwards, also if `draw` fails.
+    ///
+    /// ```ignore
+    /// display.draw_to_ram(|display| display.set_pixels(0, 280, 239, 319, image))?;
+    /// ```
+    pub fn draw_to_ram<F, R>(&mut self, draw: F) -> R
+    where
+        F: FnOnce(&mut Self) -> R,
+    {
+        let panel = self.panel;
+        self.panel = Panel {
+            width: 240,
+            height: 320,
+            x_offset: 0,
+            y_offset: 0,
+        };
+        let result = draw(self);
+        self.panel = panel;
+
+        result
+    }
+}
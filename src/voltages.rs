@@ -0,0 +1,306 @@
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::command::Command;
+use crate::{Error, TransferObserver, ST7789V};
+
+/// VCOM voltage (VCOMS)
+///
+/// Ranges from 0.1 V to 1.675 V in 25 mV steps, register code `n` is `0.1 V + n * 25 mV`.
+/// The reset default is 0.9 V.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VcomSetting(u8);
+
+impl VcomSetting {
+    /// Creates the setting from the voltage in millivolts, rounded down to a 25 mV step.
+    /// Returns `None` outside of 100 mV to 1675 mV.
+    pub fn from_millivolts(millivolts: u16) -> Option<Self> {
+        if !(100..=1675).contains(&millivolts) {
+            return None;
+        }
+
+        Some(VcomSetting(((millivolts - 100) / 25) as u8))
+    }
+
+    /// Creates the setting from the register code, returns `None` for codes above `0x3F`.
+    pub fn from_code(code: u8) -> Option<Self> {
+        if code > 0x3F {
+            return None;
+        }
+
+        Some(VcomSetting(code))
+    }
+
+    /// Returns the register code.
+    pub fn code(self) -> u8 {
+        self.0
+    }
+
+    /// Returns the voltage in millivolts.
+    pub fn millivolts(self) -> u16 {
+        100 + u16::from(self.0) * 25
+    }
+}
+
+impl Default for VcomSetting {
+    fn default() -> Self {
+        VcomSetting(0x20)
+    }
+}
+
+/// VCOM offset voltage (VCMOFSET)
+///
+/// Ranges from -0.8 V to 0.775 V in 25 mV steps, register code `n` is `n * 25 mV - 0.8 V`.
+/// The reset default is 0 V.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VcomOffset(u8);
+
+impl VcomOffset {
+    /// Creates the offset from the voltage in millivolts, rounded down to a 25 mV step.
+    /// Returns `None` outside of -800 mV to 775 mV.
+    pub fn from_millivolts(millivolts: i16) -> Option<Self> {
+        if !(-800..=775).contains(&millivolts) {
+            return None;
+        }
+
+        Some(VcomOffset(((millivolts + 800) / 25) as u8))
+    }
+
+    /// Creates the offset from the register code, returns `None` for codes above `0x3F`.
+    pub fn from_code(code: u8) -> Option<Self> {
+        if code > 0x3F {
+            return None;
+        }
+
+        Some(VcomOffset(code))
+    }
+
+    /// Returns the register code.
+    pub fn code(self) -> u8 {
+        self.0
+    }
+
+    /// Returns the voltage in millivolts.
+    pub fn millivolts(self) -> i16 {
+        i16::from(self.0) * 25 - 800
+    }
+}
+
+impl Default for VcomOffset {
+    fn default() -> Self {
+        VcomOffset(0x20)
+    }
+}
+
+/// Gate high voltage (VGHS)
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GateHighVoltage {
+    /// 12.2 V
+    V12_20 = 0x00,
+    /// 12.54 V
+    V12_54 = 0x01,
+    /// 12.89 V
+    V12_89 = 0x02,
+    /// 13.26 V, the reset default
+    V13_26 = 0x03,
+    /// 13.65 V
+    V13_65 = 0x04,
+    /// 14.06 V
+    V14_06 = 0x05,
+    /// 14.5 V
+    V14_50 = 0x06,
+    /// 14.97 V
+    V14_97 = 0x07,
+}
+
+impl GateHighVoltage {
+    pub fn value(self) -> u8 {
+        self as u8
+    }
+}
+
+/// Gate low voltage (VGLS)
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GateLowVoltage {
+    /// -7.16 V
+    V7_16 = 0x00,
+    /// -7.67 V
+    V7_67 = 0x01,
+    /// -8.23 V
+    V8_23 = 0x02,
+    /// -8.87 V
+    V8_87 = 0x03,
+    /// -9.6 V
+    V9_60 = 0x04,
+    /// -10.43 V, the reset default
+    V10_43 = 0x05,
+    /// -11.38 V
+    V11_38 = 0x06,
+    /// -12.5 V
+    V12_50 = 0x07,
+}
+
+impl GateLowVoltage {
+    pub fn value(self) -> u8 {
+        self as u8
+    }
+}
+
+/// VRH voltage (VRHS), which sets the GVDD source driver voltage
+///
+/// Ranges from 3.55 V to 5.5 V in 50 mV steps, register code `n` is `3.55 V + n * 50 mV`.
+/// Codes above `0x27` are reserved. The reset default is 4.1 V.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VrhSetting(u8);
+
+impl VrhSetting {
+    /// Creates the setting from the voltage in millivolts, rounded down to a 50 mV step.
+    /// Returns `None` outside of 3550 mV to 5500 mV.
+    pub fn from_millivolts(millivolts: u16) -> Option<Self> {
+        if !(3550..=5500).contains(&millivolts) {
+            return None;
+        }
+
+        Some(VrhSetting(((millivolts - 3550) / 50) as u8))
+    }
+
+    /// Creates the setting from the register code, returns `None` for the reserved codes
+    /// above `0x27`.
+    pub fn from_code(code: u8) -> Option<Self> {
+        if code > 0x27 {
+            return None;
+        }
+
+        Some(VrhSetting(code))
+    }
+
+    /// Returns the register code.
+    pub fn code(self) -> u8 {
+        self.0
+    }
+
+    /// Returns the voltage in millivolts.
+    pub fn millivolts(self) -> u16 {
+        3550 + u16::from(self.0) * 50
+    }
+}
+
+impl Default for VrhSetting {
+    fn default() -> Self {
+        VrhSetting(0x0B)
+    }
+}
+
+/// VDV voltage (VDVS), which offsets the source driver voltages
+///
+/// Ranges from -0.8 V to 0.775 V in 25 mV steps, register code `n` is `n * 25 mV - 0.8 V`.
+/// The reset default is 0 V.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VdvSetting(u8);
+
+impl VdvSetting {
+    /// Creates the setting from the voltage in millivolts, rounded down to a 25 mV step.
+    /// Returns `None` outside of -800 mV to 775 mV.
+    pub fn from_millivolts(millivolts: i16) -> Option<Self> {
+        VcomOffset::from_millivolts(millivolts).map(|offset| VdvSetting(offset.code()))
+    }
+
+    /// Creates the setting from the register code, returns `None` for codes above `0x3F`.
+    pub fn from_code(code: u8) -> Option<Self> {
+        VcomOffset::from_code(code).map(|offset| VdvSetting(offset.code()))
+    }
+
+    /// Returns the register code.
+    pub fn code(self) -> u8 {
+        self.0
+    }
+
+    /// Returns the voltage in millivolts.
+    pub fn millivolts(self) -> i16 {
+        i16::from(self.0) * 25 - 800
+    }
+}
+
+impl Default for VdvSetting {
+    fn default() -> Self {
+        VdvSetting(0x20)
+    }
+}
+
+/// Panel voltage settings
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Sets the VCOM voltage. Panel vendors often recommend a value other than the default to
+    /// reduce ghosting or flicker.
+    pub fn set_vcom(
+        &mut self,
+        vcom: VcomSetting,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.command(Command::VCOMS, Some(&[vcom.code()]))?;
+
+        Ok(self)
+    }
+
+    /// Sets the VCOM offset voltage, which is added to the VCOM voltage.
+    pub fn set_vcom_offset(
+        &mut self,
+        offset: VcomOffset,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.command(Command::VCMOFSET, Some(&[offset.code()]))?;
+
+        Ok(self)
+    }
+
+    /// Sets the gate driver high and low voltages.
+    pub fn set_gate_voltages(
+        &mut self,
+        vghs: GateHighVoltage,
+        vgls: GateLowVoltage,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.command(Command::GCTRL, Some(&[vghs.value() << 4 | vgls.value()]))?;
+
+        Ok(self)
+    }
+
+    /// Selects whether the VRH and VDV voltages are taken from
+    /// [`set_vrh`](#method.set_vrh) and [`set_vdv`](#method.set_vdv), or from NVM.
+    pub fn vdv_vrh_from_commands(
+        &mut self,
+        enable: bool,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.command(Command::VDVVRHEN, Some(&[u8::from(enable), 0xFF]))?;
+
+        Ok(self)
+    }
+
+    /// Sets the VRH voltage. Only used after enabling
+    /// [`vdv_vrh_from_commands`](#method.vdv_vrh_from_commands).
+    pub fn set_vrh(
+        &mut self,
+        vrh: VrhSetting,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.command(Command::VRHS, Some(&[vrh.code()]))?;
+
+        Ok(self)
+    }
+
+    /// Sets the VDV voltage. Only used after enabling
+    /// [`vdv_vrh_from_commands`](#method.vdv_vrh_from_commands).
+    pub fn set_vdv(
+        &mut self,
+        vdv: VdvSetting,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.command(Command::VDVS, Some(&[vdv.code()]))?;
+
+        Ok(self)
+    }
+}
@@ -0,0 +1,79 @@
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::{InputPin, OutputPin};
+
+use crate::command::Command;
+use crate::{Error, TransferObserver, ST7789V};
+
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Turns the tearing effect output on. The TE pin is pulsed high during vertical blanking.
+    pub fn tearing_effect_on(
+        &mut self,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.command(Command::TEON, Some(&[0x00]))?;
+
+        Ok(self)
+    }
+
+    /// Turns the tearing effect output off.
+    pub fn tearing_effect_off(
+        &mut self,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.command(Command::TEOFF, None)?;
+
+        Ok(self)
+    }
+
+    /// Waits for the start of the next vertical blanking period.
+    ///
+    /// Polls `te` until it sees a rising edge: the pin is first waited for to be low, so a
+    /// pulse which is already running is not mistaken for the start of a new one. Returns
+    /// `Error::VsyncTimeout` if no edge is seen within `timeout_iters` polls, e.g. because
+    /// the pin is miswired or the tearing effect output is off.
+    pub fn wait_for_vsync<TE>(
+        &mut self,
+        te: &mut TE,
+        timeout_iters: u32,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>>
+    where
+        TE: InputPin<Error = PinError>,
+    {
+        let mut low_seen = false;
+        for _ in 0..timeout_iters {
+            let high = te.is_high().map_err(Error::Te)?;
+            if !high {
+                low_seen = true;
+            } else if low_seen {
+                return Ok(self);
+            }
+        }
+
+        Err(Error::VsyncTimeout)
+    }
+
+    /// Waits for the next vertical blanking period, see
+    /// [`wait_for_vsync`](#method.wait_for_vsync), then calls `draw` with the display.
+    ///
+    /// The frame memory write in `draw` has to keep ahead of the display scan to avoid
+    /// tearing, so it should only push prepared pixel data.
+    pub fn draw_synced<TE, F, R>(
+        &mut self,
+        te: &mut TE,
+        timeout_iters: u32,
+        draw: F,
+    ) -> Result<R, Error<PinError, SpiError, CS::Error, RST::Error>>
+    where
+        TE: InputPin<Error = PinError>,
+        F: FnOnce(&mut Self) -> Result<R, Error<PinError, SpiError, CS::Error, RST::Error>>,
+    {
+        self.wait_for_vsync(te, timeout_iters)?;
+        draw(self)
+    }
+}
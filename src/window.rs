@@ -0,0 +1,207 @@
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{Error, PixelFormat, TransferObserver, ST7789V};
+
+/// Incremental pixel write into a window
+///
+/// Created by [`ST7789V::window`]. The address window is set and the memory write is started
+/// once, pushed RGB565 colors are collected in a `BUF` byte chunk and written to the bus
+/// whenever the chunk is full.
+///
+/// [`finish`](#method.finish) writes the rest of the chunk and checks that the window was
+/// filled completely. Dropping the writer without finishing it writes the collected colors as
+/// well, but bus errors and a short count go unnoticed, so prefer `finish`.
+///
+/// ```ignore
+/// let mut window = display.window(10, 10, 4, 2)?;
+/// for i in 0..8 {
+///     window.push(if i % 2 == 0 { 0xFFFF } else { 0x0000 })?;
+/// }
+/// window.finish()?;
+/// ```
+///
+/// [`ST7789V::window`]: struct.ST7789V.html#method.window
+pub struct WindowWriter<'a, SPI, CS, DC, RST, D = (), O = (), const BUF: usize = 128>
+where
+    SPI: spi::Write<u8>,
+    CS: OutputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    display: &'a mut ST7789V<SPI, CS, DC, RST, D, O, BUF>,
+    buffer: [u8; BUF],
+    len: usize,
+    written: u32,
+    expected: u32,
+}
+
+impl<'a, SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    WindowWriter<'a, SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Pushes the next RGB565 color.
+    ///
+    /// Returns `Error::PixelCountMismatch` if the window is already full.
+    pub fn push(
+        &mut self,
+        color: u16,
+    ) -> Result<(), Error<PinError, SpiError, CS::Error, RST::Error>> {
+        if self.written == self.expected {
+            return Err(Error::PixelCountMismatch {
+                expected: self.expected,
+                written: self.written + 1,
+            });
+        }
+
+        self.buffer[self.len..self.len + 2].copy_from_slice(&color.to_be_bytes());
+        self.len += 2;
+        self.written += 1;
+        if self.len + 2 > BUF {
+            self.flush()?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the number of pixels still missing to fill the window.
+    pub fn remaining(&self) -> u32 {
+        self.expected - self.written
+    }
+
+    /// Writes the collected colors and ends the window.
+    ///
+    /// Returns `Error::PixelCountMismatch` if less pixels than the window holds were pushed.
+    pub fn finish(mut self) -> Result<(), Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.flush()?;
+        self.display.end_transaction()?;
+        if self.written != self.expected {
+            return Err(Error::PixelCountMismatch {
+                expected: self.expected,
+                written: self.written,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Writes the collected colors.
+    fn flush(&mut self) -> Result<(), Error<PinError, SpiError, CS::Error, RST::Error>> {
+        let len = core::mem::take(&mut self.len);
+        self.display.write_data(&self.buffer[..len])?;
+
+        Ok(())
+    }
+}
+
+impl<'a, SPI, CS, DC, RST, D, O, const BUF: usize> Drop
+    for WindowWriter<'a, SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8>,
+    CS: OutputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    fn drop(&mut self) {
+        let display = &mut *self.display;
+        if self.len > 0 {
+            display.dc_level = None;
+            if display.cfg.dc.set_high().is_ok() {
+                display.dc_level = Some(true);
+                display.spi.write(&self.buffer[..self.len]).ok();
+            }
+        }
+
+        if display.cs_selected {
+            if let Some(cs) = display.cfg.cs.as_mut() {
+                cs.set_high().ok();
+            }
+            display.cs_selected = false;
+        }
+    }
+}
+
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Sets the `width` by `height` pixel window at `x`, `y`, starts a memory write and
+    /// returns a writer to push the RGB565 colors one by one, see
+    /// [`WindowWriter`](struct.WindowWriter.html).
+    ///
+    /// Returns `Error::InvalidParameter` for an empty window.
+    pub fn window(
+        &mut self,
+        x: u16,
+        y: u16,
+        width: u16,
+        height: u16,
+    ) -> Result<
+        WindowWriter<'_, SPI, CS, DC, RST, D, O, BUF>,
+        Error<PinError, SpiError, CS::Error, RST::Error>,
+    > {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::CHUNK_SIZE_CHECK;
+
+        if width == 0 || height == 0 {
+            return Err(Error::InvalidParameter);
+        }
+
+        self.check_pixel_format(PixelFormat::Rgb565)?;
+        self.address_window(
+            x,
+            y,
+            x.saturating_add(width - 1),
+            y.saturating_add(height - 1),
+        )?;
+        self.start_memory_write()?;
+
+        Ok(WindowWriter {
+            display: self,
+            buffer: [0; BUF],
+            len: 0,
+            written: 0,
+            expected: u32::from(width) * u32::from(height),
+        })
+    }
+
+    /// Fills the `width` by `height` pixel window at `x`, `y` with the RGB565 colors returned
+    /// by `f` for the screen coordinates of each pixel.
+    ///
+    /// The pixels are visited row by row, the order the controller fills the window in. The
+    /// memory access control settings apply to the window and to the fill order, so the
+    /// coordinates passed to `f` always match the screen orientation.
+    ///
+    /// Returns `Error::InvalidParameter` for an empty window.
+    pub fn write_pixels_from_fn<F>(
+        &mut self,
+        x: u16,
+        y: u16,
+        width: u16,
+        height: u16,
+        mut f: F,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>>
+    where
+        F: FnMut(u16, u16) -> u16,
+    {
+        let mut window = self.window(x, y, width, height)?;
+        for row in 0..height {
+            for column in 0..width {
+                window.push(f(x.saturating_add(column), y.saturating_add(row)))?;
+            }
+        }
+        window.finish()?;
+
+        Ok(self)
+    }
+}
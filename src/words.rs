@@ -0,0 +1,107 @@
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{Error, PixelFormat, TransferObserver, ST7789V};
+
+/// Pixel writes for SPI peripherals with 16 bit frames
+///
+/// Commands and their parameters are still written as bytes, only the pixel data is written
+/// as 16 bit words. The SPI implementation has to switch between 8 and 16 bit frames on every
+/// `write` call, which most HALs supporting both word sizes do. The 16 bit frames have to be
+/// transferred MSB first.
+impl<SPI, CS, DC, RST, PinError, SpiError, D, O, const BUF: usize>
+    ST7789V<SPI, CS, DC, RST, D, O, BUF>
+where
+    SPI: spi::Write<u8, Error = SpiError> + spi::Write<u16, Error = SpiError>,
+    CS: OutputPin,
+    DC: OutputPin<Error = PinError>,
+    RST: OutputPin,
+    O: TransferObserver,
+{
+    /// Sets a single pixel to the given RGB565 color using a 16 bit SPI transfer.
+    pub fn pixel_u16(
+        &mut self,
+        x: u16,
+        y: u16,
+        color: u16,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.pixels_u16_from_slice(x, y, x, y, &[color])
+    }
+
+    /// Sets the pixels of a window to the given RGB565 colors using 16 bit SPI transfers.
+    ///
+    /// The colors are collected in chunks of `BUF` bytes, `BUF / 2` words, which are written as
+    /// single transfers.
+    pub fn pixels_u16(
+        &mut self,
+        xs: u16,
+        ys: u16,
+        xe: u16,
+        ye: u16,
+        colors: &mut dyn Iterator<Item = u16>,
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::CHUNK_SIZE_CHECK;
+        self.start_words(xs, ys, xe, ye)?;
+
+        // an array length cannot be derived from BUF, only the first BUF / 2 words are used
+        let mut array = [0u16; BUF];
+        let buffer = &mut array[..BUF / 2];
+        let mut len = 0;
+        for color in colors {
+            buffer[len] = color;
+            len += 1;
+            if len == buffer.len() {
+                self.write_words(buffer)?;
+                len = 0;
+            }
+        }
+        self.write_words(&buffer[..len])?;
+
+        self.end_transaction()
+    }
+
+    /// Sets the pixels of a window to the given RGB565 colors with a single 16 bit SPI
+    /// transfer, e.g. straight from a frame buffer.
+    pub fn pixels_u16_from_slice(
+        &mut self,
+        xs: u16,
+        ys: u16,
+        xe: u16,
+        ye: u16,
+        colors: &[u16],
+    ) -> Result<&mut Self, Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.start_words(xs, ys, xe, ye)?;
+        self.write_words(colors)?;
+
+        self.end_transaction()
+    }
+
+    /// Sets the address window and starts the memory write.
+    fn start_words(
+        &mut self,
+        xs: u16,
+        ys: u16,
+        xe: u16,
+        ye: u16,
+    ) -> Result<(), Error<PinError, SpiError, CS::Error, RST::Error>> {
+        self.check_pixel_format(PixelFormat::Rgb565)?;
+        self.address_window(xs, ys, xe, ye)?;
+        self.start_memory_write()?;
+        self.set_dc(true)?;
+
+        Ok(())
+    }
+
+    fn write_words(
+        &mut self,
+        words: &[u16],
+    ) -> Result<(), Error<PinError, SpiError, CS::Error, RST::Error>> {
+        if words.is_empty() {
+            return Ok(());
+        }
+
+        self.observer.on_data(2 * words.len());
+        spi::Write::<u16>::write(&mut self.spi, words).map_err(Error::Spi)
+    }
+}
@@ -0,0 +1,53 @@
+use embedded_hal::blocking::delay::DelayMs;
+
+use st7789v::recording::{RecordingInterface, Transfer};
+
+/// Delay provider recording every requested delay
+#[derive(Default)]
+struct RecordingDelay(Vec<u16>);
+
+impl DelayMs<u16> for RecordingDelay {
+    fn delay_ms(&mut self, ms: u16) {
+        self.0.push(ms);
+    }
+}
+
+/// Runs `fade_brightness` and returns the written brightness values and the delays.
+fn fade(from: u8, to: u8, duration_ms: u16) -> (Vec<u8>, Vec<u16>) {
+    let interface = RecordingInterface::new();
+    let mut display = interface.display();
+    let mut delay = RecordingDelay::default();
+    display
+        .fade_brightness(from, to, duration_ms, &mut delay)
+        .unwrap();
+
+    let transfers = interface.transfers();
+    let values = transfers
+        .windows(2)
+        .filter_map(|pair| match pair {
+            [Transfer::Command(0x51), Transfer::Data(data)] => Some(data[0]),
+            _ => None,
+        })
+        .collect();
+
+    (values, delay.0)
+}
+
+#[test]
+fn fade_steps_every_10_ms() {
+    assert_eq!(fade(0, 200, 50), (vec![40, 80, 120, 160, 200], vec![10; 5]));
+    assert_eq!(fade(255, 0, 30), (vec![170, 85, 0], vec![10; 3]));
+}
+
+#[test]
+fn fade_takes_at_most_one_step_per_level() {
+    assert_eq!(fade(0, 4, 100), (vec![1, 2, 3, 4], vec![25; 4]));
+    assert_eq!(fade(101, 100, 1000), (vec![100], vec![1000]));
+}
+
+#[test]
+fn fade_without_distance_or_time_sets_the_target() {
+    assert_eq!(fade(50, 50, 100), (vec![50], vec![]));
+    assert_eq!(fade(200, 100, 5), (vec![100], vec![5]));
+    assert_eq!(fade(0, 255, 0), (vec![255], vec![0]));
+}
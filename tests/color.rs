@@ -0,0 +1,67 @@
+use st7789v::color::{self, BLACK, BLUE, GRAY, GREEN, RED, WHITE};
+
+#[test]
+fn packing_rounds_to_the_nearest_value() {
+    assert_eq!(color::rgb565(0, 0, 0), BLACK);
+    assert_eq!(color::rgb565(255, 255, 255), WHITE);
+    assert_eq!(color::rgb565(255, 0, 0), RED);
+    assert_eq!(color::rgb565(0, 255, 0), GREEN);
+    assert_eq!(color::rgb565(0, 0, 255), BLUE);
+    // 4 / 255 * 31 = 0.49 and 5 / 255 * 31 = 0.61
+    assert_eq!(color::rgb565(4, 0, 0), 0x0000);
+    assert_eq!(color::rgb565(5, 0, 0), 0x0800);
+    assert_eq!(color::rgb565(0, 2, 0), 0x0000);
+    assert_eq!(color::rgb565(0, 3, 0), 0x0020);
+    assert_eq!(color::from_rgb888(0x80_80_80), GRAY);
+    assert_eq!(
+        color::from_rgb888(0xFF_12_34_56),
+        color::rgb565(0x12, 0x34, 0x56)
+    );
+}
+
+#[test]
+fn unpacking_reaches_the_full_range() {
+    assert_eq!(color::to_rgb888(BLACK), (0, 0, 0));
+    assert_eq!(color::to_rgb888(WHITE), (255, 255, 255));
+    assert_eq!(color::to_rgb888(RED), (255, 0, 0));
+    assert_eq!(color::to_rgb888(0x0800), (8, 0, 0));
+    assert_eq!(color::to_rgb888(0x0020), (0, 4, 0));
+    assert_eq!(
+        (
+            color::red(0x1234),
+            color::green(0x1234),
+            color::blue(0x1234)
+        ),
+        (0x02, 0x11, 0x14)
+    );
+}
+
+#[test]
+fn every_color_survives_a_round_trip() {
+    for c in 0..=u16::MAX {
+        let (r, g, b) = color::to_rgb888(c);
+        assert_eq!(color::rgb565(r, g, b), c);
+    }
+}
+
+#[test]
+fn blend_interpolates_each_channel() {
+    assert_eq!(color::blend(BLACK, WHITE, 0), BLACK);
+    assert_eq!(color::blend(BLACK, WHITE, 255), WHITE);
+    assert_eq!(color::blend(RED, BLUE, 128), 0x7810);
+    assert_eq!(color::blend(BLACK, GREEN, 128), 0x0400);
+    assert_eq!(
+        color::blend(GRAY, RED, 51),
+        st7789v::blend_rgb565(GRAY, RED, 51)
+    );
+}
+
+#[test]
+fn gray_scales_each_channel() {
+    assert_eq!(color::gray(0), BLACK);
+    assert_eq!(color::gray(255), WHITE);
+    assert_eq!(color::gray(0x80), GRAY);
+    // 5 bits: 2 / 255 * 31 = 0.24, 6 bits: 2 / 255 * 63 = 0.49, 3 / 255 * 63 = 0.74
+    assert_eq!(color::gray(2), 0x0000);
+    assert_eq!(color::gray(3), 0x0020);
+}
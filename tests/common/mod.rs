@@ -0,0 +1,122 @@
+#![allow(dead_code)]
+
+use embedded_hal_mock::pin::{Mock as PinMock, State, Transaction as PinTransaction};
+use embedded_hal_mock::spi::{Mock as SpiMock, Transaction as SpiTransaction};
+
+use embedded_hal_mock::MockError;
+use st7789v::{NoPin, ST7789V};
+
+pub type Display = ST7789V<SpiMock, NoPin<MockError>, PinMock, PinMock>;
+
+/// Expected SPI and pin traffic of a test
+#[derive(Default)]
+pub struct Expectations {
+    pub spi: Vec<SpiTransaction>,
+    pub dc: Vec<PinTransaction>,
+    pub rst: Vec<PinTransaction>,
+    /// Level of the data/command pin, the driver only sets it when it changes
+    dc_level: Option<State>,
+}
+
+impl Expectations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A command without parameters
+    pub fn command(&mut self, cmd: u8) -> &mut Self {
+        self.set_dc(State::Low);
+        self.spi.push(SpiTransaction::write(vec![cmd]));
+        self
+    }
+
+    /// A command followed by its parameters
+    pub fn command_params(&mut self, cmd: u8, params: &[u8]) -> &mut Self {
+        self.command(cmd).data(params)
+    }
+
+    /// Data bytes, nothing is sent for empty data
+    pub fn data(&mut self, data: &[u8]) -> &mut Self {
+        if !data.is_empty() {
+            self.set_dc(State::High);
+            self.spi.push(SpiTransaction::write(data.to_vec()));
+        }
+        self
+    }
+
+    /// A read command, the zero bytes sent while reading return `response`
+    pub fn read(&mut self, cmd: u8, response: &[u8]) -> &mut Self {
+        self.command(cmd).set_dc(State::High);
+        self.spi.push(SpiTransaction::transfer(
+            vec![0; response.len()],
+            response.to_vec(),
+        ));
+        self
+    }
+
+    fn set_dc(&mut self, level: State) {
+        if self.dc_level != Some(level) {
+            self.dc.push(PinTransaction::set(level));
+            self.dc_level = Some(level);
+        }
+    }
+
+    pub fn hard_reset(&mut self) -> &mut Self {
+        self.rst.push(PinTransaction::set(State::High));
+        self.rst.push(PinTransaction::set(State::Low));
+        self.rst.push(PinTransaction::set(State::High));
+        self
+    }
+
+    /// CASET and RASET for the given window
+    pub fn window(&mut self, xs: u16, ys: u16, xe: u16, ye: u16) -> &mut Self {
+        self.command_params(0x2A, &[xs.to_be_bytes(), xe.to_be_bytes()].concat())
+            .command_params(0x2B, &[ys.to_be_bytes(), ye.to_be_bytes()].concat())
+    }
+
+    /// The traffic of `ST7789V::init` with the default options
+    pub fn init(&mut self) -> &mut Self {
+        self.init_inversion(true)
+    }
+
+    /// The traffic of `ST7789V::init_with` with the default options apart from `inversion`
+    pub fn init_inversion(&mut self, inversion: bool) -> &mut Self {
+        self.hard_reset()
+            .command(0x01)
+            .command(0x11)
+            .command_params(0x3A, &[0x55])
+            .command_params(0x36, &[0x00])
+            .window(0, 0, 239, 239)
+            .command(if inversion { 0x21 } else { 0x20 })
+            .command(0x13)
+            .command(0x29)
+    }
+
+    pub fn mocks(&self) -> Mocks {
+        Mocks {
+            spi: SpiMock::new(&self.spi),
+            dc: PinMock::new(&self.dc),
+            rst: PinMock::new(&self.rst),
+        }
+    }
+}
+
+/// Mocks sharing their expectations with the driver under test
+pub struct Mocks {
+    pub spi: SpiMock,
+    pub dc: PinMock,
+    pub rst: PinMock,
+}
+
+impl Mocks {
+    pub fn display(&self) -> Display {
+        ST7789V::new(self.spi.clone(), self.dc.clone(), self.rst.clone())
+    }
+
+    /// Asserts that all expected traffic happened.
+    pub fn done(&mut self) {
+        self.spi.done();
+        self.dc.done();
+        self.rst.done();
+    }
+}
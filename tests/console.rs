@@ -0,0 +1,123 @@
+use core::fmt::Write;
+
+use st7789v::recording::{FrameMemory, RecordingInterface, Transfer};
+use st7789v::{Error, InitOptions, MemAccCtrlConfig, PageColumnOrder};
+
+const WHITE: u16 = 0xFFFF;
+const BLUE: u16 = 0x001F;
+
+fn memory(interface: &RecordingInterface) -> FrameMemory {
+    let mut memory = FrameMemory::new();
+    memory.replay(&interface.transfers());
+    memory
+}
+
+/// Renders a cell as one string per row, '#' for foreground pixels.
+fn cell(memory: &FrameMemory, column: u16, row: u16) -> Vec<String> {
+    (0..8)
+        .map(|y| {
+            (0..6)
+                .map(|x| match memory.pixel(column * 6 + x, row * 8 + y) {
+                    WHITE => '#',
+                    _ => '.',
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn console_clears_with_background() {
+    let interface = RecordingInterface::new();
+    let mut display = interface.display();
+    display.init(&mut interface.delay()).unwrap();
+
+    display.console(WHITE, BLUE).unwrap();
+
+    let memory = memory(&interface);
+    assert_eq!(memory.pixel(0, 0), BLUE);
+    assert_eq!(memory.pixel(239, 239), BLUE);
+    assert_eq!(memory.pixel(0, 240), 0);
+}
+
+#[test]
+fn characters_are_drawn_into_cells() {
+    let interface = RecordingInterface::new();
+    let mut display = interface.display();
+    display.init(&mut interface.delay()).unwrap();
+
+    let mut console = display.console(WHITE, BLUE).unwrap();
+    write!(console, "A\n!").unwrap();
+
+    let memory = memory(&interface);
+    assert_eq!(
+        cell(&memory, 0, 0),
+        vec![".###..", "#...#.", "#...#.", "#...#.", "#####.", "#...#.", "#...#.", "......",]
+    );
+    assert_eq!(
+        cell(&memory, 0, 1),
+        vec!["..#...", "..#...", "..#...", "..#...", "..#...", "......", "..#...", "......",]
+    );
+}
+
+#[test]
+fn long_lines_wrap() {
+    let interface = RecordingInterface::new();
+    let mut display = interface.display();
+    display.init(&mut interface.delay()).unwrap();
+
+    let mut console = display.console(WHITE, BLUE).unwrap();
+    assert_eq!(console.size(), (40, 30));
+    for _ in 0..41 {
+        console.write_str("|").unwrap();
+    }
+
+    let memory = memory(&interface);
+    assert_eq!(cell(&memory, 39, 0)[0], "..#...");
+    assert_eq!(cell(&memory, 0, 1)[0], "..#...");
+    assert_eq!(cell(&memory, 1, 1)[0], "......");
+}
+
+#[test]
+fn last_line_scrolls_up() {
+    let interface = RecordingInterface::new();
+    let mut display = interface.display();
+    display.init(&mut interface.delay()).unwrap();
+
+    let mut console = display.console(WHITE, BLUE).unwrap();
+    for _ in 0..30 {
+        writeln!(console, "-").unwrap();
+    }
+    console.write_str("|").unwrap();
+
+    let transfers = interface.transfers();
+    let vscrsadd = transfers
+        .iter()
+        .rposition(|t| *t == Transfer::Command(0x37))
+        .unwrap();
+    assert_eq!(transfers[vscrsadd + 1], Transfer::Data(vec![0x00, 0x08]));
+
+    // The scrolled out top line is reused as the bottom line
+    let memory = memory(&interface);
+    assert_eq!(cell(&memory, 0, 0)[0], "..#...");
+    assert_eq!(cell(&memory, 0, 1)[3], "#####.");
+}
+
+#[test]
+fn console_needs_normal_page_column_order() {
+    let interface = RecordingInterface::new();
+    let mut display = interface.display();
+    display
+        .init_with(
+            InitOptions::new().memory_access_control(
+                MemAccCtrlConfig::new().page_column_order(PageColumnOrder::ReverseMode),
+            ),
+            &mut interface.delay(),
+        )
+        .unwrap();
+
+    assert_eq!(
+        display.console(WHITE, BLUE).err(),
+        Some(Error::InvalidParameter)
+    );
+}
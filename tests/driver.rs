@@ -0,0 +1,1017 @@
+mod common;
+
+use embedded_hal::blocking::delay::{DelayMs, DelayUs};
+use embedded_hal::digital::v2::OutputPin;
+use embedded_hal_mock::delay::MockNoop;
+use embedded_hal_mock::pin::{Mock as PinMock, State as PinState, Transaction as PinTransaction};
+use st7789v::{
+    blend_rgb565, CabcControl, ColorFormat, ColorOrder, ColumnAddressOrder, CountingObserver,
+    Error, FrameInversion, FrameRateDiv, GateHighVoltage, GateLowVoltage, GateScanMode,
+    InitOptions, InitStatus, IntervalScan, LatchOrder, LineAddressOrder, LowPowerOptions,
+    MemAccCtrlConfig, NdsOutput, NoPin, PageAddressOrder, PageColumnOrder, PwmFrequency,
+    ResetPolarity, RgbInterfaceConfig, RgbTransferMode, ST7789VConfig, VcomOffset, VcomSetting,
+    VdvSetting, VrhSetting, ST7789V,
+};
+
+use common::{Display, Expectations};
+
+#[test]
+fn init_sequence() {
+    let mut mocks = Expectations::new().init().mocks();
+    let mut display = mocks.display();
+
+    display.init(&mut MockNoop::new()).unwrap();
+
+    mocks.done();
+}
+
+#[test]
+fn poll_init_sends_each_step_once_its_wait_elapsed() {
+    let mut mocks = Expectations::new().init().mocks();
+    let mut display = mocks.display();
+    // the clock wraps around during the sequence
+    let t0 = u32::MAX - 200;
+    let at = |ms: u32| t0.wrapping_add(ms);
+
+    let mut init = display.begin_init(InitOptions::new(), at(0)).unwrap();
+    let mut poll = |ms| display.poll_init(&mut init, at(ms)).unwrap();
+    assert_eq!(poll(0), InitStatus::Pending { wait_ms: 1 });
+    assert_eq!(poll(1), InitStatus::Pending { wait_ms: 1 });
+    assert_eq!(poll(2), InitStatus::Pending { wait_ms: 120 });
+    assert_eq!(poll(100), InitStatus::Pending { wait_ms: 22 });
+    assert_eq!(poll(122), InitStatus::Pending { wait_ms: 150 });
+    assert_eq!(poll(272), InitStatus::Pending { wait_ms: 500 });
+    assert_eq!(poll(772), InitStatus::Pending { wait_ms: 10 });
+    assert_eq!(poll(782), InitStatus::Complete);
+    assert_eq!(poll(800), InitStatus::Complete);
+    assert!(display.is_display_on());
+
+    mocks.done();
+}
+
+#[test]
+fn init_with_inversion_on_sends_invon() {
+    let mut mocks = Expectations::new().init_inversion(true).mocks();
+    let mut display = mocks.display();
+
+    let options = InitOptions::new().inversion(true);
+    display.init_with(options, &mut MockNoop::new()).unwrap();
+
+    mocks.done();
+}
+
+#[test]
+fn init_with_inversion_off_sends_invoff() {
+    let mut mocks = Expectations::new().init_inversion(false).mocks();
+    let mut display = mocks.display();
+
+    let options = InitOptions::new().inversion(false);
+    display.init_with(options, &mut MockNoop::new()).unwrap();
+
+    mocks.done();
+}
+
+#[test]
+fn column_address_is_sent_big_endian() {
+    let mut mocks = Expectations::new()
+        .command_params(0x36, &[0x20])
+        .command_params(0x2A, &[0x01, 0x02, 0x01, 0x3F])
+        .mocks();
+    let mut display = mocks.display();
+
+    // With exchanged page and column order there are 320 columns
+    display
+        .set_page_column_order(PageColumnOrder::ReverseMode)
+        .unwrap();
+    display.column_address(0x0102, 0x013F).unwrap();
+
+    mocks.done();
+}
+
+#[test]
+fn row_address_is_sent_big_endian() {
+    let mut mocks = Expectations::new()
+        .command_params(0x2B, &[0x00, 0x10, 0x01, 0x3F])
+        .mocks();
+    let mut display = mocks.display();
+
+    display.row_address(0x0010, 0x013F).unwrap();
+
+    mocks.done();
+}
+
+#[test]
+fn address_start_after_end_is_rejected() {
+    let mut mocks = Expectations::new().mocks();
+    let mut display = mocks.display();
+
+    assert_eq!(
+        display.column_address(10, 9).err(),
+        Some(Error::InvalidColumnAddress {
+            start: 10,
+            end: 9,
+            max: 239
+        })
+    );
+    assert_eq!(
+        display.row_address(10, 9).err(),
+        Some(Error::InvalidRowAddress {
+            start: 10,
+            end: 9,
+            max: 319
+        })
+    );
+
+    mocks.done();
+}
+
+#[test]
+fn address_beyond_frame_memory_is_rejected() {
+    let mut mocks = Expectations::new().mocks();
+    let mut display = mocks.display();
+
+    assert_eq!(
+        display.column_address(0, 240).err(),
+        Some(Error::InvalidColumnAddress {
+            start: 0,
+            end: 240,
+            max: 239
+        })
+    );
+    assert_eq!(
+        display.row_address(300, 320).err(),
+        Some(Error::InvalidRowAddress {
+            start: 300,
+            end: 320,
+            max: 319
+        })
+    );
+
+    mocks.done();
+}
+
+#[test]
+fn address_window_is_checked_against_panel_size() {
+    let mut mocks = Expectations::new().mocks();
+    let mut display = mocks.display();
+
+    // The default panel is 240x240, although the frame memory has 320 rows
+    assert_eq!(
+        display.address_window(0, 200, 10, 240).err(),
+        Some(Error::InvalidRowAddress {
+            start: 200,
+            end: 240,
+            max: 239
+        })
+    );
+    assert_eq!(
+        display.pixel(240, 0, 0xFFFF).err(),
+        Some(Error::InvalidColumnAddress {
+            start: 240,
+            end: 240,
+            max: 239
+        })
+    );
+
+    mocks.done();
+}
+
+#[test]
+fn memory_access_control_non_default() {
+    let mut mocks = Expectations::new().command_params(0x36, &[0xC8]).mocks();
+    let mut display = mocks.display();
+
+    let config = MemAccCtrlConfig::default()
+        .page_order(PageAddressOrder::BottomToTop)
+        .column_order(ColumnAddressOrder::RightToLeft)
+        .color_order(ColorOrder::Bgr);
+    display.memory_access_control(config).unwrap();
+
+    mocks.done();
+}
+
+#[test]
+fn memory_access_control_bits() {
+    for bits in 0..64u8 {
+        let bit = |n: u8| bits & (1 << n) != 0;
+        let config = MemAccCtrlConfig::default()
+            .page_order(if bit(0) {
+                PageAddressOrder::BottomToTop
+            } else {
+                PageAddressOrder::TopToBottom
+            })
+            .column_order(if bit(1) {
+                ColumnAddressOrder::RightToLeft
+            } else {
+                ColumnAddressOrder::LeftToRight
+            })
+            .page_column_order(if bit(2) {
+                PageColumnOrder::ReverseMode
+            } else {
+                PageColumnOrder::NormalMode
+            })
+            .line_order(if bit(3) {
+                LineAddressOrder::BottomToTop
+            } else {
+                LineAddressOrder::TopToBottom
+            })
+            .color_order(if bit(4) {
+                ColorOrder::Bgr
+            } else {
+                ColorOrder::Rgb
+            })
+            .latch_order(if bit(5) {
+                LatchOrder::RightToLeft
+            } else {
+                LatchOrder::LeftToRight
+            });
+
+        // MY, MX, MV, ML, RGB and MH are bits 7 down to 2
+        assert_eq!(
+            config.value(),
+            bits.reverse_bits(),
+            "config bits {:06b}",
+            bits
+        );
+        assert_eq!(MemAccCtrlConfig::from_value(config.value()), config);
+    }
+}
+
+#[test]
+fn madctl_fields_are_updated_in_place() {
+    let mut mocks = Expectations::new()
+        .command_params(0x36, &[0x40])
+        .command_params(0x36, &[0x48])
+        .command_params(0x36, &[0x68])
+        .mocks();
+    let mut display = mocks.display();
+
+    assert_eq!(display.madctl(), MemAccCtrlConfig::default());
+    display
+        .set_column_order(ColumnAddressOrder::RightToLeft)
+        .unwrap()
+        .set_color_order(ColorOrder::Bgr)
+        .unwrap()
+        .set_page_column_order(PageColumnOrder::ReverseMode)
+        .unwrap();
+    assert_eq!(display.madctl().get_color_order(), ColorOrder::Bgr);
+    assert_eq!(display.madctl().value(), 0x68);
+
+    mocks.done();
+}
+
+#[test]
+fn pixel_sets_window_and_writes_color() {
+    let mut mocks = Expectations::new()
+        .window(3, 4, 3, 4)
+        .command_params(0x2C, &[0xF8, 0x1F])
+        .mocks();
+    let mut display = mocks.display();
+
+    display.pixel(3, 4, 0xF81F).unwrap();
+
+    mocks.done();
+}
+
+#[test]
+fn pixels_streams_colors_after_memory_write() {
+    let mut mocks = Expectations::new()
+        .window(0, 0, 1, 1)
+        .command_params(0x2C, &[0x00, 0x01, 0x02, 0x03, 0x04, 0x05])
+        .mocks();
+    let mut display = mocks.display();
+
+    let mut colors = [0x0001, 0x0203, 0x0405].iter().copied();
+    display.pixels(0, 0, 1, 1, &mut colors).unwrap();
+
+    mocks.done();
+}
+
+#[test]
+fn flush_area_swaps_native_colors_in_place() {
+    let mut mocks = Expectations::new()
+        .window(10, 20, 11, 21)
+        .command_params(0x2C, &[0xF8, 0x00, 0x07, 0xE0, 0x00, 0x1F, 0xFF, 0xFF])
+        .mocks();
+    let mut display = mocks.display();
+
+    let mut buf = [0x00, 0xF8, 0xE0, 0x07, 0x1F, 0x00, 0xFF, 0xFF];
+    display.flush_area(10, 20, 11, 21, &mut buf, true).unwrap();
+    assert_eq!(buf, [0xF8, 0x00, 0x07, 0xE0, 0x00, 0x1F, 0xFF, 0xFF]);
+    assert_eq!(
+        display
+            .flush_area(10, 20, 11, 21, &mut buf[..6], false)
+            .err(),
+        Some(Error::PixelCountMismatch {
+            expected: 4,
+            written: 3
+        })
+    );
+
+    mocks.done();
+}
+
+#[test]
+fn tearing_effect_on_selects_vblank_mode() {
+    let mut mocks = Expectations::new().command_params(0x35, &[0x00]).mocks();
+    let mut display = mocks.display();
+
+    display.tearing_effect_on().unwrap();
+
+    mocks.done();
+}
+
+#[test]
+fn wait_for_vsync_waits_for_rising_edge() {
+    let mut mocks = Expectations::new().mocks();
+    let mut display = mocks.display();
+    let mut te = PinMock::new(&[
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::High),
+    ]);
+
+    display.wait_for_vsync(&mut te, 10).unwrap();
+
+    te.done();
+    mocks.done();
+}
+
+#[test]
+fn wait_for_vsync_times_out() {
+    let mut mocks = Expectations::new().mocks();
+    let mut display = mocks.display();
+    let mut te = PinMock::new(&vec![PinTransaction::get(PinState::High); 3]);
+
+    assert_eq!(
+        display.wait_for_vsync(&mut te, 3).err(),
+        Some(Error::VsyncTimeout)
+    );
+
+    te.done();
+    mocks.done();
+}
+
+#[test]
+fn pixel_stream_sets_data_command_pin_only_on_change() {
+    let mut expectations = Expectations::new();
+    expectations.window(0, 0, 9, 9).command(0x2C);
+    let bytes: Vec<u8> = (0..100u16).flat_map(|c| c.to_be_bytes().to_vec()).collect();
+    for chunk in bytes.chunks(128) {
+        expectations.data(chunk);
+    }
+    // CASET, RASET and RAMWR each switch to command and back to data
+    assert_eq!(expectations.dc.len(), 6);
+    let mut mocks = expectations.mocks();
+    let mut display = mocks.display();
+
+    display.pixels(0, 0, 9, 9, &mut (0..100)).unwrap();
+
+    mocks.done();
+}
+
+#[test]
+fn pixel_data_is_written_in_chunks() {
+    let bytes: Vec<u8> = (0..10u16).flat_map(|c| c.to_be_bytes().to_vec()).collect();
+    let mut mocks = Expectations::new()
+        .window(0, 0, 9, 0)
+        .command_params(0x2C, &bytes[..8])
+        .data(&bytes[8..16])
+        .data(&bytes[16..])
+        .mocks();
+    let mut display = mocks.display().with_chunk_size::<9>();
+
+    display.pixels(0, 0, 9, 0, &mut (0..10)).unwrap();
+
+    mocks.done();
+}
+
+#[test]
+fn no_pin_stands_in_for_chip_select() {
+    struct Screen {
+        display: Display,
+    }
+
+    let mut mocks = Expectations::new().command_params(0x35, &[0x00]).mocks();
+    let cfg = ST7789VConfig::new(mocks.dc.clone(), mocks.rst.clone());
+    let mut screen = Screen {
+        display: ST7789V::with_config(mocks.spi.clone(), cfg).unwrap(),
+    };
+
+    screen.display.tearing_effect_on().unwrap();
+    let mut cs = NoPin::<()>::default();
+    cs.set_high().unwrap();
+
+    mocks.done();
+}
+
+#[test]
+fn resume_replays_configuration_without_reset() {
+    let mut mocks = Expectations::new()
+        .init()
+        .command_params(0x36, &[0x08])
+        .command(0x28)
+        .command(0x10)
+        .command(0x11)
+        .command_params(0x3A, &[0x55])
+        .command_params(0x36, &[0x08])
+        .window(0, 0, 239, 239)
+        .command(0x21)
+        .command(0x13)
+        .command(0x29)
+        .mocks();
+    let mut display = mocks.display();
+    let mut delay = MockNoop::new();
+
+    display.init(&mut delay).unwrap();
+    display.set_color_order(ColorOrder::Bgr).unwrap();
+    display.sleep(&mut delay).unwrap();
+    display.resume(&mut delay).unwrap();
+    assert!(!display.is_asleep());
+
+    mocks.done();
+}
+
+#[test]
+fn state_getters_follow_the_written_settings() {
+    let mut mocks = Expectations::new()
+        .init()
+        .command(0x28)
+        .command(0x39)
+        .command(0x20)
+        .mocks();
+    let mut display = mocks.display();
+
+    assert_eq!(display.color_format(), None);
+    display.init(&mut MockNoop::new()).unwrap();
+    assert!(!display.is_asleep());
+    assert!(display.is_display_on());
+    assert!(display.is_inverted());
+    assert_eq!(display.color_format(), Some(ColorFormat::RGB65K_CI16Bit));
+
+    display
+        .display_off()
+        .unwrap()
+        .idle_on()
+        .unwrap()
+        .inversion_off()
+        .unwrap();
+    assert!(!display.is_display_on());
+    assert!(display.is_idle());
+    assert!(!display.is_inverted());
+
+    mocks.done();
+}
+
+#[test]
+fn memory_writes_while_asleep_are_rejected_unless_forced() {
+    let mut mocks = Expectations::new()
+        .command(0x10)
+        .window(1, 2, 1, 2)
+        .command_params(0x2C, &[0xF8, 0x00])
+        .mocks();
+    let mut display = mocks.display();
+
+    display.sleep_in(&mut MockNoop::new()).unwrap();
+    assert!(display.is_asleep());
+    assert_eq!(
+        display.pixel(1, 2, 0xF800).err(),
+        Some(Error::DisplayAsleep)
+    );
+    display.force_mem_write(&[0xF8, 0x00]).unwrap();
+
+    mocks.done();
+}
+
+#[test]
+fn partial_mode_needs_a_partial_area_unless_forced() {
+    let mut mocks = Expectations::new()
+        .command(0x12)
+        .command_params(0x30, &[0x00, 0x10, 0x00, 0x20])
+        .command(0x12)
+        .mocks();
+    let mut display = mocks.display();
+
+    assert_eq!(
+        display.partial_display_mode().err(),
+        Some(Error::NoPartialArea)
+    );
+    display.force_partial_display_mode().unwrap();
+    display
+        .partial_area(0x10, 0x20)
+        .unwrap()
+        .partial_display_mode()
+        .unwrap();
+
+    mocks.done();
+}
+
+#[test]
+fn counting_observer_totals_commands_data_and_dc_writes() {
+    let mut mocks = Expectations::new()
+        .window(1, 2, 1, 2)
+        .command_params(0x2C, &[0xF8, 0x00])
+        .command(0x29)
+        .mocks();
+    let mut display = mocks.display().with_observer(CountingObserver::new());
+
+    display.pixel(1, 2, 0xF800).unwrap();
+    assert_eq!(
+        display.observer_mut().take(),
+        CountingObserver {
+            commands: 3,
+            data_bytes: 10,
+            data_transfers: 3,
+            dc_toggles: 6,
+        }
+    );
+
+    display.display_on().unwrap();
+    assert_eq!(display.observer().commands, 1);
+    assert_eq!(display.observer().data_bytes, 0);
+
+    mocks.done();
+}
+
+/// Delay provider recording the requested delays in microseconds
+#[derive(Default)]
+struct DelayLog(Vec<u32>);
+
+impl DelayUs<u32> for DelayLog {
+    fn delay_us(&mut self, us: u32) {
+        self.0.push(us);
+    }
+}
+
+impl DelayMs<u16> for DelayLog {
+    fn delay_ms(&mut self, ms: u16) {
+        self.0.push(u32::from(ms) * 1000);
+    }
+}
+
+#[test]
+fn hard_reset_us_pulses_for_microseconds() {
+    let mut mocks = Expectations::new().hard_reset().mocks();
+    let mut display = mocks.display();
+
+    let mut delay = DelayLog::default();
+    display.hard_reset_us(&mut delay).unwrap();
+    assert_eq!(delay.0, [10, 10, 120_000]);
+
+    mocks.done();
+}
+
+#[test]
+fn owned_delay_is_used_and_returned() {
+    let mut mocks = Expectations::new().hard_reset().command(0x11).mocks();
+    let mut display = ST7789V::with_delay(
+        mocks.spi.clone(),
+        mocks.dc.clone(),
+        mocks.rst.clone(),
+        DelayLog::default(),
+    );
+
+    display.hard_reset_with_own_delay().unwrap();
+    display.sleep_out_with_own_delay().unwrap();
+    let (_, _, _, _, delay) = display.into_parts();
+    assert_eq!(delay.0, [1000, 1000, 120_000, 500_000]);
+
+    mocks.done();
+}
+
+#[test]
+fn low_power_mode_is_entered_and_restored() {
+    let mut mocks = Expectations::new()
+        .init()
+        .command_params(0x51, &[200])
+        .command_params(0x51, &[20])
+        .command_params(0x30, &[0, 10, 0, 50])
+        .command(0x12)
+        .command(0x39)
+        .command(0x39)
+        .command(0x38)
+        .command_params(0x51, &[200])
+        .command(0x13)
+        .mocks();
+    let mut display = mocks.display();
+    display.init(&mut MockNoop::new()).unwrap();
+    display.brightness(200).unwrap();
+
+    display
+        .low_power_mode(LowPowerOptions::new().brightness(20).partial_area(10, 50))
+        .unwrap();
+    // entering again keeps the settings to restore
+    display.low_power_mode(LowPowerOptions::new()).unwrap();
+    display.exit_low_power_mode().unwrap();
+    display.exit_low_power_mode().unwrap();
+
+    mocks.done();
+}
+
+#[test]
+fn low_power_mode_restores_the_reset_brightness() {
+    let mut mocks = Expectations::new()
+        .init()
+        .command_params(0x51, &[20])
+        .command(0x39)
+        .command(0x38)
+        .command_params(0x51, &[0])
+        .command(0x39)
+        .command(0x38)
+        .mocks();
+    let mut display = mocks.display();
+    display.init(&mut MockNoop::new()).unwrap();
+
+    display
+        .low_power_mode(LowPowerOptions::new().brightness(20))
+        .unwrap();
+    display.exit_low_power_mode().unwrap();
+    // the brightness is not touched if low power mode did not change it
+    display.low_power_mode(LowPowerOptions::new()).unwrap();
+    display.exit_low_power_mode().unwrap();
+
+    mocks.done();
+}
+
+#[test]
+fn power_down_sleeps_and_holds_reset() {
+    let mut expectations = Expectations::new();
+    expectations.command(0x28).command(0x10);
+    expectations.rst.push(PinTransaction::set(PinState::Low));
+    let mut mocks = expectations.mocks();
+    let display = mocks.display();
+
+    display.power_down(&mut MockNoop::new(), true).unwrap();
+
+    mocks.done();
+}
+
+#[test]
+fn active_high_reset_inverts_the_reset_levels() {
+    let mut expectations = Expectations::new();
+    expectations.command(0x28).command(0x10);
+    for state in [PinState::Low, PinState::High, PinState::Low, PinState::High] {
+        expectations.rst.push(PinTransaction::set(state));
+    }
+    let mut mocks = expectations.mocks();
+    let cfg = ST7789VConfig::new(mocks.dc.clone(), mocks.rst.clone())
+        .reset_polarity(ResetPolarity::ActiveHigh);
+    let mut display: Display = ST7789V::with_config(mocks.spi.clone(), cfg).unwrap();
+
+    display.hard_reset(&mut MockNoop::new()).unwrap();
+    display.power_down(&mut MockNoop::new(), true).unwrap();
+
+    mocks.done();
+}
+
+#[test]
+fn vcom_voltages_map_to_register_codes() {
+    let mut mocks = Expectations::new()
+        .command_params(0xBB, &[0x32])
+        .command_params(0xC5, &[0x1C])
+        .mocks();
+    let mut display = mocks.display();
+
+    let vcom = VcomSetting::from_millivolts(1350).unwrap();
+    let offset = VcomOffset::from_millivolts(-100).unwrap();
+    display
+        .set_vcom(vcom)
+        .unwrap()
+        .set_vcom_offset(offset)
+        .unwrap();
+    assert_eq!(VcomSetting::default().millivolts(), 900);
+    assert_eq!(VcomSetting::from_millivolts(99), None);
+    assert_eq!(VcomSetting::from_millivolts(1700), None);
+    assert_eq!(VcomSetting::from_code(0x40), None);
+    assert_eq!(VcomOffset::from_millivolts(800), None);
+    assert_eq!(VcomOffset::from_code(0x3F).unwrap().millivolts(), 775);
+
+    mocks.done();
+}
+
+#[test]
+fn gate_and_source_voltages_are_written() {
+    let mut mocks = Expectations::new()
+        .command_params(0xB7, &[0x35])
+        .command_params(0xC2, &[0x01, 0xFF])
+        .command_params(0xC3, &[0x12])
+        .command_params(0xC4, &[0x20])
+        .mocks();
+    let mut display = mocks.display();
+
+    display
+        .set_gate_voltages(GateHighVoltage::V13_26, GateLowVoltage::V10_43)
+        .unwrap()
+        .vdv_vrh_from_commands(true)
+        .unwrap()
+        .set_vrh(VrhSetting::from_millivolts(4450).unwrap())
+        .unwrap()
+        .set_vdv(VdvSetting::default())
+        .unwrap();
+    assert_eq!(VrhSetting::from_code(0x28), None);
+    assert_eq!(VrhSetting::from_millivolts(5550), None);
+    assert_eq!(VrhSetting::default().millivolts(), 4100);
+    assert_eq!(VdvSetting::from_millivolts(-825), None);
+
+    mocks.done();
+}
+
+#[test]
+fn digital_gamma_tables_are_loaded_and_enabled() {
+    let red: [u8; 64] = std::array::from_fn(|i| i as u8 * 4);
+    let blue: [u8; 64] = std::array::from_fn(|i| 255 - i as u8 * 4);
+    let mut mocks = Expectations::new()
+        .command_params(0xE2, &red)
+        .command_params(0xE3, &blue)
+        .command_params(0xBA, &[0x04])
+        .command_params(0xBA, &[0x00])
+        .mocks();
+    let mut display = mocks.display();
+
+    display
+        .set_digital_gamma_luts(&red, &blue)
+        .unwrap()
+        .enable_digital_gamma(true)
+        .unwrap()
+        .enable_digital_gamma(false)
+        .unwrap();
+
+    mocks.done();
+}
+
+#[test]
+fn gate_lines_are_set_in_steps_of_eight() {
+    let mut mocks = Expectations::new()
+        .command_params(0xE4, &[0x1D, 0x00, 0x10])
+        .command_params(0xE4, &[0x15, 0x05, 0x11])
+        .mocks();
+    let mut display = mocks.display();
+
+    display
+        .set_gate_lines(240, 0, GateScanMode::Normal)
+        .unwrap()
+        .set_gate_lines(176, 40, GateScanMode::Reverse)
+        .unwrap();
+    for &(lines, start) in &[(0, 0), (100, 0), (240, 4), (320, 8)] {
+        assert_eq!(
+            display
+                .set_gate_lines(lines, start, GateScanMode::Normal)
+                .err(),
+            Some(Error::InvalidParameter)
+        );
+    }
+
+    mocks.done();
+}
+
+#[test]
+fn rgb_interface_is_configured_and_selected() {
+    let mut mocks = Expectations::new()
+        .command_params(0xB1, &[0x6A, 0x08, 0x1F])
+        .command_params(0xB0, &[0x11, 0xF0])
+        .command_params(0xB0, &[0x00, 0xF0])
+        .mocks();
+    let mut display = mocks.display();
+
+    let config = RgbInterfaceConfig::new()
+        .mode(RgbTransferMode::Sync)
+        .vsync_active_high(true)
+        .pclk_falling_edge(true)
+        .vertical_back_porch(8)
+        .horizontal_back_porch(31);
+    display
+        .configure_rgb_interface(config)
+        .unwrap()
+        .mcu_interface()
+        .unwrap();
+    assert_eq!(
+        display
+            .configure_rgb_interface(config.horizontal_back_porch(32))
+            .err(),
+        Some(Error::InvalidParameter)
+    );
+
+    mocks.done();
+}
+
+#[test]
+fn backlight_pwm_output_is_configured() {
+    let mut mocks = Expectations::new()
+        .command_params(0xCC, &[0x3D])
+        .command_params(0xC7, &[0x09])
+        .mocks();
+    let mut display = mocks.display();
+
+    let options = CabcControl::new()
+        .led_on_inverted(true)
+        .pwm_active_low(true);
+    display
+        .set_backlight_pwm_frequency(PwmFrequency::from_codes(7, 5).unwrap())
+        .unwrap()
+        .set_cabc_control(options)
+        .unwrap();
+    assert_eq!(PwmFrequency::from_codes(8, 0), None);
+    assert_eq!(PwmFrequency::default().value(), 0x08);
+
+    mocks.done();
+}
+
+#[test]
+fn command2_registers_are_rejected_while_locked() {
+    let mut mocks = Expectations::new()
+        .command_params(0xDF, &[0x5A, 0x69, 0x02, 0x00])
+        .command_params(0x35, &[0x00])
+        .command_params(0xDF, &[0x5A, 0x69, 0x02, 0x01])
+        .command_params(0xBB, &[0x20])
+        .mocks();
+    let mut display = mocks.display();
+
+    assert!(display.is_command2_enabled());
+    display.enable_command2(false).unwrap();
+    assert_eq!(
+        display.set_vcom(VcomSetting::default()).err(),
+        Some(Error::Command2Locked)
+    );
+    display.tearing_effect_on().unwrap();
+    display
+        .enable_command2(true)
+        .unwrap()
+        .set_vcom(VcomSetting::default())
+        .unwrap();
+
+    mocks.done();
+}
+
+#[test]
+fn idle_and_partial_frame_rates_are_set() {
+    let mut mocks = Expectations::new()
+        .command_params(0xB3, &[0x10, 0xFF, 0xEF])
+        .mocks();
+    let mut display = mocks.display();
+
+    display
+        .set_idle_partial_frame_rates(
+            FrameRateDiv::Hz39,
+            FrameRateDiv::Hz60,
+            FrameInversion::Column,
+        )
+        .unwrap();
+
+    mocks.done();
+}
+
+#[test]
+fn partial_control_selects_interval_scan() {
+    let mut mocks = Expectations::new()
+        .command_params(0xB5, &[0x84])
+        .command(0x12)
+        .command_params(0xB5, &[0x00])
+        .mocks();
+    let mut display = mocks.display();
+
+    display
+        .set_partial_control(NdsOutput::V0, IntervalScan::Frames9)
+        .unwrap()
+        .force_partial_display_mode()
+        .unwrap()
+        .set_partial_control(NdsOutput::V63, IntervalScan::Off)
+        .unwrap();
+
+    mocks.done();
+}
+
+#[cfg(feature = "nvm")]
+#[test]
+fn nvm_write_sends_program_sequence() {
+    let mut mocks = Expectations::new()
+        .command_params(0xFA, &[0x5A, 0x69, 0xEE, 0x04])
+        .command_params(0xFC, &[0xBB, 0x32])
+        .command_params(0xFE, &[0x29, 0xA5])
+        .mocks();
+    let mut display = mocks.display();
+
+    let otp = st7789v::OneTimeProgrammable::i_understand_this_is_one_time_programmable();
+    display
+        .nvm_write(0xBB, 0x32, otp, &mut MockNoop::new())
+        .unwrap();
+
+    mocks.done();
+}
+
+#[test]
+fn copy_area_reads_rows_and_writes_them_back_as_rgb565() {
+    // the areas overlap and the destination is lower, so the last row is copied first
+    let mut mocks = Expectations::new()
+        .window(0, 1, 1, 1)
+        .read(0x2E, &[0xFF, 0xFC, 0x00, 0x00, 0x00, 0xFC, 0x00])
+        .window(1, 2, 2, 2)
+        .command_params(0x2C, &[0xF8, 0x00, 0x07, 0xE0])
+        .window(0, 0, 1, 0)
+        .read(0x2E, &[0xFF, 0x00, 0x00, 0xFC, 0xFC, 0xFC, 0xFC])
+        .window(1, 1, 2, 1)
+        .command_params(0x2C, &[0x00, 0x1F, 0xFF, 0xFF])
+        .mocks();
+    let mut display = mocks.display();
+
+    let mut scratch = [0; 7];
+    display
+        .copy_area((0, 0, 2, 2), (1, 1), &mut scratch)
+        .unwrap();
+    assert_eq!(
+        display
+            .copy_area((0, 0, 2, 2), (1, 1), &mut scratch[..6])
+            .err(),
+        Some(Error::BufferTooSmall)
+    );
+
+    mocks.done();
+}
+
+#[test]
+fn blend_rgb565_rounds_each_channel() {
+    assert_eq!(blend_rgb565(0x0000, 0xFFFF, 128), 0x8410);
+    assert_eq!(blend_rgb565(0x001F, 0xF800, 64), 0x4017);
+    assert_eq!(blend_rgb565(0x1234, 0xFFFF, 0), 0x1234);
+    assert_eq!(blend_rgb565(0x1234, 0xABCD, 255), 0xABCD);
+}
+
+#[test]
+fn blend_rect_reads_blends_and_writes_back() {
+    let mut mocks = Expectations::new()
+        .window(5, 6, 6, 6)
+        .read(0x2E, &[0xFF, 0xFC, 0xFC, 0xFC, 0x00, 0x00, 0x00])
+        .window(5, 6, 6, 6)
+        .command_params(0x2C, &[0x7B, 0xEF, 0x00, 0x00])
+        .mocks();
+    let mut display = mocks.display();
+
+    let mut scratch = [0; 16];
+    display
+        .blend_rect(5, 6, 2, 1, 0x0000, 128, &mut scratch)
+        .unwrap();
+    assert_eq!(
+        display.blend_rect(0, 0, 8, 1, 0, 128, &mut scratch).err(),
+        Some(Error::BufferTooSmall)
+    );
+
+    mocks.done();
+}
+
+#[test]
+fn init_and_verify_checks_power_mode_and_status() {
+    let mut mocks = Expectations::new()
+        .init()
+        .read(0x0A, &[0x9C])
+        .read(0x09, &[0x40, 0x01, 0x82, 0x00, 0x00])
+        .read(0x0A, &[0x08])
+        .read(0x09, &[0x00, 0x01, 0x00, 0x00, 0x00])
+        .mocks();
+    let mut display = mocks.display();
+
+    display.init_and_verify(&mut MockNoop::new()).unwrap();
+    assert_eq!(
+        display.verify(),
+        Err(Error::VerificationFailed {
+            power_mode: 0x08,
+            status: 0x0002_0000
+        })
+    );
+
+    mocks.done();
+}
+
+#[test]
+fn verify_config_compares_madctl_and_colmod_with_the_written_values() {
+    let mut mocks = Expectations::new()
+        .init()
+        .read(0x0A, &[0x9C])
+        .read(0x09, &[0x40, 0x01, 0x82, 0x00, 0x00])
+        .read(0x0B, &[0x00])
+        .read(0x0C, &[0x55])
+        .read(0x0B, &[0x48])
+        .read(0x0C, &[0x66])
+        .mocks();
+    let mut display = mocks.display();
+
+    display
+        .init_with_and_verify(InitOptions::new(), &mut MockNoop::new())
+        .unwrap();
+    assert_eq!(
+        display.verify_config(),
+        Err(Error::ReadbackMismatch {
+            register: 0x0B,
+            expected: 0x00,
+            got: 0x48
+        })
+    );
+    assert_eq!(
+        display.verify_colmod(),
+        Err(Error::ReadbackMismatch {
+            register: 0x0C,
+            expected: 0x55,
+            got: 0x66
+        })
+    );
+
+    mocks.done();
+}
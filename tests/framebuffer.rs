@@ -0,0 +1,175 @@
+use embedded_graphics::pixelcolor::Rgb565;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::Rectangle;
+use embedded_graphics::style::PrimitiveStyle;
+use st7789v::recording::{FrameMemory, RecordingDisplay, RecordingInterface, Transfer};
+use st7789v::{Error, FrameBuffer};
+
+const RED: u16 = 0xF800;
+const GREEN: u16 = 0x07E0;
+
+fn initialized() -> (RecordingInterface, RecordingDisplay) {
+    let interface = RecordingInterface::new();
+    let mut display = interface.display();
+    display.init(&mut interface.delay()).unwrap();
+
+    (interface, display)
+}
+
+/// Returns the column and row window of the last captured memory write.
+fn windows(transfers: &[Transfer]) -> (Vec<u8>, Vec<u8>) {
+    let params = |cmd| match transfers.iter().rposition(|t| *t == Transfer::Command(cmd)) {
+        Some(i) => match &transfers[i + 1] {
+            Transfer::Data(data) => data.clone(),
+            _ => Vec::new(),
+        },
+        None => Vec::new(),
+    };
+
+    (params(0x2A), params(0x2B))
+}
+
+#[test]
+fn new_buffer_is_dirty_and_flush_resets_it() {
+    let (interface, mut display) = initialized();
+    let mut buffer = [0u16; 16 * 8];
+    let mut frame_buffer = FrameBuffer::new(&mut buffer, 16, 8);
+    interface.take_transfers();
+
+    assert_eq!(frame_buffer.dirty(), Some((0, 0, 15, 7)));
+    frame_buffer.flush(&mut display).unwrap();
+    assert_eq!(frame_buffer.dirty(), None);
+    assert_eq!(
+        windows(&interface.take_transfers()),
+        (vec![0, 0, 0, 15], vec![0, 0, 0, 7])
+    );
+
+    frame_buffer.flush(&mut display).unwrap();
+    assert!(interface.transfers().is_empty());
+}
+
+#[test]
+fn flush_sends_bounding_box_of_changes() {
+    let (interface, mut display) = initialized();
+    let mut buffer = [0u16; 16 * 8];
+    let mut frame_buffer = FrameBuffer::new(&mut buffer, 16, 8);
+    frame_buffer.flush(&mut display).unwrap();
+
+    frame_buffer.set_pixel(3, 2, RED).set_pixel(5, 4, GREEN);
+    assert_eq!(frame_buffer.dirty(), Some((3, 2, 5, 4)));
+    frame_buffer.flush(&mut display).unwrap();
+
+    let transfers = interface.transfers();
+    assert_eq!(windows(&transfers), (vec![0, 3, 0, 5], vec![0, 2, 0, 4]));
+    assert_eq!(
+        transfers.last(),
+        Some(&Transfer::Data(
+            [RED, 0, 0, 0, 0, 0, 0, 0, GREEN]
+                .iter()
+                .flat_map(|c| c.to_be_bytes().to_vec())
+                .collect()
+        ))
+    );
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&transfers);
+    assert_eq!(memory.pixel(3, 2), RED);
+    assert_eq!(memory.pixel(5, 4), GREEN);
+}
+
+#[test]
+fn direct_buffer_changes_need_mark_dirty() {
+    let (interface, mut display) = initialized();
+    let mut buffer = [0u16; 16 * 8];
+    let mut frame_buffer = FrameBuffer::new(&mut buffer, 16, 8);
+    frame_buffer.flush(&mut display).unwrap();
+    interface.take_transfers();
+
+    frame_buffer.buffer_mut()[17] = RED;
+    frame_buffer.flush(&mut display).unwrap();
+    assert!(interface.transfers().is_empty());
+
+    frame_buffer.mark_dirty(1, 1, 40, 1);
+    assert_eq!(frame_buffer.dirty(), Some((1, 1, 15, 1)));
+    frame_buffer.flush(&mut display).unwrap();
+    assert_eq!(
+        windows(&interface.take_transfers()),
+        (vec![0, 1, 0, 15], vec![0, 1, 0, 1])
+    );
+
+    frame_buffer.flush_full(&mut display).unwrap();
+    assert_eq!(
+        windows(&interface.take_transfers()),
+        (vec![0, 0, 0, 15], vec![0, 0, 0, 7])
+    );
+}
+
+#[test]
+fn drawing_marks_dirty() {
+    let (_interface, mut display) = initialized();
+    let mut buffer = [0u16; 16 * 8];
+    let mut frame_buffer = FrameBuffer::new(&mut buffer, 16, 8);
+    frame_buffer.flush(&mut display).unwrap();
+
+    Rectangle::new(Point::new(4, 1), Point::new(6, 3))
+        .into_styled(PrimitiveStyle::with_fill(Rgb565::RED))
+        .draw(&mut frame_buffer)
+        .unwrap();
+
+    assert_eq!(frame_buffer.dirty(), Some((4, 1, 6, 3)));
+    assert_eq!(frame_buffer.pixel(5, 2), Some(RED));
+}
+
+#[test]
+fn update_region_writes_the_rendered_region_at_once() {
+    let (interface, mut display) = initialized();
+    let init_len = interface.transfers().len();
+
+    let mut buf = [0u16; 4 * 3 + 2];
+    display
+        .update_region(100, 50, 4, 3, &mut buf, |canvas| {
+            canvas.clear(GREEN);
+            Rectangle::new(Point::new(1, 1), Point::new(2, 1))
+                .into_styled(PrimitiveStyle::with_fill(Rgb565::RED))
+                .draw(canvas)
+                .unwrap();
+        })
+        .unwrap();
+
+    let transfers = interface.transfers();
+    let memory_writes = transfers[init_len..]
+        .iter()
+        .filter(|&t| *t == Transfer::Command(0x2C))
+        .count();
+    assert_eq!(memory_writes, 1);
+    assert_eq!(
+        windows(&transfers),
+        (vec![0, 100, 0, 103], vec![0, 50, 0, 52])
+    );
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&transfers);
+    let (g, r) = (GREEN, RED);
+    assert_eq!(
+        memory.area(100, 50, 4, 3),
+        vec![g, g, g, g, g, r, r, g, g, g, g, g]
+    );
+    // the rest of the buffer is not touched
+    assert_eq!(buf[12..], [0, 0]);
+}
+
+#[test]
+fn update_region_checks_the_buffer_size() {
+    let (_interface, mut display) = initialized();
+    let mut buf = [0u16; 11];
+
+    assert_eq!(
+        display.update_region(0, 0, 4, 3, &mut buf, |_| {}).err(),
+        Some(Error::BufferTooSmall)
+    );
+    assert_eq!(
+        display.update_region(0, 0, 0, 3, &mut buf, |_| {}).err(),
+        Some(Error::InvalidParameter)
+    );
+    assert!(display.update_region(0, 0, 4, 2, &mut buf, |_| {}).is_ok());
+}
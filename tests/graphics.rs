@@ -0,0 +1,543 @@
+use std::cell::Cell;
+
+use embedded_graphics::image::{Image, ImageDimensions, ImageRaw, IntoPixelIter};
+use embedded_graphics::pixelcolor::{BinaryColor, Rgb565, Rgb888};
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::{Circle, Line, Rectangle, Triangle};
+use embedded_graphics::style::{PrimitiveStyle, PrimitiveStyleBuilder, Styled};
+use st7789v::color::{BLUE, GREEN, RED};
+use st7789v::recording::{FrameMemory, RecordingInterface, Transfer};
+use st7789v::{FrameBuffer, InitOptions, MemAccCtrlConfig, PageColumnOrder};
+
+const WIDTH: u16 = 240;
+const HEIGHT: u16 = 240;
+
+/// Draws `drawable` to the display and to a frame buffer drawing pixel by pixel, asserts that
+/// both results match and returns the number of memory writes used by the display.
+fn draw_and_compare<T>(drawable: &T) -> usize
+where
+    for<'a> &'a T: Drawable<Rgb565>,
+{
+    let interface = RecordingInterface::new();
+    let mut display = interface.display();
+    display.init(&mut interface.delay()).unwrap();
+    let init_len = interface.transfers().len();
+    drawable.draw(&mut display).unwrap();
+
+    let mut buffer = vec![0u16; WIDTH as usize * HEIGHT as usize];
+    let mut expected = FrameBuffer::new(&mut buffer, WIDTH, HEIGHT);
+    drawable.draw(&mut expected).unwrap();
+
+    let transfers = interface.transfers();
+    let mut memory = FrameMemory::new();
+    memory.replay(&transfers);
+    let drawn = memory.area(0, 0, WIDTH, HEIGHT);
+    let mismatch = drawn
+        .iter()
+        .zip(expected.buffer_mut().iter())
+        .position(|(a, b)| a != b)
+        .map(|i| (i % WIDTH as usize, i / WIDTH as usize));
+    assert_eq!(mismatch, None, "first mismatching pixel");
+
+    transfers[init_len..]
+        .iter()
+        .filter(|&t| *t == Transfer::Command(0x2C))
+        .count()
+}
+
+fn line(start: (i32, i32), end: (i32, i32), width: u32) -> Styled<Line, PrimitiveStyle<Rgb565>> {
+    Line::new(Point::new(start.0, start.1), Point::new(end.0, end.1))
+        .into_styled(PrimitiveStyle::with_stroke(Rgb565::RED, width))
+}
+
+#[test]
+fn axis_aligned_lines_use_one_memory_write() {
+    assert_eq!(draw_and_compare(&line((10, 5), (40, 5), 1)), 1);
+    assert_eq!(draw_and_compare(&line((40, 5), (10, 5), 1)), 1);
+    assert_eq!(draw_and_compare(&line((7, 30), (7, 2), 1)), 1);
+}
+
+#[test]
+fn axis_aligned_lines_are_clipped() {
+    assert_eq!(draw_and_compare(&line((-10, 5), (20, 5), 1)), 1);
+    assert_eq!(draw_and_compare(&line((-10, -5), (20, -5), 1)), 0);
+}
+
+#[test]
+fn sloped_lines_are_drawn_as_spans() {
+    // 31 columns over 4 rows: one span per row
+    assert_eq!(draw_and_compare(&line((2, 3), (32, 6), 1)), 4);
+    assert_eq!(draw_and_compare(&line((32, 6), (2, 3), 1)), 4);
+    // 41 rows over 3 columns: one span per column
+    assert_eq!(draw_and_compare(&line((5, 2), (7, 42), 1)), 3);
+    assert_eq!(draw_and_compare(&line((7, 42), (5, 2), 1)), 3);
+}
+
+#[test]
+fn thick_lines_match_pixel_drawing() {
+    let writes = draw_and_compare(&line((3, 3), (50, 20), 4));
+    assert!(writes < 120, "{} memory writes", writes);
+    draw_and_compare(&line((20, 40), (25, 2), 3));
+    draw_and_compare(&line((4, 10), (40, 10), 5));
+}
+
+fn circle(
+    center: (i32, i32),
+    radius: u32,
+    style: PrimitiveStyle<Rgb565>,
+) -> Styled<Circle, PrimitiveStyle<Rgb565>> {
+    Circle::new(Point::new(center.0, center.1), radius).into_styled(style)
+}
+
+fn triangle(
+    p1: (i32, i32),
+    p2: (i32, i32),
+    p3: (i32, i32),
+) -> Styled<Triangle, PrimitiveStyle<Rgb565>> {
+    Triangle::new(
+        Point::new(p1.0, p1.1),
+        Point::new(p2.0, p2.1),
+        Point::new(p3.0, p3.1),
+    )
+    .into_styled(PrimitiveStyle::with_fill(Rgb565::GREEN))
+}
+
+#[test]
+fn filled_circle_uses_one_memory_write_per_row() {
+    let fill = PrimitiveStyle::with_fill(Rgb565::BLUE);
+    let writes = draw_and_compare(&circle((120, 160), 50, fill));
+    assert_eq!(writes, 101);
+}
+
+#[test]
+fn circle_stroke_and_fill_match_pixel_drawing() {
+    let style = PrimitiveStyleBuilder::new()
+        .stroke_color(Rgb565::RED)
+        .stroke_width(3)
+        .fill_color(Rgb565::BLUE)
+        .build();
+    let writes = draw_and_compare(&circle((60, 60), 40, style));
+    assert!(writes < 250, "{} memory writes", writes);
+    draw_and_compare(&circle(
+        (60, 60),
+        40,
+        PrimitiveStyle::with_stroke(Rgb565::RED, 1),
+    ));
+    draw_and_compare(&circle((30, 30), 0, PrimitiveStyle::with_fill(Rgb565::RED)));
+}
+
+#[test]
+fn circles_are_clipped_at_the_display_edges() {
+    let fill = PrimitiveStyle::with_fill(Rgb565::BLUE);
+    draw_and_compare(&circle((5, 225), 30, fill));
+    draw_and_compare(&circle((230, 10), 30, fill));
+    assert_eq!(draw_and_compare(&circle((-40, -40), 10, fill)), 0);
+}
+
+#[test]
+fn filled_triangles_are_drawn_as_spans() {
+    let writes = draw_and_compare(&triangle((10, 10), (200, 40), (60, 120)));
+    assert!(writes < 4 * 111, "{} memory writes", writes);
+    draw_and_compare(&triangle((230, 5), (260, 100), (-20, 250)));
+}
+
+#[test]
+fn degenerate_triangles_match_pixel_drawing() {
+    draw_and_compare(&triangle((10, 10), (40, 40), (70, 70)));
+    draw_and_compare(&triangle((10, 20), (90, 20), (50, 20)));
+    draw_and_compare(&triangle((15, 15), (15, 15), (15, 15)));
+}
+
+#[test]
+fn monochrome_rectangle_is_filled_with_one_memory_write() {
+    let interface = RecordingInterface::new();
+    let mut display = interface.display();
+    display.init(&mut interface.delay()).unwrap();
+    let init_len = interface.transfers().len();
+
+    Rectangle::new(Point::new(4, 6), Point::new(13, 9))
+        .into_styled(PrimitiveStyle::with_fill(BinaryColor::On))
+        .draw(&mut display.monochrome(Rgb565::RED, Rgb565::BLUE))
+        .unwrap();
+
+    let transfers = interface.transfers();
+    let writes = transfers[init_len..]
+        .iter()
+        .filter(|&t| *t == Transfer::Command(0x2C))
+        .count();
+    assert_eq!(writes, 1);
+    let mut memory = FrameMemory::new();
+    memory.replay(&transfers);
+    assert_eq!(memory.area(4, 6, 10, 4), vec![0xF800; 40]);
+}
+
+#[test]
+fn monochrome_images_are_opaque_or_transparent() {
+    let interface = RecordingInterface::new();
+    let mut display = interface.display();
+    display.init(&mut interface.delay()).unwrap();
+    display.clear(0x07E0).unwrap();
+
+    let data = [0b1010_0000, 0b0101_0000];
+    let raw = ImageRaw::<BinaryColor>::new(&data, 4, 2);
+    Image::new(&raw, Point::new(0, 0))
+        .draw(&mut display.monochrome(Rgb565::RED, Rgb565::BLUE))
+        .unwrap();
+    Image::new(&raw, Point::new(10, 0))
+        .draw(
+            &mut display
+                .monochrome(Rgb565::WHITE, Rgb565::BLUE)
+                .transparent(),
+        )
+        .unwrap();
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&interface.transfers());
+    let (r, b, g, w) = (0xF800, 0x001F, 0x07E0, 0xFFFF);
+    assert_eq!(memory.area(0, 0, 4, 2), vec![r, b, r, b, b, r, b, r]);
+    assert_eq!(memory.area(10, 0, 4, 2), vec![w, g, w, g, g, w, g, w]);
+}
+
+#[test]
+fn rgb888_target_matches_the_rgb565_target() {
+    let colors = [
+        Rgb888::new(255, 0, 0),
+        Rgb888::new(0, 255, 0),
+        Rgb888::new(0, 0, 255),
+        Rgb888::new(0x87, 0x4C, 0x1A),
+    ];
+    let pixels = |x: i32| (0..4).map(move |i| Pixel(Point::new(x + i, 3), colors[i as usize]));
+    let circle = Circle::new(Point::new(100, 100), 20);
+
+    let native = RecordingInterface::new();
+    let mut display = native.display();
+    display
+        .draw_iter(pixels(-2).map(|Pixel(p, c)| Pixel(p, Rgb565::from(c))))
+        .unwrap();
+    circle
+        .into_styled(PrimitiveStyle::with_fill(Rgb565::from(colors[3])))
+        .draw(&mut display)
+        .unwrap();
+
+    let adapted = RecordingInterface::new();
+    let mut display = adapted.display();
+    display.rgb888().draw_iter(pixels(-2)).unwrap();
+    circle
+        .into_styled(PrimitiveStyle::with_fill(colors[3]))
+        .draw(&mut display.rgb888())
+        .unwrap();
+
+    assert_eq!(adapted.transfers(), native.transfers());
+    // the two visible pixels are written as one run
+    assert_eq!(
+        adapted.transfers()[..6],
+        [
+            Transfer::Command(0x2A),
+            Transfer::Data(vec![0, 0, 0, 1]),
+            Transfer::Command(0x2B),
+            Transfer::Data(vec![0, 3, 0, 3]),
+            Transfer::Command(0x2C),
+            Transfer::Data(vec![0x00, 0x1F, 0x82, 0x63]),
+        ]
+    );
+}
+
+#[test]
+fn rgb888_dithering_follows_the_bayer_matrix() {
+    let interface = RecordingInterface::new();
+    let mut display = interface.display();
+    display.init(&mut interface.delay()).unwrap();
+
+    // red rises by 2 per column, a quarter of the RGB565 red step
+    let gradient = (0..4)
+        .flat_map(|y| (0..4).map(move |x| Pixel(Point::new(x, y), Rgb888::new(x as u8 * 2, 0, 0))));
+    display
+        .rgb888()
+        .with_dithering()
+        .draw_iter(gradient)
+        .unwrap();
+    Rectangle::new(Point::new(8, 0), Point::new(11, 3))
+        .into_styled(PrimitiveStyle::with_fill(Rgb888::new(2, 0, 0)))
+        .draw(&mut display.rgb888().with_dithering())
+        .unwrap();
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&interface.transfers());
+    let r = 0x0800;
+    #[rustfmt::skip]
+    assert_eq!(memory.area(0, 0, 4, 4), vec![
+        0, 0, 0, r,
+        0, 0, r, r,
+        0, 0, 0, r,
+        0, 0, r, r,
+    ]);
+    #[rustfmt::skip]
+    assert_eq!(memory.area(8, 0, 4, 4), vec![
+        0, 0, 0, 0,
+        r, 0, r, 0,
+        0, 0, 0, 0,
+        r, 0, r, 0,
+    ]);
+}
+
+#[test]
+fn rgb888_dithering_applies_to_single_pixels() {
+    let interface = RecordingInterface::new();
+    let mut display = interface.display();
+    display.init(&mut interface.delay()).unwrap();
+
+    // the threshold at (2, 1) lifts red 2 to the first RGB565 step, at (0, 0) it does not
+    let color = Rgb888::new(2, 0, 0);
+    let mut target = display.rgb888().with_dithering();
+    target.draw_pixel(Pixel(Point::new(2, 1), color)).unwrap();
+    target.draw_pixel(Pixel(Point::new(0, 0), color)).unwrap();
+    display
+        .rgb888()
+        .draw_pixel(Pixel(Point::new(2, 2), color))
+        .unwrap();
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&interface.transfers());
+    assert_eq!(memory.pixel(2, 1), 0x0800);
+    assert_eq!(memory.pixel(0, 0), 0);
+    assert_eq!(memory.pixel(2, 2), 0);
+}
+
+#[test]
+fn zero_sized_images_draw_nothing() {
+    let raw = ImageRaw::<Rgb565>::new(&[], 0, 0);
+    assert_eq!(draw_and_compare(&Image::new(&raw, Point::new(0, 0))), 0);
+    assert_eq!(draw_and_compare(&Image::new(&raw, Point::new(-3, 7))), 0);
+}
+
+#[test]
+fn rectangles_with_negative_origin_are_clipped() {
+    let fill = PrimitiveStyle::with_fill(Rgb565::BLUE);
+    let rect = |tl: (i32, i32), br: (i32, i32)| {
+        Rectangle::new(Point::new(tl.0, tl.1), Point::new(br.0, br.1)).into_styled(fill)
+    };
+    draw_and_compare(&rect((-5, -5), (10, 10)));
+    draw_and_compare(&rect((-70000, 3), (4, 8)));
+    assert_eq!(draw_and_compare(&rect((-20, -20), (-1, -1))), 0);
+}
+
+#[test]
+fn windows_up_to_the_last_pixel_are_drawn() {
+    let data = [0xF8u8, 0x00].repeat(8);
+    let raw = ImageRaw::<Rgb565>::new(&data, 4, 2);
+    assert_eq!(draw_and_compare(&Image::new(&raw, Point::new(236, 238))), 1);
+    draw_and_compare(&Image::new(&raw, Point::new(237, 239)));
+    let rect = Rectangle::new(Point::new(230, 230), Point::new(300, 300))
+        .into_styled(PrimitiveStyle::with_fill(Rgb565::GREEN));
+    draw_and_compare(&rect);
+
+    let interface = RecordingInterface::new();
+    let mut display = interface.display();
+    display
+        .draw_pixel(Pixel(Point::new(-1, 0), Rgb565::RED))
+        .unwrap();
+    assert!(interface.transfers().is_empty());
+    assert!(display.write_line(0, u16::MAX - 1, &[0; 4]).is_err());
+    assert!(display.pixel(u16::MAX, u16::MAX, 0).is_err());
+}
+
+#[test]
+fn pixels_rgb_takes_arrays_and_iterator_chains() {
+    let interface = RecordingInterface::new();
+    let mut display = interface.display();
+    display.init(&mut interface.delay()).unwrap();
+
+    display
+        .pixels_rgb(0, 0, 1, 0, [Rgb565::RED, Rgb565::BLUE])
+        .unwrap()
+        .pixels_rgb(0, 1, 3, 1, (0..4).map(|i| Rgb565::new(i, 0, 0)))
+        .unwrap();
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&interface.transfers());
+    assert_eq!(memory.area(0, 0, 2, 1), vec![0xF800, 0x001F]);
+    assert_eq!(
+        memory.area(0, 1, 4, 1),
+        vec![0x0000, 0x0800, 0x1000, 0x1800]
+    );
+}
+
+#[test]
+fn image_regions_are_drawn_from_an_atlas() {
+    let interface = RecordingInterface::new();
+    let mut display = interface.display();
+    display.init(&mut interface.delay()).unwrap();
+
+    // 4x2 atlas with a different red value in every pixel
+    let data: Vec<u8> = (0..8u16).flat_map(|i| (i << 11).to_be_bytes()).collect();
+    let atlas = ImageRaw::<Rgb565>::new(&data, 4, 2);
+    let cell = Rectangle::new(Point::new(1, 0), Point::new(2, 1));
+    display
+        .draw_image_region(&atlas, cell, Point::new(5, 6))
+        .unwrap();
+    // clipped at the right edge
+    display
+        .draw_image_region(&atlas, cell, Point::new(239, 0))
+        .unwrap();
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&interface.transfers());
+    assert_eq!(
+        memory.area(5, 6, 2, 2),
+        vec![1 << 11, 2 << 11, 5 << 11, 6 << 11]
+    );
+    assert_eq!(memory.area(238, 0, 2, 2), vec![0, 1 << 11, 0, 5 << 11]);
+
+    let outside = Rectangle::new(Point::new(3, 0), Point::new(4, 1));
+    assert!(display
+        .draw_image_region(&atlas, outside, Point::new(0, 0))
+        .is_err());
+}
+
+/// Image with a red channel counting up, which counts the pixels taken from it
+struct CountingImage {
+    width: u32,
+    height: u32,
+    taken: Cell<usize>,
+}
+
+impl ImageDimensions for CountingImage {
+    fn width(&self) -> u32 {
+        self.width
+    }
+
+    fn height(&self) -> u32 {
+        self.height
+    }
+}
+
+impl<'a> IntoPixelIter<Rgb565> for &'a CountingImage {
+    type PixelIterator = Box<dyn Iterator<Item = Pixel<Rgb565>> + 'a>;
+
+    fn pixel_iter(self) -> Self::PixelIterator {
+        let width = self.width as i32;
+        Box::new((0..(self.width * self.height) as i32).map(move |i| {
+            self.taken.set(self.taken.get() + 1);
+            Pixel(
+                Point::new(i % width, i / width),
+                Rgb565::new(i as u8 % 32, 0, 0),
+            )
+        }))
+    }
+}
+
+#[test]
+fn image_regions_stop_after_their_last_row() {
+    let interface = RecordingInterface::new();
+    let mut display = interface.display();
+    display.init(&mut interface.delay()).unwrap();
+
+    let atlas = CountingImage {
+        width: 16,
+        height: 16,
+        taken: Cell::new(0),
+    };
+    let cell = Rectangle::new(Point::new(4, 2), Point::new(7, 3));
+    display
+        .draw_image_region(&atlas, cell, Point::new(10, 10))
+        .unwrap();
+    assert_eq!(atlas.taken.get(), 4 * 16);
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&interface.transfers());
+    // pixel i of the atlas has red i % 32
+    assert_eq!(
+        memory.area(10, 10, 4, 2),
+        [36, 37, 38, 39, 52, 53, 54, 55]
+            .iter()
+            .map(|i| (i % 32) << 11)
+            .collect::<Vec<u16>>()
+    );
+}
+
+#[test]
+fn fills_are_clipped_against_the_exchanged_panel_size() {
+    let interface = RecordingInterface::new();
+    let mut display = interface.display();
+    display
+        .init_with(
+            InitOptions::new().size(135, 240).offset(52, 40),
+            &mut interface.delay(),
+        )
+        .unwrap();
+    display
+        .memory_access_control(
+            MemAccCtrlConfig::default().page_column_order(PageColumnOrder::ReverseMode),
+        )
+        .unwrap();
+
+    Rectangle::new(Point::new(230, 130), Point::new(250, 140))
+        .into_styled(PrimitiveStyle::with_fill(Rgb565::RED))
+        .draw(&mut display)
+        .unwrap();
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&interface.transfers());
+    // the last panel row and column, transposed into the frame memory
+    assert_eq!(memory.pixel(52 + 134, 40 + 239), 0xF800);
+    assert_eq!(memory.pixel(52 + 130, 40 + 230), 0xF800);
+    assert_eq!(memory.pixel(52 + 129, 40 + 230), 0);
+}
+
+#[test]
+fn cropped_target_translates_and_clips() {
+    let interface = RecordingInterface::new();
+    let mut display = interface.display();
+    display.init(&mut interface.delay()).unwrap();
+
+    let mut widget = display.cropped(Rectangle::new(Point::new(10, 10), Point::new(29, 19)));
+    assert_eq!(widget.size(), Size::new(20, 10));
+    let start = interface.transfers().len();
+    Rectangle::new(Point::new(-5, -5), Point::new(4, 4))
+        .into_styled(PrimitiveStyle::with_fill(Rgb565::RED))
+        .draw(&mut widget)
+        .unwrap();
+    let memory_writes = interface.transfers()[start..]
+        .iter()
+        .filter(|&t| *t == Transfer::Command(0x2C))
+        .count();
+    assert_eq!(memory_writes, 1);
+    Circle::new(Point::new(19, 9), 4)
+        .into_styled(PrimitiveStyle::with_fill(Rgb565::BLUE))
+        .draw(&mut widget)
+        .unwrap();
+
+    // nested targets are clipped to their parent
+    let mut label = widget.cropped(Rectangle::new(Point::new(15, 5), Point::new(40, 40)));
+    assert_eq!(label.size(), Size::new(26, 36));
+    Pixel(Point::new(0, 0), Rgb565::GREEN)
+        .draw(&mut label)
+        .unwrap();
+    Pixel(Point::new(5, 0), Rgb565::GREEN)
+        .draw(&mut label)
+        .unwrap();
+
+    // nothing of the target is visible
+    let before = interface.bus_calls();
+    let mut hidden = label.cropped(Rectangle::new(Point::new(100, 0), Point::new(110, 10)));
+    Rectangle::new(Point::new(0, 0), Point::new(10, 10))
+        .into_styled(PrimitiveStyle::with_fill(Rgb565::RED))
+        .draw(&mut hidden)
+        .unwrap();
+    assert_eq!(interface.bus_calls(), before);
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&interface.transfers());
+    assert_eq!(memory.area(9, 9, 7, 1), vec![0; 7]);
+    assert_eq!(
+        memory.area(9, 14, 7, 1),
+        vec![0, RED, RED, RED, RED, RED, 0]
+    );
+    assert_eq!(memory.pixel(10, 15), 0);
+    // the circle is cut at the right and bottom edge
+    assert_eq!(memory.pixel(29, 19), BLUE);
+    assert_eq!(memory.area(30, 15, 5, 5), vec![0; 25]);
+    assert_eq!(memory.area(25, 20, 5, 5), vec![0; 25]);
+    assert_eq!(memory.pixel(25, 15), GREEN);
+    assert_eq!(memory.pixel(30, 15), 0);
+}
@@ -0,0 +1,94 @@
+use std::cell::RefCell;
+use std::convert::Infallible;
+use std::rc::Rc;
+
+use embedded_hal::digital::v2::OutputPin;
+use st7789v::recording::{FrameMemory, NoDelay, Transfer};
+use st7789v::{NoPin, Parallel8080, ST7789V};
+
+/// Levels of the parallel interface lines and the bytes latched by the display
+#[derive(Default)]
+struct Lines {
+    data: u8,
+    dc_high: bool,
+    wr_high: bool,
+    latched: Vec<(bool, u8)>,
+}
+
+#[derive(Clone, Copy)]
+enum Line {
+    Data(u8),
+    Dc,
+    Wr,
+}
+
+struct LinePin(Rc<RefCell<Lines>>, Line);
+
+impl LinePin {
+    fn set(&mut self, high: bool) {
+        let mut lines = self.0.borrow_mut();
+        match self.1 {
+            Line::Data(bit) if high => lines.data |= 1 << bit,
+            Line::Data(bit) => lines.data &= !(1 << bit),
+            Line::Dc => lines.dc_high = high,
+            Line::Wr => {
+                // the display latches the data bus on the rising edge of WR
+                if high && !lines.wr_high {
+                    let byte = (lines.dc_high, lines.data);
+                    lines.latched.push(byte);
+                }
+                lines.wr_high = high;
+            }
+        }
+    }
+}
+
+impl OutputPin for LinePin {
+    type Error = Infallible;
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.set(false);
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.set(true);
+        Ok(())
+    }
+}
+
+#[test]
+fn bytes_are_latched_on_the_write_strobe() {
+    let lines = Rc::new(RefCell::new(Lines {
+        wr_high: true,
+        ..Lines::default()
+    }));
+    let pin = |line| LinePin(lines.clone(), line);
+    let data = [0, 1, 2, 3, 4, 5, 6, 7].map(|bit| pin(Line::Data(bit)));
+    let bus = Parallel8080::new(data, pin(Line::Wr)).unwrap();
+    let mut display = ST7789V::new(bus, pin(Line::Dc), NoPin::new());
+
+    display.init(&mut NoDelay).unwrap();
+    display.pixel(3, 4, 0xA5C3).unwrap();
+
+    let lines = lines.borrow();
+    let transfers: Vec<_> = lines
+        .latched
+        .iter()
+        .map(|&(dc_high, byte)| {
+            if dc_high {
+                Transfer::Data(vec![byte])
+            } else {
+                Transfer::Command(byte)
+            }
+        })
+        .collect();
+    assert_eq!(
+        transfers[..2],
+        [Transfer::Command(0x01), Transfer::Command(0x11)]
+    );
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&transfers);
+    assert_eq!(memory.area(3, 4, 1, 1), vec![0xA5C3]);
+}
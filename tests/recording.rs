@@ -0,0 +1,1183 @@
+use std::ops::ControlFlow;
+
+use std::cell::Cell;
+use std::convert::Infallible;
+use std::rc::Rc;
+
+use embedded_hal::blocking::spi::{self, Write};
+use embedded_hal::digital::v2::OutputPin;
+use st7789v::recording::{
+    replay, FrameMemory, RecordingDisplay, RecordingInterface, RecordingSpi, Transfer,
+};
+#[cfg(feature = "bmp")]
+use st7789v::BmpError;
+#[cfg(feature = "patterns")]
+use st7789v::GradientDirection;
+use st7789v::{
+    presets, ColumnAddressOrder, Error, InitOptions, MemAccCtrlConfig, NoPin, PageColumnOrder,
+    ST7789V,
+};
+
+const RED: u16 = 0xF800;
+const GREEN: u16 = 0x07E0;
+const BLUE: u16 = 0x001F;
+
+fn initialized() -> (RecordingInterface, RecordingDisplay) {
+    let interface = RecordingInterface::new();
+    let mut display = interface.display();
+    display.init(&mut interface.delay()).unwrap();
+
+    (interface, display)
+}
+
+#[test]
+fn captures_commands_and_merges_data() {
+    let interface = RecordingInterface::new();
+    let mut display = interface.display();
+
+    display
+        .pixels(1, 0, 2, 0, &mut [RED, GREEN].iter().copied())
+        .unwrap();
+
+    assert_eq!(
+        interface.take_transfers(),
+        vec![
+            Transfer::Command(0x2A),
+            Transfer::Data(vec![0x00, 0x01, 0x00, 0x02]),
+            Transfer::Command(0x2B),
+            Transfer::Data(vec![0x00, 0x00, 0x00, 0x00]),
+            Transfer::Command(0x2C),
+            Transfer::Data(vec![0xF8, 0x00, 0x07, 0xE0]),
+        ]
+    );
+    assert!(interface.transfers().is_empty());
+}
+
+#[test]
+fn pixel_lands_at_its_position() {
+    let (interface, mut display) = initialized();
+
+    display.pixel(3, 2, RED).unwrap();
+
+    let frame = replay(&interface.transfers(), 240, 240);
+    assert_eq!(frame[2 * 240 + 3], RED);
+    assert_eq!(frame.iter().filter(|&&color| color != 0).count(), 1);
+}
+
+#[test]
+fn address_pointer_wraps_inside_window() {
+    let (interface, mut display) = initialized();
+
+    display
+        .pixels(
+            10,
+            20,
+            11,
+            21,
+            &mut [RED, GREEN, BLUE, RED, BLUE].iter().copied(),
+        )
+        .unwrap();
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&interface.transfers());
+    assert_eq!(memory.area(10, 20, 2, 2), vec![BLUE, GREEN, BLUE, RED]);
+}
+
+#[test]
+fn memory_access_control_mirrors_and_exchanges() {
+    let (interface, mut display) = initialized();
+
+    display
+        .memory_access_control(
+            MemAccCtrlConfig::default().column_order(ColumnAddressOrder::RightToLeft),
+        )
+        .unwrap();
+    display.pixel(0, 0, RED).unwrap();
+    display
+        .memory_access_control(
+            MemAccCtrlConfig::default().page_column_order(PageColumnOrder::ReverseMode),
+        )
+        .unwrap();
+    display.pixel(5, 1, GREEN).unwrap();
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&interface.transfers());
+    assert_eq!(memory.pixel(239, 0), RED);
+    assert_eq!(memory.pixel(1, 5), GREEN);
+}
+
+#[test]
+fn color_formats_are_converted_to_rgb565() {
+    let (interface, mut display) = initialized();
+
+    display
+        .color_mode(
+            st7789v::ColorFormat::RGB262K_CI18Bit,
+            &mut interface.delay(),
+        )
+        .unwrap();
+    display
+        .pixels18(0, 0, 1, 0, &mut [0x3_F000, 0x0_003F].iter().copied())
+        .unwrap();
+
+    assert_eq!(replay(&interface.transfers(), 2, 1), vec![RED, BLUE]);
+}
+
+#[test]
+fn pixels18_are_written_in_chunks() {
+    let (interface, mut display) = initialized();
+    display
+        .color_mode(
+            st7789v::ColorFormat::RGB262K_CI18Bit,
+            &mut interface.delay(),
+        )
+        .unwrap();
+
+    let bus_calls = interface.bus_calls();
+    display
+        .pixels18(0, 0, 9, 9, &mut core::iter::repeat(0x3_F000).take(100))
+        .unwrap();
+    // CASET, RASET and their parameters, RAMWR and 300 bytes in chunks of 42 pixels
+    assert_eq!(interface.bus_calls() - bus_calls, 5 + 3);
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&interface.transfers());
+    assert_eq!(memory.area(0, 0, 10, 10), vec![RED; 100]);
+}
+
+#[test]
+fn pixels12_packs_pairs_and_sends_a_trailing_pixel_as_two_bytes() {
+    let (interface, mut display) = initialized();
+    display
+        .color_mode(st7789v::ColorFormat::RGB65K_CI12Bit, &mut interface.delay())
+        .unwrap();
+
+    display
+        .pixels12(0, 0, 2, 0, &mut [0xF00, 0x0F0, 0x00F].iter().copied())
+        .unwrap();
+    assert_eq!(
+        interface.transfers().last(),
+        Some(&Transfer::Data(vec![0xF0, 0x00, 0xF0, 0x00, 0xF0]))
+    );
+
+    // the display only stores complete pairs
+    assert_eq!(replay(&interface.transfers(), 3, 1), vec![RED, GREEN, 0]);
+}
+
+/// SPI bus with 16 bit frames, the words are recorded MSB first
+struct WordSpi {
+    spi: RecordingSpi,
+    word_writes: Rc<Cell<usize>>,
+}
+
+impl spi::Write<u8> for WordSpi {
+    type Error = Infallible;
+
+    fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+        self.spi.write(words)
+    }
+}
+
+impl spi::Write<u16> for WordSpi {
+    type Error = Infallible;
+
+    fn write(&mut self, words: &[u16]) -> Result<(), Self::Error> {
+        self.word_writes.set(self.word_writes.get() + 1);
+        let bytes: Vec<u8> = words.iter().flat_map(|word| word.to_be_bytes()).collect();
+        self.spi.write(&bytes)
+    }
+}
+
+#[test]
+fn pixels_u16_are_written_as_words() {
+    let interface = RecordingInterface::new();
+    let word_writes = Rc::new(Cell::new(0));
+    let spi = WordSpi {
+        spi: interface.spi(),
+        word_writes: word_writes.clone(),
+    };
+    let mut display = ST7789V::new(spi, interface.dc(), NoPin::new());
+    display.init(&mut interface.delay()).unwrap();
+
+    let colors = (0..100u16).map(|i| i * 0x0201);
+    display.pixels_u16(0, 0, 9, 9, &mut colors.clone()).unwrap();
+    // 128 byte chunks of 64 colors
+    assert_eq!(word_writes.get(), 2);
+    display.pixel_u16(20, 0, RED).unwrap();
+
+    // 20 byte chunks of 10 colors
+    let mut display = display.with_chunk_size::<20>();
+    display
+        .pixels_u16(0, 20, 9, 29, &mut colors.clone())
+        .unwrap();
+    assert_eq!(word_writes.get(), 13);
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&interface.transfers());
+    let colors: Vec<_> = colors.collect();
+    assert_eq!(memory.area(0, 0, 10, 10), colors);
+    assert_eq!(memory.area(0, 20, 10, 10), colors);
+    assert_eq!(memory.pixel(20, 0), RED);
+}
+
+#[test]
+fn frame_writer_hands_out_the_bus_for_pixel_data() {
+    let (interface, mut display) = initialized();
+
+    let mut writer = display.start_frame_write(4, 5, 5, 5).unwrap();
+    writer.spi().write(&[0xF8, 0x00]).unwrap();
+    writer.with_bus(|spi| spi.write(&[0x07, 0xE0])).unwrap();
+    writer.finish().unwrap();
+    assert_eq!(interface.transfers().last(), Some(&Transfer::Command(0x00)));
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&interface.transfers());
+    assert_eq!(memory.area(4, 5, 2, 1), vec![RED, GREEN]);
+}
+
+#[test]
+fn software_reset_keeps_memory_content() {
+    let (interface, mut display) = initialized();
+
+    display.pixel(0, 0, RED).unwrap();
+    display.soft_reset(&mut interface.delay()).unwrap();
+
+    assert_eq!(replay(&interface.transfers(), 1, 1), vec![RED]);
+}
+
+#[test]
+fn write_line_fills_a_row() {
+    let (interface, mut display) = initialized();
+
+    display.write_line(1, 2, &[RED, GREEN, BLUE]).unwrap();
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&interface.transfers());
+    assert_eq!(memory.area(1, 1, 5, 1), vec![0, RED, GREEN, BLUE, 0]);
+}
+
+#[test]
+fn render_by_lines_continues_the_memory_write() {
+    let (interface, mut display) = initialized();
+
+    display
+        .render_by_lines(4, 6, 5, 8, |y, line| {
+            for (x, color) in line.iter_mut().enumerate() {
+                *color = y * 16 + x as u16;
+            }
+        })
+        .unwrap();
+
+    let transfers = interface.transfers();
+    let writes = |cmd| {
+        transfers
+            .iter()
+            .filter(|&t| *t == Transfer::Command(cmd))
+            .count()
+    };
+    assert_eq!((writes(0x2C), writes(0x3C)), (1, 2));
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&transfers);
+    assert_eq!(memory.area(4, 6, 2, 3), vec![96, 97, 112, 113, 128, 129]);
+}
+
+#[test]
+fn scroll_up_needs_scroll_area() {
+    let (_interface, mut display) = initialized();
+
+    assert_eq!(
+        display.scroll_up(8, RED).err(),
+        Some(st7789v::Error::NoScrollArea)
+    );
+}
+
+#[test]
+fn scroll_up_fills_exposed_rows_and_wraps() {
+    let (interface, mut display) = initialized();
+
+    display.vertical_scroll_area(0, 320, 0).unwrap();
+    display.vertical_scroll_start(315).unwrap();
+    display.scroll_up(10, RED).unwrap();
+    assert_eq!(display.scroll_offset(), 5);
+
+    let transfers = interface.transfers();
+    assert_eq!(
+        transfers[transfers.len() - 2..],
+        [Transfer::Command(0x37), Transfer::Data(vec![0x00, 0x05])]
+    );
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&transfers);
+    for row in (0..5).chain(315..320) {
+        assert_eq!(memory.pixel(17, row), RED, "row {}", row);
+    }
+    assert_eq!(memory.pixel(17, 5), 0);
+    assert_eq!(memory.pixel(17, 314), 0);
+}
+
+#[test]
+fn scroll_up_fills_gate_lines_with_exchanged_page_and_column_order() {
+    let interface = RecordingInterface::new();
+    let mut display = interface.display();
+    display
+        .init_with(
+            InitOptions::new().memory_access_control(
+                MemAccCtrlConfig::new().page_column_order(PageColumnOrder::ReverseMode),
+            ),
+            &mut interface.delay(),
+        )
+        .unwrap();
+    display.vertical_scroll_area(0, 320, 0).unwrap();
+
+    // a full turn through the scroll area
+    for _ in 0..20 {
+        display.scroll_up(16, RED).unwrap();
+    }
+    assert_eq!(display.scroll_offset(), 0);
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&interface.transfers());
+    for row in [0, 15, 16, 319].iter().copied() {
+        assert_eq!(memory.area(0, row, 240, 1), vec![RED; 240], "row {}", row);
+    }
+}
+
+#[cfg(feature = "graphics")]
+#[test]
+fn draw_iter_coalesces_horizontal_runs() {
+    use embedded_graphics::drawable::Pixel;
+    use embedded_graphics::pixelcolor::Rgb565;
+    use embedded_graphics::prelude::*;
+
+    let (interface, mut display) = initialized();
+
+    let pixels = [(3, 1), (4, 1), (5, 1), (6, 1), (8, 1), (3, 2)];
+    display
+        .draw_iter(
+            pixels
+                .iter()
+                .map(|&(x, y)| Pixel(Point::new(x, y), Rgb565::RED)),
+        )
+        .unwrap();
+
+    let transfers = interface.transfers();
+    let memory_writes = transfers
+        .iter()
+        .filter(|&t| *t == Transfer::Command(0x2C))
+        .count();
+    assert_eq!(memory_writes, 3);
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&transfers);
+    assert_eq!(
+        memory.area(2, 1, 8, 1),
+        vec![0, RED, RED, RED, RED, 0, RED, 0]
+    );
+    assert_eq!(memory.area(2, 2, 3, 1), vec![0, RED, 0]);
+}
+
+#[test]
+fn blit_mono_expands_bits_with_row_padding() {
+    let (interface, mut display) = initialized();
+
+    // 10 pixels wide, so each row takes two bytes with the last 6 bits unused
+    let bitmap = [0b1010_0000, 0b0100_0000, 0b0000_0001, 0b1111_1111];
+    display.blit_mono(3, 4, 10, 2, &bitmap, RED, BLUE).unwrap();
+
+    let transfers = interface.transfers();
+    let memory_writes = transfers
+        .iter()
+        .filter(|&t| *t == Transfer::Command(0x2C))
+        .count();
+    assert_eq!(memory_writes, 1);
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&transfers);
+    let (f, b) = (RED, BLUE);
+    assert_eq!(
+        memory.area(3, 4, 10, 2),
+        vec![f, b, f, b, b, b, b, b, b, f, b, b, b, b, b, b, b, f, f, f]
+    );
+}
+
+#[test]
+fn blit_mono_is_clipped_at_the_display_edge() {
+    let (interface, mut display) = initialized();
+
+    let bitmap = [0b1100_0000, 0b0100_0000, 0b1000_0000];
+    display
+        .blit_mono(238, 238, 3, 3, &bitmap, RED, BLUE)
+        .unwrap();
+    let transfers = interface.transfers();
+    display.blit_mono(240, 0, 3, 3, &bitmap, RED, BLUE).unwrap();
+    assert_eq!(interface.transfers(), transfers);
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&transfers);
+    assert_eq!(memory.area(238, 238, 2, 3), vec![RED, RED, BLUE, RED, 0, 0]);
+}
+
+#[test]
+fn blit_mono_rejects_a_short_bitmap() {
+    let (interface, mut display) = initialized();
+
+    let transfers = interface.transfers();
+    // three rows of two bytes are needed
+    let bitmap = [0xFF; 5];
+    assert_eq!(
+        display.blit_mono(0, 0, 10, 3, &bitmap, RED, BLUE).err(),
+        Some(st7789v::Error::BufferTooSmall)
+    );
+    assert_eq!(interface.transfers(), transfers);
+}
+
+#[test]
+fn blit_sub_draws_a_cell_of_an_atlas() {
+    let (interface, mut display) = initialized();
+
+    // 6x3 atlas, each pixel holds its own index
+    let atlas: Vec<u16> = (0..18).collect();
+    display.blit_sub(10, 20, &atlas, 6, 2, 1, 3, 2).unwrap();
+    display.blit_sub(238, 0, &atlas, 6, 0, 0, 3, 2).unwrap();
+
+    let transfers = interface.transfers();
+    let memory_writes = transfers
+        .iter()
+        .filter(|&t| *t == Transfer::Command(0x2C))
+        .count();
+    assert_eq!(memory_writes, 2);
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&transfers);
+    assert_eq!(memory.area(10, 20, 3, 2), vec![8, 9, 10, 14, 15, 16]);
+    assert_eq!(memory.area(238, 0, 2, 2), vec![0, 1, 6, 7]);
+}
+
+#[test]
+fn blit_sub_checks_the_source_bounds() {
+    let (_interface, mut display) = initialized();
+    let atlas = [0u16; 18];
+
+    assert_eq!(
+        display.blit_sub(0, 0, &atlas, 6, 4, 0, 3, 1).err(),
+        Some(Error::InvalidParameter)
+    );
+    assert_eq!(
+        display.blit_sub(0, 0, &atlas, 6, 0, 2, 1, 2).err(),
+        Some(Error::BufferTooSmall)
+    );
+    assert!(display.blit_sub(0, 0, &atlas, 6, 3, 2, 3, 1).is_ok());
+}
+
+#[test]
+fn window_transaction_writes_all_slices_with_one_bus_call() {
+    use embedded_hal::blocking::spi::Operation;
+
+    let (interface, mut display) = initialized();
+    let top = [0xF8, 0x00, 0x07, 0xE0];
+    let bottom = [0x00, 0x1F, 0xF8, 0x00];
+    let calls = interface.bus_calls();
+
+    display
+        .write_window_transaction(
+            3,
+            4,
+            4,
+            5,
+            &mut [Operation::Write(&top), Operation::Write(&bottom)],
+        )
+        .unwrap();
+
+    // CASET, its parameters, RASET, its parameters, RAMWR and the pixel data
+    assert_eq!(interface.bus_calls() - calls, 6);
+    let mut memory = FrameMemory::new();
+    memory.replay(&interface.transfers());
+    assert_eq!(memory.area(3, 4, 2, 2), vec![RED, GREEN, BLUE, RED]);
+}
+
+#[test]
+fn window_writer_streams_pushed_colors() {
+    let (interface, mut display) = initialized();
+
+    let mut window = display.window(10, 20, 3, 2).unwrap();
+    for &color in &[RED, GREEN, BLUE, BLUE, GREEN, RED] {
+        window.push(color).unwrap();
+    }
+    assert_eq!(window.remaining(), 0);
+    assert_eq!(
+        window.push(RED).unwrap_err(),
+        Error::PixelCountMismatch {
+            expected: 6,
+            written: 7
+        }
+    );
+    window.finish().unwrap();
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&interface.transfers());
+    assert_eq!(
+        memory.area(10, 20, 3, 2),
+        vec![RED, GREEN, BLUE, BLUE, GREEN, RED]
+    );
+}
+
+#[test]
+fn pixel_counts_are_checked_against_the_window() {
+    let (interface, mut display) = initialized();
+
+    // an off by one in the width is caught before anything is written
+    let bus_calls = interface.bus_calls();
+    assert_eq!(
+        display.pixels_exact(0, 0, 3, 1, [RED; 10]).err(),
+        Some(Error::PixelCountMismatch {
+            expected: 8,
+            written: 10
+        })
+    );
+    assert_eq!(interface.bus_calls(), bus_calls);
+    display.pixels_exact(0, 0, 3, 1, [GREEN; 8]).unwrap();
+
+    assert_eq!(
+        display
+            .set_pixels_checked(0, 4, 2, 4, core::iter::repeat(BLUE))
+            .err(),
+        Some(Error::PixelCountMismatch {
+            expected: 3,
+            written: 4
+        })
+    );
+    assert_eq!(
+        display
+            .set_pixels_checked(0, 6, 2, 6, [RED; 2].iter().copied())
+            .err(),
+        Some(Error::PixelCountMismatch {
+            expected: 3,
+            written: 2
+        })
+    );
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&interface.transfers());
+    assert_eq!(memory.area(0, 0, 4, 2), vec![GREEN; 8]);
+    assert_eq!(memory.area(0, 4, 4, 1), vec![BLUE, BLUE, BLUE, 0]);
+    assert_eq!(memory.area(0, 6, 3, 1), vec![RED, RED, 0]);
+}
+
+#[test]
+fn window_writer_reports_missing_pixels_and_flushes_on_drop() {
+    let (interface, mut display) = initialized();
+
+    let mut window = display.window(0, 0, 2, 2).unwrap();
+    window.push(RED).unwrap();
+    assert_eq!(
+        window.finish().unwrap_err(),
+        Error::PixelCountMismatch {
+            expected: 4,
+            written: 1
+        }
+    );
+
+    let mut window = display.window(5, 5, 2, 1).unwrap();
+    window.push(GREEN).unwrap();
+    drop(window);
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&interface.transfers());
+    assert_eq!(memory.area(0, 0, 1, 1), vec![RED]);
+    assert_eq!(memory.area(5, 5, 1, 1), vec![GREEN]);
+    assert_eq!(
+        display.window(0, 0, 0, 1).err(),
+        Some(Error::InvalidParameter)
+    );
+}
+
+#[test]
+fn pixels_from_fn_follow_screen_coordinates() {
+    let (interface, mut display) = initialized();
+    let gradient = |x: u16, y: u16| (y << 8) | x;
+
+    display
+        .write_pixels_from_fn(4, 6, 3, 2, gradient)
+        .unwrap()
+        .memory_access_control(
+            MemAccCtrlConfig::default().page_column_order(PageColumnOrder::ReverseMode),
+        )
+        .unwrap()
+        .write_pixels_from_fn(20, 10, 2, 3, gradient)
+        .unwrap();
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&interface.transfers());
+    assert_eq!(
+        memory.area(4, 6, 3, 2),
+        vec![0x0604, 0x0605, 0x0606, 0x0704, 0x0705, 0x0706]
+    );
+    // rows and columns are exchanged in frame memory
+    for (x, y) in [(20, 10), (21, 10), (20, 12), (21, 12)] {
+        assert_eq!(memory.pixel(y, x), gradient(x, y));
+    }
+}
+
+#[test]
+fn pixels_from_fn_reject_windows_past_the_address_range() {
+    let (interface, mut display) = initialized();
+
+    let transfers = interface.transfers();
+    assert!(display
+        .write_pixels_from_fn(u16::MAX, u16::MAX, 2, 2, |_, _| RED)
+        .is_err());
+    assert_eq!(interface.transfers(), transfers);
+}
+
+#[test]
+fn self_test_leaves_border_and_corner_marker() {
+    let (interface, mut display) = initialized();
+
+    display.self_test(&mut interface.delay()).unwrap();
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&interface.transfers());
+    for i in 0..240 {
+        assert_eq!(memory.pixel(i, 0), 0xFFFF);
+        assert_eq!(memory.pixel(i, 239), 0xFFFF);
+        assert_eq!(memory.pixel(0, i), 0xFFFF);
+        assert_eq!(memory.pixel(239, i), 0xFFFF);
+    }
+    assert_eq!(memory.pixel(1, 1), 0x0000);
+    assert_eq!(memory.area(2, 2, 15, 15), vec![RED; 15 * 15]);
+    assert_eq!(memory.pixel(17, 17), 0x0000);
+    assert_eq!(memory.pixel(238, 238), 0x0000);
+}
+
+/// A 16 bit RGB565 BMP file with the rows given top to bottom
+#[cfg(feature = "bmp")]
+fn bmp565(width: i32, rows: &[&[u16]], top_down: bool) -> Vec<u8> {
+    let height = rows.len() as i32;
+    let mut data = b"BM".to_vec();
+    data.extend_from_slice(&[0; 8]);
+    data.extend_from_slice(&66u32.to_le_bytes());
+    data.extend_from_slice(&40u32.to_le_bytes());
+    data.extend_from_slice(&width.to_le_bytes());
+    data.extend_from_slice(&(if top_down { -height } else { height }).to_le_bytes());
+    data.extend_from_slice(&1u16.to_le_bytes());
+    data.extend_from_slice(&16u16.to_le_bytes());
+    data.extend_from_slice(&3u32.to_le_bytes());
+    data.extend_from_slice(&[0; 20]);
+    for mask in &[0xF800u32, 0x07E0, 0x001F] {
+        data.extend_from_slice(&mask.to_le_bytes());
+    }
+
+    let mut ordered = rows.to_vec();
+    if !top_down {
+        ordered.reverse();
+    }
+    for row in ordered {
+        for color in row {
+            data.extend_from_slice(&color.to_le_bytes());
+        }
+        while data.len() % 4 != 66 % 4 {
+            data.push(0);
+        }
+    }
+    data
+}
+
+#[cfg(feature = "bmp")]
+#[test]
+fn bmp_rows_are_drawn_top_down_without_padding() {
+    let rows: [&[u16]; 2] = [&[RED, GREEN, BLUE], &[BLUE, RED, GREEN]];
+    for &top_down in &[false, true] {
+        let (interface, mut display) = initialized();
+
+        display
+            .draw_bmp565(5, 6, &bmp565(3, &rows, top_down))
+            .unwrap();
+
+        let mut memory = FrameMemory::new();
+        memory.replay(&interface.transfers());
+        assert_eq!(
+            memory.area(5, 6, 3, 2),
+            vec![RED, GREEN, BLUE, BLUE, RED, GREEN]
+        );
+    }
+}
+
+#[cfg(feature = "bmp")]
+#[test]
+fn unsupported_bmp_is_rejected() {
+    let (_interface, mut display) = initialized();
+
+    let mut rgb555 = bmp565(1, &[&[RED]], false);
+    rgb555[54..58].copy_from_slice(&0x7C00u32.to_le_bytes());
+    assert_eq!(
+        display.draw_bmp565(0, 0, &rgb555).err(),
+        Some(Error::InvalidBmp(BmpError::UnsupportedCompression(3)))
+    );
+
+    let mut rgb888 = bmp565(1, &[&[RED]], false);
+    rgb888[28] = 24;
+    assert_eq!(
+        display.draw_bmp565(0, 0, &rgb888).err(),
+        Some(Error::InvalidBmp(BmpError::UnsupportedBitDepth(24)))
+    );
+
+    let truncated = bmp565(2, &[&[RED, GREEN], &[BLUE, RED]], false);
+    assert_eq!(
+        display
+            .draw_bmp565(0, 0, &truncated[..truncated.len() - 1])
+            .err(),
+        Some(Error::InvalidBmp(BmpError::Truncated))
+    );
+}
+
+#[cfg(feature = "patterns")]
+#[test]
+fn gradient_reaches_both_colors_and_steps_evenly() {
+    let (interface, mut display) = initialized();
+
+    display
+        .draw_gradient(GradientDirection::Horizontal, 0x0000, 0xFFFF)
+        .unwrap();
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&interface.transfers());
+    assert_eq!(memory.pixel(0, 100), 0x0000);
+    assert_eq!(memory.pixel(239, 100), 0xFFFF);
+    let blue: Vec<u16> = (0..240).map(|x| memory.pixel(x, 0) & 0x1F).collect();
+    assert!(blue.windows(2).all(|pair| pair[1] - pair[0] <= 1));
+    // 32 blue values spread over 240 columns, each one 7 or 8 columns wide
+    for value in 1..31 {
+        let columns = blue.iter().filter(|&&b| b == value).count();
+        assert!(columns == 7 || columns == 8, "{} columns", columns);
+    }
+}
+
+#[cfg(feature = "patterns")]
+#[test]
+fn checkerboard_and_grid_follow_the_panel_offset() {
+    let interface = RecordingInterface::new();
+    let mut display = interface.display();
+    let options = InitOptions::new().size(135, 240).offset(52, 40);
+    display.init_with(options, &mut interface.delay()).unwrap();
+
+    display.draw_checkerboard(10, RED, BLUE).unwrap();
+    let mut memory = FrameMemory::new();
+    memory.replay(&interface.take_transfers());
+    assert_eq!(memory.pixel(52, 40), RED);
+    assert_eq!(memory.pixel(62, 40), BLUE);
+    assert_eq!(memory.pixel(62, 50), RED);
+    assert_eq!(memory.pixel(51, 40), 0);
+
+    display.draw_grid(20, GREEN, 0).unwrap();
+    memory.replay(&interface.take_transfers());
+    assert_eq!(memory.pixel(52 + 20, 40 + 5), GREEN);
+    assert_eq!(memory.pixel(52 + 5, 40 + 5), 0);
+    assert_eq!(memory.pixel(52 + 134, 40 + 5), GREEN);
+    assert_eq!(memory.pixel(52 + 5, 40 + 239), GREEN);
+}
+
+#[cfg(feature = "patterns")]
+#[test]
+fn color_bars_fill_the_panel() {
+    let (interface, mut display) = initialized();
+
+    display.draw_color_bars().unwrap();
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&interface.transfers());
+    assert_eq!(memory.pixel(0, 0), 0xFFFF);
+    assert_eq!(memory.pixel(30, 239), 0xFFE0);
+    assert_eq!(memory.pixel(5 * 30, 120), RED);
+    assert_eq!(memory.pixel(239, 239), 0x0000);
+    assert_eq!(memory.pixel(6 * 30 + 29, 0), BLUE);
+}
+
+#[test]
+fn set_pixels_writes_the_same_chunks_as_pixels() {
+    let count: usize = 240 * 240;
+    let (dyn_interface, mut display) = initialized();
+    dyn_interface.take_transfers();
+    let calls = dyn_interface.bus_calls();
+    display
+        .pixels(0, 0, 239, 239, &mut (0..count).map(|i| i as u16))
+        .unwrap();
+    let dyn_calls = dyn_interface.bus_calls() - calls;
+
+    let (interface, mut display) = initialized();
+    interface.take_transfers();
+    let calls = interface.bus_calls();
+    display
+        .set_pixels(0, 0, 239, 239, (0..count).map(|i| i as u16))
+        .unwrap();
+
+    // window, RAMWR and one transfer for every 128 byte chunk
+    assert_eq!(dyn_calls, 5 + (2 * count + 127) / 128);
+    assert_eq!(interface.bus_calls() - calls, dyn_calls);
+    assert_eq!(interface.transfers(), dyn_interface.transfers());
+}
+
+#[test]
+fn uninitialized_display_is_ready_after_init() {
+    let interface = RecordingInterface::new();
+    let display = interface.display().into_uninitialized();
+    assert!(interface.transfers().is_empty());
+
+    let mut display = display.init(&mut interface.delay()).unwrap();
+    let init = interface.take_transfers();
+    let reference = RecordingInterface::new();
+    reference.display().init(&mut reference.delay()).unwrap();
+    assert_eq!(init, reference.transfers());
+
+    display.pixel(0, 0, RED).unwrap();
+    let display = display
+        .reset_into_uninitialized(&mut interface.delay())
+        .unwrap();
+    let mut display = display.init(&mut interface.delay()).unwrap();
+    display.pixel(1, 0, GREEN).unwrap();
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&interface.transfers());
+    assert_eq!(memory.pixel(1, 0), GREEN);
+    assert!(display.release().is_ok());
+}
+
+#[test]
+fn profiles_set_the_panel_geometry() {
+    let interface = RecordingInterface::new();
+    let mut display = interface.display();
+    display
+        .init_with_profile(&presets::TTGO_T_DISPLAY, &mut interface.delay())
+        .unwrap();
+    assert_eq!(display.size(), (135, 240));
+    display.pixel(0, 0, RED).unwrap();
+    assert!(display.pixel(135, 0, RED).is_err());
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&interface.transfers());
+    assert_eq!(memory.pixel(52, 40), RED);
+
+    let reference = RecordingInterface::new();
+    reference
+        .display()
+        .init_with(
+            InitOptions::new().size(135, 240).offset(52, 40),
+            &mut reference.delay(),
+        )
+        .unwrap();
+    let profile = RecordingInterface::new();
+    profile
+        .display()
+        .init_with_profile(&presets::TTGO_T_DISPLAY, &mut profile.delay())
+        .unwrap();
+    assert_eq!(profile.transfers(), reference.transfers());
+}
+
+#[test]
+fn long_operations_report_progress_after_every_transfer() {
+    let (interface, mut display) = initialized();
+
+    let mut calls = Vec::new();
+    display
+        .fill_with_progress(0, 0, 9, 9, RED, |written, total| {
+            calls.push((written, total));
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+    assert_eq!(calls, vec![(64, 100), (100, 100)]);
+
+    let mut calls = 0;
+    display
+        .clear_with_progress(BLUE, |_, _| {
+            calls += 1;
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+    assert_eq!(calls, (240 * 240 + 63) / 64);
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&interface.transfers());
+    assert_eq!(memory.pixel(0, 0), BLUE);
+    assert_eq!(memory.pixel(239, 239), BLUE);
+}
+
+#[test]
+fn progress_callback_aborts_the_memory_write() {
+    let (interface, mut display) = initialized();
+
+    assert_eq!(
+        display
+            .set_pixels_with_progress(0, 0, 9, 9, std::iter::repeat(RED), |_, _| {
+                ControlFlow::Break(())
+            })
+            .err(),
+        Some(Error::Aborted)
+    );
+    display.pixel(0, 9, GREEN).unwrap();
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&interface.transfers());
+    assert_eq!(memory.area(0, 0, 10, 7)[..64], [RED; 64]);
+    assert_eq!(memory.pixel(4, 6), 0);
+    assert_eq!(memory.pixel(0, 9), GREEN);
+}
+
+#[test]
+fn viewport_origin_wraps_around_the_scroll_area() {
+    let (interface, mut display) = initialized();
+
+    display.set_viewport_origin(80).unwrap();
+    assert_eq!(display.viewport_origin(), 80);
+    let transfers = interface.take_transfers();
+    assert_eq!(
+        transfers[transfers.len() - 4..],
+        [
+            Transfer::Command(0x33),
+            Transfer::Data(vec![0x00, 0x00, 0x01, 0x40, 0x00, 0x00]),
+            Transfer::Command(0x37),
+            Transfer::Data(vec![0x00, 0x50]),
+        ]
+    );
+
+    // the panel starts at row 40, so row 0 is shown after wrapping around
+    let interface = RecordingInterface::new();
+    let mut display = interface.display();
+    display
+        .init_with_profile(&presets::TTGO_T_DISPLAY, &mut interface.delay())
+        .unwrap();
+    display.vertical_scroll_area(20, 280, 20).unwrap();
+    display.set_viewport_origin(20).unwrap();
+    assert_eq!(display.viewport_origin(), 20);
+    assert_eq!(display.scroll_offset(), 260);
+    assert_eq!(
+        display.set_viewport_origin(300).err(),
+        Some(Error::InvalidParameter)
+    );
+}
+
+#[test]
+fn draw_to_ram_addresses_the_whole_frame_memory() {
+    let (interface, mut display) = initialized();
+
+    display
+        .draw_to_ram(|display| display.pixel(5, 300, RED).map(|_| ()))
+        .unwrap();
+    assert_eq!(display.size(), (240, 240));
+    assert!(display.pixel(5, 300, RED).is_err());
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&interface.transfers());
+    assert_eq!(memory.pixel(5, 300), RED);
+}
+
+#[test]
+fn exchanged_page_and_column_order_transposes_the_panel() {
+    let color = |c: u16, r: u16| r * 20 + c + 1;
+    let draw = |exchange: bool| {
+        let interface = RecordingInterface::new();
+        let mut display = interface.display();
+        display
+            .init_with_profile(&presets::TTGO_T_DISPLAY, &mut interface.delay())
+            .unwrap();
+        if exchange {
+            display
+                .memory_access_control(
+                    MemAccCtrlConfig::default().page_column_order(PageColumnOrder::ReverseMode),
+                )
+                .unwrap();
+            assert_eq!(display.size(), (240, 135));
+        }
+        let colors = (0..10).flat_map(|r| (0..20).map(move |c| color(c, r)));
+        display.set_pixels(5, 100, 24, 109, colors).unwrap();
+
+        let mut memory = FrameMemory::new();
+        memory.replay(&interface.transfers());
+        memory
+    };
+
+    let normal = draw(false);
+    let exchanged = draw(true);
+    for r in 0..10 {
+        for c in 0..20 {
+            assert_eq!(normal.pixel(52 + 5 + c, 40 + 100 + r), color(c, r));
+            assert_eq!(exchanged.pixel(52 + 100 + r, 40 + 5 + c), color(c, r));
+        }
+    }
+}
+
+#[test]
+fn init_with_exchanged_page_and_column_order_on_a_non_square_panel() {
+    let interface = RecordingInterface::new();
+    let mut display = interface.display();
+    display
+        .init_with(
+            InitOptions::new()
+                .size(135, 240)
+                .offset(52, 40)
+                .memory_access_control(
+                    MemAccCtrlConfig::new().page_column_order(PageColumnOrder::ReverseMode),
+                ),
+            &mut interface.delay(),
+        )
+        .unwrap();
+    assert_eq!(display.size(), (240, 135));
+
+    // the initial window covers the exchanged panel
+    let transfers = interface.transfers();
+    let caset = transfers
+        .iter()
+        .position(|t| *t == Transfer::Command(0x2A))
+        .unwrap();
+    assert_eq!(
+        transfers[caset + 1],
+        Transfer::Data(vec![0, 40, 0x01, 0x17])
+    );
+    assert_eq!(transfers[caset + 2], Transfer::Command(0x2B));
+    assert_eq!(transfers[caset + 3], Transfer::Data(vec![0, 52, 0, 186]));
+
+    display.pixel(239, 134, RED).unwrap();
+    let mut memory = FrameMemory::new();
+    memory.replay(&interface.transfers());
+    assert_eq!(memory.pixel(52 + 134, 40 + 239), RED);
+}
+
+#[test]
+fn write_gray8_expands_luma_while_streaming() {
+    let (interface, mut display) = initialized();
+
+    let frame: Vec<u8> = (0..12).map(|i| i * 20).collect();
+    display.write_gray8(10, 20, 4, 3, &frame).unwrap();
+    let mut lut = [0; 256];
+    lut[255] = RED;
+    display
+        .write_gray8_lut(0, 0, 1, 2, &[255, 0], &lut)
+        .unwrap();
+    assert_eq!(
+        display.write_gray8(0, 0, 4, 3, &frame[..11]).err(),
+        Some(Error::PixelCountMismatch {
+            expected: 12,
+            written: 11
+        })
+    );
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&interface.transfers());
+    let expected: Vec<u16> = frame
+        .iter()
+        .map(|&luma| st7789v::color::gray(luma))
+        .collect();
+    assert_eq!(memory.area(10, 20, 4, 3), expected);
+    assert_eq!(memory.area(0, 0, 1, 2), vec![RED, 0]);
+}
+
+/// Error of a pin on an I2C port expander
+#[derive(Debug, PartialEq, Eq)]
+struct I2cError;
+
+/// Output pin on an I2C port expander which fails while `fail` is set
+struct ExpanderPin(Rc<Cell<bool>>);
+
+impl OutputPin for ExpanderPin {
+    type Error = I2cError;
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        Some(()).filter(|_| !self.0.get()).ok_or(I2cError)
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.set_low()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct ResetFault;
+
+/// Reset pin which always fails
+struct BrokenResetPin;
+
+impl OutputPin for BrokenResetPin {
+    type Error = ResetFault;
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        Err(ResetFault)
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        Err(ResetFault)
+    }
+}
+
+#[test]
+fn pins_have_their_own_error_types() {
+    let interface = RecordingInterface::new();
+    let fail = Rc::new(Cell::new(false));
+    let mut display = ST7789V::with_cs(
+        interface.spi(),
+        ExpanderPin(fail.clone()),
+        interface.dc(),
+        BrokenResetPin,
+    )
+    .unwrap();
+
+    display.pixel(1, 2, RED).unwrap();
+    assert_eq!(
+        display.hard_reset(&mut interface.delay()).err(),
+        Some(Error::Rst(ResetFault))
+    );
+    fail.set(true);
+    assert_eq!(display.release().err(), Some(Error::Cs(I2cError)));
+}
+
+#[test]
+fn failing_pins_are_reported_by_the_pin() {
+    let interface = RecordingInterface::new();
+    let (cs, dc, rst) = (
+        Rc::new(Cell::new(false)),
+        Rc::new(Cell::new(false)),
+        Rc::new(Cell::new(false)),
+    );
+    let mut display = ST7789V::with_cs(
+        interface.spi(),
+        ExpanderPin(cs.clone()),
+        ExpanderPin(dc.clone()),
+        ExpanderPin(rst.clone()),
+    )
+    .unwrap();
+
+    rst.set(true);
+    assert_eq!(
+        display.hard_reset(&mut interface.delay()).err(),
+        Some(Error::Rst(I2cError))
+    );
+    rst.set(false);
+    display.init(&mut interface.delay()).unwrap();
+
+    // the data/command pin is low after the last command, so only the data fails
+    dc.set(true);
+    assert_eq!(display.pixel(1, 2, RED).err(), Some(Error::Dc(I2cError)));
+    assert_eq!(display.display_on().err(), Some(Error::Dc(I2cError)));
+    dc.set(false);
+    display.display_on().unwrap();
+
+    cs.set(true);
+    assert_eq!(display.release().err(), Some(Error::Cs(I2cError)));
+}
+
+#[test]
+fn failing_chip_select_is_reported_when_selecting() {
+    let interface = RecordingInterface::new();
+    let fail = Rc::new(Cell::new(true));
+    let pin = || ExpanderPin(fail.clone());
+    assert_eq!(
+        ST7789V::with_cs(interface.spi(), pin(), interface.dc(), NoPin::new()).err(),
+        Some(Error::Cs(I2cError))
+    );
+
+    fail.set(false);
+    let mut display =
+        ST7789V::with_cs_per_transaction(interface.spi(), pin(), interface.dc(), NoPin::new())
+            .unwrap();
+    display.init(&mut interface.delay()).unwrap();
+    fail.set(true);
+    assert_eq!(display.display_on().err(), Some(Error::Cs(I2cError)));
+}
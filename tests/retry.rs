@@ -0,0 +1,137 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use embedded_hal::blocking::spi;
+
+use st7789v::recording::{FrameMemory, NoDelay, RecordingDc, RecordingInterface, RecordingSpi};
+use st7789v::{ColorFormat, Error, InitOptions, NoPin, RetryPolicy, ST7789V};
+
+/// SPI bus failing the `fail_at`th write after sending the first half of it
+struct FlakySpi {
+    spi: RecordingSpi,
+    fail_at: Rc<Cell<usize>>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Overrun;
+
+impl spi::Write<u8> for FlakySpi {
+    type Error = Overrun;
+
+    fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+        let fail_at = self.fail_at.get();
+        self.fail_at.set(fail_at.saturating_sub(1));
+        if fail_at == 1 {
+            self.spi.write(&words[..words.len() / 2]).ok();
+            return Err(Overrun);
+        }
+
+        self.spi.write(words).map_err(|_| Overrun)
+    }
+}
+
+type FlakyDisplay<const BUF: usize> = ST7789V<FlakySpi, NoPin, RecordingDc, NoPin, (), (), BUF>;
+
+fn flaky<const BUF: usize>(
+    color_format: ColorFormat,
+) -> (RecordingInterface, Rc<Cell<usize>>, FlakyDisplay<BUF>) {
+    let interface = RecordingInterface::new();
+    let fail_at = Rc::new(Cell::new(0));
+    let spi = FlakySpi {
+        spi: interface.spi(),
+        fail_at: fail_at.clone(),
+    };
+    let mut display = ST7789V::new(spi, interface.dc(), NoPin::new()).with_chunk_size::<BUF>();
+    display
+        .init_with(InitOptions::new().color_format(color_format), &mut NoDelay)
+        .unwrap();
+
+    (interface, fail_at, display)
+}
+
+fn colors() -> impl Iterator<Item = u16> {
+    (0..40).map(|i| 0x0421 * i + 1)
+}
+
+#[test]
+fn resumes_a_failed_chunk_in_the_middle_of_a_row() {
+    let (interface, fail_at, mut display) = flaky::<8>(ColorFormat::RGB65K_CI16Bit);
+    display.set_retry(RetryPolicy::new(1));
+
+    // CASET, its parameters, RASET, its parameters, RAMWR and three chunks of four pixels
+    fail_at.set(8);
+    display.set_pixels(3, 5, 12, 8, colors()).unwrap();
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&interface.transfers());
+    assert_eq!(memory.area(3, 5, 10, 4), colors().collect::<Vec<_>>());
+    assert_eq!(memory.pixel(2, 5), 0);
+    assert_eq!(memory.pixel(13, 6), 0);
+}
+
+#[test]
+fn resumes_18_bit_pixel_writes() {
+    let (interface, fail_at, mut display) = flaky::<9>(ColorFormat::RGB262K_CI18Bit);
+    display.set_retry(RetryPolicy::new(1));
+
+    // three pixels per chunk, the third chunk in the middle of the second row fails
+    fail_at.set(8);
+    display
+        .pixels18(0, 0, 4, 1, &mut core::iter::repeat(0x3FFFF).take(10))
+        .unwrap();
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&interface.transfers());
+    assert_eq!(memory.area(0, 0, 5, 2), vec![0xFFFF; 10]);
+}
+
+#[test]
+fn sends_the_command_again_for_failed_parameters() {
+    let (interface, fail_at, mut display) = flaky::<128>(ColorFormat::RGB65K_CI16Bit);
+    display.set_retry(RetryPolicy::new(1));
+
+    fail_at.set(2);
+    display.pixel(7, 9, 0xF800).unwrap();
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&interface.transfers());
+    assert_eq!(memory.pixel(7, 9), 0xF800);
+}
+
+#[test]
+fn returns_the_error_without_retry_policy() {
+    let (_, fail_at, mut display) = flaky::<8>(ColorFormat::RGB65K_CI16Bit);
+
+    fail_at.set(8);
+    assert_eq!(
+        display.set_pixels(3, 5, 12, 8, colors()).err(),
+        Some(Error::Spi(Overrun))
+    );
+
+    fail_at.set(8);
+    display.set_retry(RetryPolicy {
+        resume_memory_writes: false,
+        ..RetryPolicy::new(1)
+    });
+    assert_eq!(
+        display.set_pixels(3, 5, 12, 8, colors()).err(),
+        Some(Error::Spi(Overrun))
+    );
+}
+
+#[test]
+fn into_parts_recovers_the_hardware_after_an_error() {
+    let (interface, fail_at, mut display) = flaky::<8>(ColorFormat::RGB65K_CI16Bit);
+
+    fail_at.set(8);
+    assert!(display.set_pixels(3, 5, 12, 8, colors()).is_err());
+    let bus_calls = interface.bus_calls();
+    let (spi, cs, _dc, _rst, ()) = display.into_parts();
+    assert_eq!(interface.bus_calls(), bus_calls);
+    assert!(cs.is_none());
+
+    // the recovered bus works
+    let mut display = ST7789V::new(spi, interface.dc(), NoPin::new());
+    display.init(&mut NoDelay).unwrap();
+    assert!(interface.bus_calls() > bus_calls);
+}
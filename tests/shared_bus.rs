@@ -0,0 +1,203 @@
+use std::cell::RefCell;
+use std::convert::Infallible;
+use std::rc::Rc;
+
+use embedded_hal::blocking::spi;
+use embedded_hal::digital::v2::OutputPin;
+use st7789v::recording::{FrameMemory, NoDelay, Transfer};
+use st7789v::{NoPin, SharedBus, ST7789V};
+
+/// Bus traffic: the selected displays, the data/command level and the bytes of every write
+#[derive(Default)]
+struct Log {
+    selected: Vec<usize>,
+    dc_high: bool,
+    writes: Vec<(Vec<usize>, bool, Vec<u8>)>,
+    /// Number of chip select pin activations before each write
+    selections: Vec<usize>,
+    activations: usize,
+}
+
+struct LogSpi(Rc<RefCell<Log>>);
+
+impl spi::Write<u8> for LogSpi {
+    type Error = Infallible;
+
+    fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+        let mut log = self.0.borrow_mut();
+        let write = (log.selected.clone(), log.dc_high, words.to_vec());
+        log.writes.push(write);
+        let activations = log.activations;
+        log.selections.push(activations);
+        Ok(())
+    }
+}
+
+struct LogDc(Rc<RefCell<Log>>);
+
+impl OutputPin for LogDc {
+    type Error = Infallible;
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.0.borrow_mut().dc_high = false;
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.0.borrow_mut().dc_high = true;
+        Ok(())
+    }
+}
+
+struct LogCs(Rc<RefCell<Log>>, usize);
+
+impl OutputPin for LogCs {
+    type Error = Infallible;
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        let mut log = self.0.borrow_mut();
+        log.selected.push(self.1);
+        log.activations += 1;
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.0.borrow_mut().selected.retain(|&cs| cs != self.1);
+        Ok(())
+    }
+}
+
+/// Returns the transfers received by display `cs`.
+fn received(log: &Log, cs: usize) -> Vec<Transfer> {
+    let mut transfers = Vec::new();
+    for (selected, dc_high, words) in &log.writes {
+        if selected != &[cs] {
+            continue;
+        }
+        if *dc_high {
+            transfers.push(Transfer::Data(words.clone()));
+        } else {
+            transfers.extend(words.iter().map(|&cmd| Transfer::Command(cmd)));
+        }
+    }
+    transfers
+}
+
+#[test]
+fn displays_on_a_shared_bus_keep_their_own_state() {
+    let log = Rc::new(RefCell::new(Log::default()));
+    let bus = SharedBus::new(LogSpi(log.clone()), LogDc(log.clone()));
+    let mut left = ST7789V::with_shared_bus(&bus, LogCs(log.clone(), 0), NoPin::new()).unwrap();
+    let mut right = ST7789V::with_shared_bus(&bus, LogCs(log.clone(), 1), NoPin::new()).unwrap();
+
+    left.init(&mut NoDelay).unwrap();
+    right.init(&mut NoDelay).unwrap();
+
+    left.pixel(1, 2, 0xF800).unwrap();
+    right.pixel(3, 4, 0x07E0).unwrap();
+    // the data/command pin was left high by the right display
+    left.display_on().unwrap();
+    right.pixel(5, 6, 0x001F).unwrap();
+    left.display_off().unwrap();
+
+    let log = log.borrow();
+    assert!(log
+        .writes
+        .iter()
+        .all(|(selected, _, _)| selected.len() == 1));
+    assert!(log.selected.is_empty());
+
+    let left_transfers = received(&log, 0);
+    assert_eq!(
+        left_transfers[left_transfers.len() - 2..],
+        [Transfer::Command(0x29), Transfer::Command(0x28)]
+    );
+    let mut memory = FrameMemory::new();
+    memory.replay(&left_transfers);
+    assert_eq!(memory.area(1, 2, 1, 1), vec![0xF800]);
+    assert_eq!(memory.area(3, 4, 1, 1), vec![0]);
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&received(&log, 1));
+    assert_eq!(memory.area(3, 4, 1, 1), vec![0x07E0]);
+    assert_eq!(memory.area(5, 6, 1, 1), vec![0x001F]);
+    assert_eq!(memory.area(1, 2, 1, 1), vec![0]);
+}
+
+#[test]
+fn chip_select_is_only_low_during_a_transaction() {
+    let log = Rc::new(RefCell::new(Log::default()));
+    let mut display = ST7789V::with_cs_per_transaction(
+        LogSpi(log.clone()),
+        LogCs(log.clone(), 0),
+        LogDc(log.clone()),
+        NoPin::new(),
+    )
+    .unwrap();
+    assert!(log.borrow().selected.is_empty());
+
+    display.init(&mut NoDelay).unwrap();
+    display.display_on().unwrap();
+
+    // another device on the bus, which leaves the data/command pin high
+    let mut other_spi = LogSpi(log.clone());
+    let mut other_cs = LogCs(log.clone(), 1);
+    other_cs.set_low().unwrap();
+    spi::Write::write(&mut other_spi, &[0xAA, 0x55]).unwrap();
+    other_cs.set_high().unwrap();
+    log.borrow_mut().dc_high = true;
+
+    // 200 bytes of pixel data, written in two chunks
+    let start = log.borrow().writes.len();
+    display
+        .set_pixels(0, 0, 99, 0, core::iter::repeat(0xF800).take(100))
+        .unwrap();
+
+    let log = log.borrow();
+    assert!(log.selected.is_empty());
+    assert!(log
+        .writes
+        .iter()
+        .all(|(selected, _, _)| selected.len() == 1));
+
+    // CASET and RASET with their parameters, then RAMWR with both chunks
+    let writes = &log.writes[start..];
+    let selections = &log.selections[start..];
+    assert_eq!(writes[0], (vec![0], false, vec![0x2A]));
+    assert_eq!(writes[4], (vec![0], false, vec![0x2C]));
+    assert_eq!(writes.len(), 7);
+    assert_eq!(selections[0], selections[1]);
+    assert!(selections[2] > selections[1]);
+    assert!(selections[4] > selections[3]);
+    assert_eq!(selections[4..], [selections[4]; 3]);
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&received(&log, 0));
+    assert_eq!(memory.area(0, 0, 101, 1)[99..], [0xF800, 0]);
+}
+
+#[test]
+fn swap_spi_raises_chip_select_while_the_bus_is_lent() {
+    let log = Rc::new(RefCell::new(Log::default()));
+    let mut display = ST7789V::with_cs(
+        LogSpi(log.clone()),
+        LogCs(log.clone(), 0),
+        LogDc(log.clone()),
+        NoPin::new(),
+    )
+    .unwrap();
+    display.init(&mut NoDelay).unwrap();
+
+    let mut display = display
+        .swap_spi(|spi| {
+            assert!(log.borrow().selected.is_empty());
+            spi
+        })
+        .unwrap();
+    assert_eq!(log.borrow().selected, [0]);
+
+    display.pixel(3, 4, 0xF800).unwrap();
+    let mut memory = FrameMemory::new();
+    memory.replay(&received(&log.borrow(), 0));
+    assert_eq!(memory.pixel(3, 4), 0xF800);
+}
@@ -0,0 +1,130 @@
+use embedded_graphics::pixelcolor::Rgb565;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::Rectangle;
+use embedded_graphics::style::PrimitiveStyle;
+
+use st7789v::recording::{FrameMemory, RecordingInterface};
+use st7789v::simulator::Simulator;
+use st7789v::{ColumnAddressOrder, FrameBuffer, InitOptions, MemAccCtrlConfig};
+
+const RED: u16 = 0xF800;
+const BLUE: u16 = 0x001F;
+
+fn draw<SPI, DC>(display: &mut st7789v::ST7789V<SPI, st7789v::NoPin, DC, st7789v::NoPin>)
+where
+    SPI: embedded_hal::blocking::spi::Write<u8>,
+    SPI::Error: core::fmt::Debug,
+    DC: embedded_hal::digital::v2::OutputPin<Error = core::convert::Infallible>,
+{
+    display.clear(BLUE).unwrap();
+    display
+        .set_pixels(2, 3, 5, 4, core::iter::repeat(RED).take(8))
+        .unwrap();
+    display
+        .memory_access_control(
+            MemAccCtrlConfig::default().column_order(ColumnAddressOrder::RightToLeft),
+        )
+        .unwrap();
+    display.pixel(0, 0, RED).unwrap();
+    // clipped by the panel size
+    assert!(display.pixel(135, 0, RED).is_err());
+}
+
+#[test]
+fn matches_the_replayed_spi_stream() {
+    let options = InitOptions::new().size(135, 240).offset(52, 40);
+
+    let simulator = Simulator::new();
+    let mut display = simulator.display();
+    display.init_with(options, &mut simulator.delay()).unwrap();
+    draw(&mut display);
+
+    let interface = RecordingInterface::new();
+    let mut recorded = interface.display();
+    recorded.init_with(options, &mut interface.delay()).unwrap();
+    draw(&mut recorded);
+
+    let mut memory = FrameMemory::new();
+    memory.replay(&interface.transfers());
+    assert_eq!(simulator.area(0, 0, 240, 320), memory.pixels());
+    assert_eq!(simulator.pixel(54, 43), RED);
+    assert_eq!(simulator.pixel(239 - 52, 40), RED);
+    assert_eq!(simulator.pixel(52, 40), BLUE);
+    assert_eq!(simulator.pixel(51, 40), 0);
+}
+
+#[test]
+fn exports_png() {
+    let simulator = Simulator::new();
+    let mut display = simulator.display();
+    display.init(&mut simulator.delay()).unwrap();
+    display.pixel(1, 0, RED).unwrap();
+
+    let mut png = Vec::new();
+    simulator.write_png(0, 0, 2, 2, &mut png).unwrap();
+
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+    assert_eq!(&png[8..16], b"\0\0\0\x0dIHDR");
+    assert_eq!(&png[16..29], &[0, 0, 0, 2, 0, 0, 0, 2, 8, 2, 0, 0, 0]);
+    assert_eq!(&png[33..41], b"\0\0\0\x19IDAT");
+    // zlib header, a single final stored block of 14 bytes
+    assert_eq!(&png[41..48], &[0x78, 0x01, 1, 14, 0, !14, 0xFF]);
+    assert_eq!(&png[48..62], &[0, 0, 0, 0, 255, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(&png[png.len() - 12..], b"\0\0\0\0IEND\xae\x42\x60\x82");
+}
+
+#[test]
+fn draws_into_an_embedded_graphics_target() {
+    let simulator = Simulator::new();
+    let mut display = simulator.display();
+    display.init(&mut simulator.delay()).unwrap();
+    Rectangle::new(Point::new(10, 10), Point::new(11, 11))
+        .into_styled(PrimitiveStyle::with_fill(Rgb565::RED))
+        .draw(&mut display)
+        .unwrap();
+
+    let mut buffer = [0; 4];
+    let mut target = FrameBuffer::new(&mut buffer, 2, 2);
+    simulator.draw_into(9, 10, 2, 2, &mut target).unwrap();
+
+    assert_eq!(buffer, [0, RED, 0, RED]);
+}
+
+#[test]
+fn png_export_rejects_empty_and_outside_areas() {
+    let simulator = Simulator::with_size(4, 4);
+
+    for &(x, y, width, height) in &[(0, 0, 0, 2), (0, 0, 2, 0), (3, 0, 2, 1), (0, 1, 1, 0xFFFF)] {
+        let mut png = Vec::new();
+        let error = simulator
+            .write_png(x, y, width, height, &mut png)
+            .unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(png.is_empty());
+    }
+    simulator.write_png(2, 2, 2, 2, std::io::sink()).unwrap();
+}
+
+#[test]
+fn draws_nothing_for_an_empty_area() {
+    let simulator = Simulator::new();
+    let mut display = simulator.display();
+    display.init(&mut simulator.delay()).unwrap();
+    display.clear(RED).unwrap();
+
+    let mut buffer = [BLUE; 4];
+    let mut target = FrameBuffer::new(&mut buffer, 2, 2);
+    simulator.draw_into(0, 0, 0, 2, &mut target).unwrap();
+    simulator.draw_into(0, 0, 2, 0, &mut target).unwrap();
+
+    assert_eq!(buffer, [BLUE; 4]);
+}
+
+#[test]
+#[should_panic(expected = "area outside the frame memory")]
+fn drawing_an_area_outside_the_frame_memory_panics() {
+    let simulator = Simulator::new();
+    let mut buffer = [0; 4];
+    let mut target = FrameBuffer::new(&mut buffer, 2, 2);
+    simulator.draw_into(239, 0, 2, 2, &mut target).unwrap();
+}